@@ -34,16 +34,19 @@ use msg::constellation_msg::{PipelineIndex, PipelineNamespaceId, WindowSizeType}
 use profile_traits::mem::{self, ReportKind, Reporter, ReporterRequest};
 use profile_traits::time::{self, ProfilerCategory, profile};
 use script_traits::CompositorEvent::{MouseMoveEvent, MouseButtonEvent, TouchEvent};
-use script_traits::{AnimationState, AnimationTickType, ConstellationControlMsg};
+use script_traits::{AnimatedValue, AnimationId, AnimationState, AnimationTickType, AnimationTiming};
+use script_traits::{CapturedLayer, CompositorAnimation, CompositorKeyframe, ConstellationControlMsg, CursorId};
+use script_traits::{ScrollAxis, ScrollTimeline, SelectionInfo};
 use script_traits::{ConstellationMsg, LayoutControlMsg, MouseButton, MouseEventType};
 use script_traits::{StackingContextScrollState, TouchpadPressurePhase, TouchEventType};
-use script_traits::{TouchId, WindowSizeData};
+use script_traits::{TouchId, ViewTransitionSnapshot, WindowSizeData};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::mem as std_mem;
 use std::rc::Rc;
 use std::sync::mpsc::Sender;
+use style_traits::cursor::Cursor;
 use style_traits::viewport::ViewportConstraints;
 use style_traits::{PagePx, ViewportPx};
 use surface_map::SurfaceMap;
@@ -187,6 +190,9 @@ pub struct IOCompositor<Window: WindowMethods> {
     /// the compositor.
     shutdown_state: ShutdownState,
 
+    /// Senders to acknowledge on once shutdown has completed.
+    shutdown_acknowledgements: Vec<IpcSender<()>>,
+
     /// Tracks the last composite time.
     last_composite_time: u64,
 
@@ -299,6 +305,15 @@ struct PipelineDetails {
 
     /// Whether this pipeline is visible
     visible: bool,
+
+    /// The animations that layout has handed off to the compositor.
+    compositor_animations: Vec<CompositorAnimation>,
+
+    /// The layers and scroll timelines driving compositor animations, keyed by animation.
+    scroll_timelines: HashMap<AnimationId, (LayerId, ScrollTimeline)>,
+
+    /// The named cursors currently shown over this pipeline, with their positions in page pixels.
+    named_cursors: HashMap<CursorId, (Cursor, Point2D<f32>)>,
 }
 
 impl PipelineDetails {
@@ -309,6 +324,9 @@ impl PipelineDetails {
             animations_running: false,
             animation_callbacks_running: false,
             visible: true,
+            compositor_animations: Vec::new(),
+            scroll_timelines: HashMap::new(),
+            named_cursors: HashMap::new(),
         }
     }
 }
@@ -482,6 +500,7 @@ impl<Window: WindowMethods> IOCompositor<Window> {
             waiting_for_results_of_scroll: false,
             composite_target: composite_target,
             shutdown_state: ShutdownState::NotShuttingDown,
+            shutdown_acknowledgements: vec![],
             page_zoom: ScaleFactor::new(1.0),
             viewport_zoom: ScaleFactor::new(1.0),
             min_viewport_zoom: None,
@@ -560,6 +579,12 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         self.delayed_composition_timer.shutdown();
 
         self.shutdown_state = ShutdownState::FinishedShuttingDown;
+
+        for sender in self.shutdown_acknowledgements.drain(..) {
+            if let Err(e) = sender.send(()) {
+                warn!("Sending shutdown acknowledgement failed ({}).", e);
+            }
+        }
     }
 
     fn handle_browser_message(&mut self, msg: Msg) -> bool {
@@ -569,7 +594,8 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 return false
             }
 
-            (Msg::Exit, _) => {
+            (Msg::Exit(sender), _) => {
+                self.shutdown_acknowledgements.extend(sender);
                 self.start_shutting_down();
             }
 
@@ -583,6 +609,25 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 self.change_running_animations_state(pipeline_id, animation_state);
             }
 
+            (Msg::SetCompositorAnimations(pipeline_id, animations),
+             ShutdownState::NotShuttingDown) => {
+                self.set_compositor_animations(pipeline_id, animations);
+            }
+
+            (Msg::SetScrollTimeline(pipeline_id, layer_id, timeline),
+             ShutdownState::NotShuttingDown) => {
+                self.pipeline_details(pipeline_id).scroll_timelines.insert(timeline.target_animation,
+                                                                           (layer_id, timeline));
+                self.composite_if_necessary(CompositingReason::Animation);
+            }
+
+            (Msg::CaptureViewTransition(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let snapshot = self.capture_view_transition(pipeline_id);
+                if let Err(e) = sender.send(snapshot) {
+                    warn!("Sending view transition snapshot failed ({}).", e);
+                }
+            }
+
             (Msg::ChangePageTitle(pipeline_id, title), ShutdownState::NotShuttingDown) => {
                 self.change_page_title(pipeline_id, title);
             }
@@ -648,6 +693,11 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 self.scroll_fragment_to_point(pipeline_id, layer_id, point);
             }
 
+            (Msg::ScrollAnchorAdjustment(pipeline_id, layer_id, delta),
+             ShutdownState::NotShuttingDown) => {
+                self.adjust_scroll_anchor(pipeline_id, layer_id, delta);
+            }
+
             (Msg::MoveTo(point),
              ShutdownState::NotShuttingDown) => {
                 self.window.set_position(point);
@@ -670,6 +720,14 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 self.window.status(message);
             }
 
+            (Msg::LinkHover(url), ShutdownState::NotShuttingDown) => {
+                self.window.link_hover(url);
+            }
+
+            (Msg::SetTooltip(tooltip), ShutdownState::NotShuttingDown) => {
+                self.window.set_tooltip(tooltip);
+            }
+
             (Msg::LoadStart(back, forward), ShutdownState::NotShuttingDown) => {
                 self.window.load_start(back, forward);
             }
@@ -716,6 +774,10 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 self.window.set_cursor(cursor)
             }
 
+            (Msg::SetNamedCursor(pipeline_id, cursor_id, cursor, point), ShutdownState::NotShuttingDown) => {
+                self.set_named_cursor(pipeline_id, cursor_id, cursor, point);
+            }
+
             (Msg::CreatePng(reply), ShutdownState::NotShuttingDown) => {
                 let res = self.composite_specific_target(CompositeTarget::WindowAndPng);
                 let img = res.unwrap_or(None);
@@ -754,6 +816,10 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 self.window.set_favicon(url);
             }
 
+            (Msg::SetThemeColor(color), ShutdownState::NotShuttingDown) => {
+                self.window.set_theme_color(color);
+            }
+
             (Msg::HeadParsed, ShutdownState::NotShuttingDown) => {
                 self.window.head_parsed();
             }
@@ -808,6 +874,220 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                 }
             }
 
+            (Msg::ChangePageEncoding(pipeline_id, encoding), ShutdownState::NotShuttingDown) => {
+                self.change_page_encoding(pipeline_id, encoding);
+            }
+
+            (Msg::ChangePageContentType(pipeline_id, content_type), ShutdownState::NotShuttingDown) => {
+                self.change_page_content_type(pipeline_id, content_type);
+            }
+
+            (Msg::ChangeSelection(pipeline_id, selection), ShutdownState::NotShuttingDown) => {
+                self.change_selection(pipeline_id, selection);
+            }
+
+            (Msg::RequestPictureInPicture(pipeline_id, media_id, sender), ShutdownState::NotShuttingDown) => {
+                let result = self.window.request_picture_in_picture(pipeline_id, media_id);
+                if let Err(e) = sender.send(result) {
+                    warn!("Sending picture-in-picture response failed ({}).", e);
+                }
+            }
+
+            (Msg::ExitPictureInPicture(pipeline_id, media_id), ShutdownState::NotShuttingDown) => {
+                self.window.exit_picture_in_picture(pipeline_id, media_id);
+            }
+
+            (Msg::SetFullscreenState(options), ShutdownState::NotShuttingDown) => {
+                self.window.set_fullscreen_state(options);
+            }
+
+            (Msg::CheckAutoplayAllowed(pipeline_id, context, sender), ShutdownState::NotShuttingDown) => {
+                let allowed = self.window.allow_autoplay(pipeline_id, context);
+                if let Err(e) = sender.send(allowed) {
+                    warn!("Sending autoplay decision failed ({}).", e);
+                }
+            }
+
+            (Msg::RequestStorageAccess(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let granted = self.window.allow_storage_access(pipeline_id);
+                if let Err(e) = sender.send(granted) {
+                    warn!("Sending storage access decision failed ({}).", e);
+                }
+            }
+
+            (Msg::SetAppBadge(badge), ShutdownState::NotShuttingDown) => {
+                self.window.set_app_badge(badge);
+            }
+
+            (Msg::Download(url), ShutdownState::NotShuttingDown) => {
+                self.window.download(url);
+            }
+
+            (Msg::GetKeyboardLayoutMap(sender), ShutdownState::NotShuttingDown) => {
+                let layout_map = self.window.keyboard_layout_map();
+                if let Err(e) = sender.send(layout_map) {
+                    warn!("Sending keyboard layout map failed ({}).", e);
+                }
+            }
+
+            (Msg::LockKeyboard(pipeline_id, codes, sender), ShutdownState::NotShuttingDown) => {
+                let result = self.window.lock_keyboard(pipeline_id, codes);
+                if let Err(e) = sender.send(result) {
+                    warn!("Sending keyboard lock response failed ({}).", e);
+                }
+            }
+
+            (Msg::UnlockKeyboard(pipeline_id), ShutdownState::NotShuttingDown) => {
+                self.window.unlock_keyboard(pipeline_id);
+            }
+
+            (Msg::SetImeState(pipeline_id, state), ShutdownState::NotShuttingDown) => {
+                self.window.set_ime_state(pipeline_id, state);
+            }
+
+            (Msg::ShowVirtualKeyboard(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let geometry = self.window.show_virtual_keyboard(pipeline_id);
+                if let Err(e) = sender.send(geometry) {
+                    warn!("Sending virtual keyboard geometry failed ({}).", e);
+                }
+            }
+
+            (Msg::HideVirtualKeyboard(pipeline_id), ShutdownState::NotShuttingDown) => {
+                self.window.hide_virtual_keyboard(pipeline_id);
+            }
+
+            (Msg::EnsureFocusVisible(pipeline_id, element_id), ShutdownState::NotShuttingDown) => {
+                self.window.focus_visible(pipeline_id, element_id);
+            }
+
+            (Msg::StartSpeechRecognition(pipeline_id, recognition_id, config),
+             ShutdownState::NotShuttingDown) => {
+                self.window.start_speech_recognition(pipeline_id, recognition_id, config);
+            }
+
+            (Msg::StopSpeechRecognition(recognition_id), ShutdownState::NotShuttingDown) => {
+                self.window.stop_speech_recognition(recognition_id);
+            }
+
+            (Msg::RequestUserMedia(pipeline_id, constraints, sender), ShutdownState::NotShuttingDown) => {
+                let result = self.window.request_user_media(pipeline_id, constraints);
+                if let Err(e) = sender.send(result) {
+                    warn!("Sending user media response failed ({}).", e);
+                }
+            }
+
+            (Msg::EnumerateMediaDevices(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let devices = self.window.media_devices(pipeline_id);
+                if let Err(e) = sender.send(devices) {
+                    warn!("Sending media devices failed ({}).", e);
+                }
+            }
+
+            (Msg::RequestDisplayCapture(pipeline_id, constraints, sender), ShutdownState::NotShuttingDown) => {
+                let result = self.window.request_display_capture(pipeline_id, constraints);
+                if let Err(e) = sender.send(result) {
+                    warn!("Sending display capture response failed ({}).", e);
+                }
+            }
+
+            (Msg::GatherIceCandidates(pipeline_id, gathering_id, config), ShutdownState::NotShuttingDown) => {
+                self.window.gather_ice_candidates(pipeline_id, gathering_id, config);
+            }
+
+            (Msg::GetMaxTouchPoints(sender), ShutdownState::NotShuttingDown) => {
+                let max_touch_points = self.window.max_touch_points();
+                if let Err(e) = sender.send(max_touch_points) {
+                    warn!("Sending max touch points failed ({}).", e);
+                }
+            }
+
+            (Msg::GetPointerCapabilities(sender), ShutdownState::NotShuttingDown) => {
+                let capabilities = self.window.pointer_capabilities();
+                if let Err(e) = sender.send(capabilities) {
+                    warn!("Sending pointer capabilities failed ({}).", e);
+                }
+            }
+
+            (Msg::GetDisplayCapabilities(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let capabilities = self.window.display_capabilities(pipeline_id);
+                if let Err(e) = sender.send(capabilities) {
+                    warn!("Sending display capabilities failed ({}).", e);
+                }
+            }
+
+            (Msg::GetDisplayRefreshRate(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let refresh_rate = self.window.display_refresh_rate(pipeline_id);
+                if let Err(e) = sender.send(refresh_rate) {
+                    warn!("Sending display refresh rate failed ({}).", e);
+                }
+            }
+
+            (Msg::GetScreens(pipeline_id, sender), ShutdownState::NotShuttingDown) => {
+                let screens = self.window.screens(pipeline_id);
+                if let Err(e) = sender.send(screens) {
+                    warn!("Sending screens failed ({}).", e);
+                }
+            }
+
+            (Msg::PlaceWindow(placement), ShutdownState::NotShuttingDown) => {
+                self.window.place_window(placement);
+            }
+
+            (Msg::SetInitialFocus(pipeline_id, element_id), ShutdownState::NotShuttingDown) => {
+                self.window.set_initial_focus(pipeline_id, element_id);
+            }
+
+            (Msg::SetAccessKeys(pipeline_id, access_keys), ShutdownState::NotShuttingDown) => {
+                self.window.set_access_keys(pipeline_id, access_keys);
+            }
+
+            (Msg::StartDrag(pipeline_id, data), ShutdownState::NotShuttingDown) => {
+                self.window.start_drag(pipeline_id, data);
+            }
+
+            (Msg::HighlightTextFragment(pipeline_id, directive), ShutdownState::NotShuttingDown) => {
+                self.window.highlight_text_fragment(pipeline_id, directive);
+            }
+
+            (Msg::ScriptTimeout(pipeline_id, elapsed), ShutdownState::NotShuttingDown) => {
+                self.window.script_timed_out(pipeline_id, elapsed);
+            }
+
+            (Msg::SetPipelineResponsive(pipeline_id, responsive), ShutdownState::NotShuttingDown) => {
+                self.window.set_pipeline_responsive(pipeline_id, responsive);
+            }
+
+            (Msg::UpdatePerformanceOverlay(pipeline_id, summary), ShutdownState::NotShuttingDown) => {
+                self.window.update_performance_overlay(pipeline_id, summary);
+            }
+
+            (Msg::GetScrollbarPreferences(sender), ShutdownState::NotShuttingDown) => {
+                let preferences = self.window.scrollbar_preferences();
+                if let Err(e) = sender.send(preferences) {
+                    warn!("Sending scrollbar preferences failed ({}).", e);
+                }
+            }
+
+            (Msg::ShowNavigationBlocked(pipeline_id, url, reason), ShutdownState::NotShuttingDown) => {
+                self.window.show_navigation_blocked(pipeline_id, url, reason);
+            }
+
+            (Msg::HideInterstitial(pipeline_id), ShutdownState::NotShuttingDown) => {
+                self.window.hide_interstitial(pipeline_id);
+            }
+
+            (Msg::PageLifecycleChanged(pipeline_id, state), ShutdownState::NotShuttingDown) => {
+                self.window.page_lifecycle_changed(pipeline_id, state);
+            }
+
+            (Msg::AuxiliaryBrowsingContextOpened(pipeline_id, features), ShutdownState::NotShuttingDown) => {
+                self.window.auxiliary_browsing_context_opened(pipeline_id, features);
+            }
+
+            (Msg::MediaTypeChanged(pipeline_id, media_type), ShutdownState::NotShuttingDown) => {
+                self.window.media_type_changed(pipeline_id, media_type);
+            }
+
             // When we are shutting_down, we need to avoid performing operations
             // such as Paint that may crash because we have begun tearing down
             // the rest of our resources.
@@ -817,6 +1097,132 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         true
     }
 
+    /// Replaces the animations that the compositor runs for the given pipeline, and schedules a
+    /// recomposite if any of them are visible.
+    fn set_compositor_animations(&mut self,
+                                 pipeline_id: PipelineId,
+                                 animations: Vec<CompositorAnimation>) {
+        let visible = self.pipeline_details(pipeline_id).visible;
+        let running = !animations.is_empty();
+        {
+            let details = self.pipeline_details(pipeline_id);
+            let stale_timelines: Vec<AnimationId> = details.scroll_timelines.keys().filter(|id| {
+                !animations.iter().any(|animation| animation.id == **id)
+            }).cloned().collect();
+            for id in &stale_timelines {
+                details.scroll_timelines.remove(id);
+            }
+            details.compositor_animations = animations;
+        }
+        if running && visible {
+            self.composite_if_necessary(CompositingReason::Animation);
+        }
+    }
+
+    /// Samples the animations that the compositor runs at the current time, and applies their
+    /// values to the animated layers. Animations that have run all of their iterations are dropped
+    /// once their final value is applied; the layer keeps that value until layout next updates its
+    /// properties. Animations bound to a scroll timeline are sampled at the scroll offset of their
+    /// timeline's layer instead, and never finish. Returns true if any time-based animation changed
+    /// a layer, and so needs to be composited again; scroll-linked ones only change on scrolls,
+    /// which composite anyway.
+    fn sample_compositor_animations(&mut self) -> bool {
+        let now = precise_time_s();
+        let pipeline_ids: Vec<PipelineId> = self.pipeline_details.iter().filter(|&(_, details)| {
+            details.visible && !details.compositor_animations.is_empty()
+        }).map(|(pipeline_id, _)| *pipeline_id).collect();
+
+        let mut changed = false;
+        for pipeline_id in pipeline_ids {
+            let animations = std_mem::replace(&mut self.pipeline_details(pipeline_id).compositor_animations,
+                                              vec![]);
+            let mut running = Vec::with_capacity(animations.len());
+            for animation in animations {
+                let timeline = self.pipeline_details(pipeline_id).scroll_timelines.get(&animation.id).cloned();
+                let progress = match timeline {
+                    Some((layer_id, ref timeline)) => {
+                        self.scroll_timeline_progress(pipeline_id, layer_id, timeline).map(|progress| {
+                            (progress, false)
+                        })
+                    }
+                    None => compositor_animation_progress(&animation.timing, now),
+                };
+                let (progress, finished) = match progress {
+                    Some(progress) => progress,
+                    None => {
+                        // The animation's delay hasn't passed yet, or its timeline's layer is gone.
+                        running.push(animation);
+                        continue;
+                    }
+                };
+                let value = compositor_animation_value(&animation.keyframes, progress);
+                let layer = self.find_layer_with_pipeline_and_layer_id(pipeline_id, animation.layer_id);
+                if let (Some(layer), Some(value)) = (layer, value) {
+                    match value {
+                        AnimatedValue::Transform(transform) => *layer.transform.borrow_mut() = transform,
+                        AnimatedValue::Opacity(opacity) => *layer.opacity.borrow_mut() = opacity,
+                    }
+                    changed = changed || timeline.is_none();
+                }
+                if !finished {
+                    running.push(animation);
+                }
+            }
+            self.pipeline_details(pipeline_id).compositor_animations = running;
+        }
+        changed
+    }
+
+    /// Returns the progress of a scroll timeline, between 0 and 1, from the current scroll offset
+    /// of its layer, or `None` if that layer no longer exists.
+    fn scroll_timeline_progress(&self,
+                                pipeline_id: PipelineId,
+                                layer_id: LayerId,
+                                timeline: &ScrollTimeline)
+                                -> Option<f32> {
+        let layer = match self.find_layer_with_pipeline_and_layer_id(pipeline_id, layer_id) {
+            Some(layer) => layer,
+            None => return None,
+        };
+        // Scroll offsets are negative: they are how far the content moved up or left.
+        let scroll_offset = layer.extra_data.borrow().scroll_offset;
+        let position = match timeline.axis {
+            ScrollAxis::Horizontal => -scroll_offset.x.get(),
+            ScrollAxis::Vertical => -scroll_offset.y.get(),
+        };
+        let (start, end) = timeline.range;
+        if end <= start {
+            return Some(if position >= end { 1.0 } else { 0.0 })
+        }
+        Some(((position - start) / (end - start)).max(0.0).min(1.0))
+    }
+
+    fn set_named_cursor(&mut self,
+                        pipeline_id: PipelineId,
+                        cursor_id: CursorId,
+                        cursor: Cursor,
+                        point: Point2D<f32>) {
+        if cursor == Cursor::None {
+            self.pipeline_details(pipeline_id).named_cursors.remove(&cursor_id);
+        } else {
+            self.pipeline_details(pipeline_id).named_cursors.insert(cursor_id, (cursor, point));
+        }
+
+        // The position is relative to the pipeline's own viewport, so offset it by wherever that
+        // pipeline's frame currently sits in the window.
+        let origin = match self.scene.root {
+            Some(ref layer) => find_pipeline_viewport_origin_for_layer(layer, pipeline_id, Point2D::typed(0., 0.)),
+            None => None,
+        };
+        match origin {
+            Some(origin) => {
+                let position = (origin + Point2D::typed(point.x, point.y)) * self.scene.scale;
+                self.window.set_named_cursor(cursor_id, cursor, position);
+            }
+            None => debug!("Named cursor for pipeline {:?} without a layer.", pipeline_id),
+        }
+    }
+
     /// Sets or unsets the animations-running flag for the given pipeline, and schedules a
     /// recomposite if necessary.
     fn change_running_animations_state(&mut self,
@@ -872,6 +1278,33 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         }
     }
 
+    fn change_page_encoding(&mut self, pipeline_id: PipelineId, encoding: String) {
+        let is_root = self.root_pipeline.as_ref().map_or(false, |root_pipeline| {
+            root_pipeline.id == pipeline_id
+        });
+        if is_root {
+            self.window.set_page_encoding(encoding);
+        }
+    }
+
+    fn change_page_content_type(&mut self, pipeline_id: PipelineId, content_type: String) {
+        let is_root = self.root_pipeline.as_ref().map_or(false, |root_pipeline| {
+            root_pipeline.id == pipeline_id
+        });
+        if is_root {
+            self.window.set_page_content_type(content_type);
+        }
+    }
+
+    fn change_selection(&mut self, pipeline_id: PipelineId, selection: Option<SelectionInfo>) {
+        let is_root = self.root_pipeline.as_ref().map_or(false, |root_pipeline| {
+            root_pipeline.id == pipeline_id
+        });
+        if is_root {
+            self.window.set_selection(selection);
+        }
+    }
+
     fn change_page_url(&mut self, _: PipelineId, url: Url) {
         self.window.set_page_url(url);
     }
@@ -963,6 +1396,55 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         self.find_layer_with_pipeline_and_layer_id(pipeline_id, LayerId::null())
     }
 
+    /// Lists the layers that currently make up the given pipeline, in painting order, together
+    /// with what each of them shows now, so that a view transition can animate them and keep
+    /// showing the old state.
+    fn capture_view_transition(&mut self, pipeline_id: PipelineId) -> ViewTransitionSnapshot {
+        let mut captured = vec![];
+        if let Some(root_layer) = self.scene.root.clone() {
+            let origin = Point2D::typed(0., 0.);
+            capture_layers_for_view_transition(&root_layer, pipeline_id, origin, &mut captured);
+        }
+        let image = if captured.is_empty() {
+            None
+        } else {
+            self.draw_scene_to_image()
+        };
+
+        let scale = self.scene.scale.get();
+        let layers = captured.into_iter().map(|(mut layer, rect)| {
+            if let Some(ref image) = image {
+                let origin = Point2D::new(rect.origin.x.get() * scale, rect.origin.y.get() * scale);
+                let size = Size2D::new(rect.size.width.get() * scale, rect.size.height.get() * scale);
+                let device_rect = Rect::new(origin, size);
+                let (pixels, pixels_size) = crop_image(image, &device_rect);
+                layer.pixels = pixels;
+                layer.pixels_size = pixels_size;
+            }
+            layer
+        }).collect();
+        ViewTransitionSnapshot {
+            layers: layers,
+        }
+    }
+
+    /// Paints the scene into an offscreen framebuffer and reads it back, without presenting it.
+    /// Returns `None` with WebRender, whose output cannot be read back here.
+    fn draw_scene_to_image(&mut self) -> Option<RgbImage> {
+        let (context, root_layer) = match (self.context, self.scene.root.clone()) {
+            (Some(context), Some(root_layer)) => (context, root_layer),
+            _ => return None,
+        };
+        let (width, height) =
+            (self.window_size.width.get() as usize, self.window_size.height.get() as usize);
+        if !self.window.prepare_for_composite(width, height) {
+            return None;
+        }
+        let render_target_info = initialize_png(width, height);
+        rendergl::render_scene(root_layer, context, &self.scene);
+        Some(self.draw_img(render_target_info, width, height))
+    }
+
     fn collect_old_layers(&mut self,
                           pipeline_id: PipelineId,
                           new_layers: &[LayerProperties]) {
@@ -1276,6 +1758,26 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         }
     }
 
+    fn adjust_scroll_anchor(&mut self,
+                            pipeline_id: PipelineId,
+                            layer_id: LayerId,
+                            delta: Point2D<f32>) {
+        let layer = match self.find_layer_with_pipeline_and_layer_id(pipeline_id, layer_id) {
+            Some(layer) => layer,
+            None => return warn!("Compositor: Tried to adjust scroll anchor of unknown layer."),
+        };
+        if layer.wants_scroll_events() != WantsScrollEventsFlag::WantsScrollEvents {
+            return;
+        }
+
+        // Scroll offsets are the negated scroll position, so scrolling down by `delta` means
+        // subtracting it.
+        let new_offset = layer.extra_data.borrow().scroll_offset - Point2D::from_untyped(&delta);
+        layer.clamp_scroll_offset_and_scroll_layer(new_offset);
+        self.perform_updates_after_scroll();
+        self.send_viewport_rects_for_all_layers()
+    }
+
     fn handle_window_message(&mut self, event: WindowEvent) {
         match event {
             WindowEvent::Idle => {}
@@ -1366,6 +1868,25 @@ impl<Window: WindowMethods> IOCompositor<Window> {
                     warn!("Sending reload to constellation failed ({}).", e);
                 }
             }
+
+            WindowEvent::ActivateAccessKey(pipeline_id, element_id) => {
+                let msg = ConstellationMsg::ActivateAccessKey(pipeline_id, element_id);
+                if let Err(e) = self.constellation_chan.send(msg) {
+                    warn!("Sending access key activation to constellation failed ({}).", e);
+                }
+            }
+
+            WindowEvent::Activated => {
+                if let Err(e) = self.constellation_chan.send(ConstellationMsg::WindowActivated) {
+                    warn!("Sending window activation to constellation failed ({}).", e);
+                }
+            }
+
+            WindowEvent::KillPipeline(pipeline_id) => {
+                if let Err(e) = self.constellation_chan.send(ConstellationMsg::KillPipeline(pipeline_id)) {
+                    warn!("Sending pipeline kill to constellation failed ({}).", e);
+                }
+            }
         }
     }
 
@@ -1768,8 +2289,14 @@ impl<Window: WindowMethods> IOCompositor<Window> {
         }
     }
 
-    /// If there are any animations running, dispatches appropriate messages to the constellation.
+    /// Advances the animations that the compositor runs itself, and if there are any animations
+    /// running in layout or script, dispatches appropriate messages to the constellation.
     fn process_animations(&mut self) {
+        if self.sample_compositor_animations() {
+            self.send_buffer_requests_for_all_layers();
+            self.schedule_delayed_composite_if_necessary();
+        }
+
         let mut pipeline_ids = vec![];
         for (pipeline_id, pipeline_details) in &self.pipeline_details {
             if (pipeline_details.animations_running ||
@@ -2086,6 +2613,12 @@ impl<Window: WindowMethods> IOCompositor<Window> {
             if details.animation_callbacks_running {
                 return true;
             }
+            // Animations driven by a scroll timeline only move when the page is scrolled.
+            if details.compositor_animations.iter().any(|animation| {
+                !details.scroll_timelines.contains_key(&animation.id)
+            }) {
+                return true;
+            }
         }
 
         false
@@ -2228,6 +2761,8 @@ impl<Window: WindowMethods> IOCompositor<Window> {
             _ => initialize_png(width, height)
         };
 
+        self.sample_compositor_animations();
+
         profile(ProfilerCategory::Compositing, None, self.time_profiler_chan.clone(), || {
             debug!("compositor: compositing");
             self.dump_layer_tree();
@@ -2691,6 +3226,125 @@ fn find_layer_with_pipeline_and_layer_id_for_layer(layer: Rc<Layer<CompositorDat
     None
 }
 
+/// Finds where the given pipeline's viewport starts, in the coordinates of `layer`'s parent.
+fn find_pipeline_viewport_origin_for_layer(layer: &Rc<Layer<CompositorData>>,
+                                           pipeline_id: PipelineId,
+                                           offset_in_parent: TypedPoint2D<LayerPixel, f32>)
+                                           -> Option<TypedPoint2D<LayerPixel, f32>> {
+    let layer_origin = offset_in_parent + layer.bounds.borrow().origin;
+    if layer.extra_data.borrow().pipeline_id == pipeline_id &&
+       layer.extra_data.borrow().id == LayerId::null() {
+        return Some(layer_origin);
+    }
+
+    let offset_for_children = layer_origin + layer.extra_data.borrow().scroll_offset;
+    for kid in &*layer.children() {
+        let result = find_pipeline_viewport_origin_for_layer(kid, pipeline_id, offset_for_children);
+        if result.is_some() {
+            return result;
+        }
+    }
+
+    None
+}
+
+/// Collects the layers of the given pipeline under `layer`, each with its bounds relative to the
+/// root layer. `origin` is the position of `layer`'s parent's content in the root layer.
+/// FIXME: Layer transforms are not taken into account.
+fn capture_layers_for_view_transition(layer: &Rc<Layer<CompositorData>>,
+                                      pipeline_id: PipelineId,
+                                      origin: TypedPoint2D<LayerPixel, f32>,
+                                      layers: &mut Vec<(CapturedLayer, TypedRect<LayerPixel, f32>)>) {
+    let bounds = *layer.bounds.borrow();
+    // Layers of subframes belong to their own pipelines, which capture them separately.
+    if layer.extra_data.borrow().pipeline_id == pipeline_id {
+        let captured = CapturedLayer {
+            layer_id: layer.extra_data.borrow().id,
+            bounds: bounds.to_untyped(),
+            pixels: vec![],
+            pixels_size: Size2D::zero(),
+        };
+        layers.push((captured, bounds.translate(&origin)));
+    }
+
+    let content_origin = origin + bounds.origin + layer.extra_data.borrow().scroll_offset;
+    for kid in &*layer.children() {
+        capture_layers_for_view_transition(kid, pipeline_id, content_origin, layers);
+    }
+}
+
+/// Copies the pixels of `image` within `rect`, clipped to the image, returning them along with
+/// the size of the copied area.
+fn crop_image(image: &RgbImage, rect: &Rect<f32>) -> (Vec<u8>, Size2D<u32>) {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let left = rect.origin.x.max(0.0).min(width).floor() as usize;
+    let top = rect.origin.y.max(0.0).min(height).floor() as usize;
+    let right = rect.max_x().max(0.0).min(width).ceil() as usize;
+    let bottom = rect.max_y().max(0.0).min(height).ceil() as usize;
+    if right <= left || bottom <= top {
+        return (vec![], Size2D::zero());
+    }
+
+    let stride = image.width() as usize * 3;
+    let row_length = (right - left) * 3;
+    let mut pixels = Vec::with_capacity(row_length * (bottom - top));
+    for y in top..bottom {
+        let start = y * stride + left * 3;
+        pixels.extend_from_slice(&image[start..start + row_length]);
+    }
+    (pixels, Size2D::new((right - left) as u32, (bottom - top) as u32))
+}
+
+/// Returns how far into its current iteration a compositor animation is at the given time, as a
+/// fraction between 0 and 1, and whether it has finished. Returns `None` while the animation is
+/// still in its delay.
+fn compositor_animation_progress(timing: &AnimationTiming, now: f64) -> Option<(f32, bool)> {
+    let elapsed = now - timing.start_time - timing.delay;
+    if elapsed < 0.0 {
+        return None
+    }
+    if timing.duration <= 0.0 {
+        return Some((1.0, true))
+    }
+
+    let iterations = elapsed / timing.duration;
+    match timing.iteration_count {
+        Some(count) if iterations >= count as f64 => {
+            // Stop at the end of the last iteration, which may be a partial one.
+            let end = (count as f64).fract();
+            let progress = if end == 0.0 && count > 0.0 { 1.0 } else { end };
+            Some((progress as f32, true))
+        }
+        _ => Some((iterations.fract() as f32, false)),
+    }
+}
+
+/// Interpolates between the keyframes of a compositor animation at the given progress. Transforms
+/// are interpolated component by component, which matches CSS for the translations and scales
+/// layout offloads.
+fn compositor_animation_value(keyframes: &[CompositorKeyframe], progress: f32) -> Option<AnimatedValue> {
+    let next = match keyframes.iter().position(|keyframe| keyframe.offset >= progress) {
+        Some(0) => return keyframes.first().map(|keyframe| keyframe.value.clone()),
+        Some(next) => next,
+        None => return keyframes.last().map(|keyframe| keyframe.value.clone()),
+    };
+
+    let (from, to) = (&keyframes[next - 1], &keyframes[next]);
+    let t = (progress - from.offset) / (to.offset - from.offset);
+    match (&from.value, &to.value) {
+        (&AnimatedValue::Opacity(from), &AnimatedValue::Opacity(to)) => {
+            Some(AnimatedValue::Opacity(from + (to - from) * t))
+        }
+        (&AnimatedValue::Transform(ref from), &AnimatedValue::Transform(ref to)) => {
+            Some(AnimatedValue::Transform(from.mul_s(1.0 - t).add(&to.mul_s(t))))
+        }
+        _ => {
+            warn!("Compositor animation keyframes animate different properties.");
+            None
+        }
+    }
+}
+
 /// Why we performed a composite. This is used for debugging.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum CompositingReason {
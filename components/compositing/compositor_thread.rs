@@ -6,6 +6,7 @@
 
 use SendableFrameTree;
 use compositor::CompositingReason;
+use cssparser::RGBA;
 use euclid::point::Point2D;
 use euclid::size::Size2D;
 use gfx_traits::{Epoch, FrameTreeId, LayerId, LayerProperties, PaintListener};
@@ -15,7 +16,13 @@ use layers::platform::surface::{NativeDisplay, NativeSurface};
 use msg::constellation_msg::{Image, Key, KeyModifiers, KeyState, PipelineId};
 use profile_traits::mem;
 use profile_traits::time;
-use script_traits::{AnimationState, ConstellationMsg, EventResult};
+use script_traits::{AnimationState, AutoplayContext, BlockReason, CompositorAnimation, ConstellationMsg, CursorId};
+use script_traits::{DisplayCapabilities, DisplayCaptureConstraints, DragData, ElementId, EventResult};
+use script_traits::{FrameStatsSummary, FullscreenOptions, IceConfig, IceGatheringId, ImeState, MediaConstraints};
+use script_traits::{MediaDeviceInfo, MediaElementId, MediaError, MediaStreamInfo, MediaType, MsDuration};
+use script_traits::{PageLifecycleState, PointerCapabilities, ScreenInfo, ScrollTimeline, ScrollbarPreferences};
+use script_traits::{SelectionInfo, SpeechRecognitionConfig, SpeechRecognitionId, TextFragmentDirective, Tooltip};
+use script_traits::{ViewTransitionSnapshot, VirtualKeyboardGeometry, WindowFeatures, WindowPlacement};
 use std::fmt::{Debug, Error, Formatter};
 use std::sync::mpsc::{Receiver, Sender, channel};
 use style_traits::cursor::Cursor;
@@ -114,8 +121,9 @@ impl PaintListener for Box<CompositorProxy + 'static + Send> {
 
 /// Messages from the painting thread and the constellation thread to the compositor thread.
 pub enum Msg {
-    /// Requests that the compositor shut down.
-    Exit,
+    /// Requests that the compositor shut down, acknowledging on the given sender, if any, once
+    /// shutdown has completed.
+    Exit(Option<IpcSender<()>>),
 
     /// Informs the compositor that the constellation has completed shutdown.
     /// Required because the constellation can have pending calls to make
@@ -132,6 +140,8 @@ pub enum Msg {
     InitializeLayersForPipeline(PipelineId, Epoch, Vec<LayerProperties>),
     /// Scroll a page in a window
     ScrollFragmentPoint(PipelineId, LayerId, Point2D<f32>, bool),
+    /// Shifts the scroll position of a layer to keep its scroll anchor in place.
+    ScrollAnchorAdjustment(PipelineId, LayerId, Point2D<f32>),
     /// Requests that the compositor assign the painted buffers to the given layers.
     AssignPaintedBuffers(PipelineId, Epoch, Vec<(LayerId, Box<LayerBufferSet>)>, FrameTreeId),
     /// Alerts the compositor that the current page has changed its title.
@@ -140,6 +150,12 @@ pub enum Msg {
     ChangePageUrl(PipelineId, Url),
     /// Alerts the compositor that the given pipeline has changed whether it is running animations.
     ChangeRunningAnimationsState(PipelineId, AnimationState),
+    /// Replaces the set of animations that the compositor runs on its own for the given pipeline.
+    SetCompositorAnimations(PipelineId, Vec<CompositorAnimation>),
+    /// Drives one of the pipeline's compositor animations from the scroll offset of a layer.
+    SetScrollTimeline(PipelineId, LayerId, ScrollTimeline),
+    /// Captures the layers of a pipeline for a view transition.
+    CaptureViewTransition(PipelineId, IpcSender<ViewTransitionSnapshot>),
     /// Replaces the current frame tree, typically called during main frame navigation.
     SetFrameTree(SendableFrameTree, IpcSender<()>),
     /// The load of a page has begun: (can go back, can go forward).
@@ -156,6 +172,8 @@ pub enum Msg {
     TouchEventProcessed(EventResult),
     /// Changes the cursor.
     SetCursor(Cursor),
+    /// Shows, moves or (with `Cursor::None`) hides one of the pipeline's named cursors.
+    SetNamedCursor(PipelineId, CursorId, Cursor, Point2D<f32>),
     /// Composite to a PNG file and return the Image over a passed channel.
     CreatePng(IpcSender<Option<Image>>),
     /// Informs the compositor that the paint thread for the given pipeline has exited.
@@ -164,8 +182,10 @@ pub enum Msg {
     ViewportConstrained(PipelineId, ViewportConstraints),
     /// A reply to the compositor asking if the output image is stable.
     IsReadyToSaveImageReply(bool),
-    /// A favicon was detected
+    /// A favicon was detected in the top-level pipeline.
     NewFavicon(Url),
+    /// The top-level pipeline set a theme color.
+    SetThemeColor(RGBA),
     /// <head> tag finished parsing
     HeadParsed,
     /// Signal that the paint thread ignored the paint requests that carried
@@ -175,6 +195,10 @@ pub enum Msg {
     CollectMemoryReports(mem::ReportsChan),
     /// A status message to be displayed by the browser chrome.
     Status(Option<String>),
+    /// The target of the link under the mouse, if any.
+    LinkHover(Option<Url>),
+    /// The tooltip for the element under the mouse, if any.
+    SetTooltip(Option<Tooltip>),
     /// Get Window Informations size and position
     GetClientWindow(IpcSender<(Size2D<u32>, Point2D<i32>)>),
     /// Move the window to a point
@@ -188,6 +212,91 @@ pub enum Msg {
     /// WebRender has successfully processed a scroll. The boolean specifies whether a composite is
     /// needed.
     NewScrollFrameReady(bool),
+    /// Alerts the compositor that the character encoding of a page is known.
+    ChangePageEncoding(PipelineId, String),
+    /// Alerts the compositor that the MIME type of a page is known.
+    ChangePageContentType(PipelineId, String),
+    /// Alerts the compositor that the selection of a page changed.
+    ChangeSelection(PipelineId, Option<SelectionInfo>),
+    /// Asks the embedder to show a media element in a picture-in-picture window.
+    RequestPictureInPicture(PipelineId, MediaElementId, IpcSender<Result<(), String>>),
+    /// Asks the embedder to close the picture-in-picture window of a media element.
+    ExitPictureInPicture(PipelineId, MediaElementId),
+    /// Asks the embedder to enter fullscreen with the given options, or to leave it.
+    SetFullscreenState(Option<FullscreenOptions>),
+    /// Asks the embedder's autoplay policy whether a media element may play unprompted.
+    CheckAutoplayAllowed(PipelineId, AutoplayContext, IpcSender<bool>),
+    /// Asks the embedder's anti-tracking policy whether a frame may use first-party storage.
+    RequestStorageAccess(PipelineId, IpcSender<bool>),
+    /// Sets or clears the application badge on behalf of the top-level pipeline.
+    SetAppBadge(Option<u64>),
+    /// Asks the embedder to download the response to a navigation instead of rendering it.
+    Download(Url),
+    /// Requests the mapping from physical key codes to keys in the current keyboard layout.
+    GetKeyboardLayoutMap(IpcSender<Vec<(String, String)>>),
+    /// Asks the embedder to route the given keys (or every key) to a pipeline.
+    LockKeyboard(PipelineId, Vec<String>, IpcSender<Result<(), String>>),
+    /// Releases a pipeline's keyboard lock.
+    UnlockKeyboard(PipelineId),
+    /// Enables or disables the embedder's input method editor for a pipeline.
+    SetImeState(PipelineId, ImeState),
+    /// Asks the embedder to show its on-screen keyboard, replying with the area it covers.
+    ShowVirtualKeyboard(PipelineId, IpcSender<VirtualKeyboardGeometry>),
+    /// Asks the embedder to hide its on-screen keyboard.
+    HideVirtualKeyboard(PipelineId),
+    /// Tells the embedder's accessibility layer that focus moved to an element now in view.
+    EnsureFocusVisible(PipelineId, ElementId),
+    /// Asks the embedder to start recognizing speech for a pipeline.
+    StartSpeechRecognition(PipelineId, SpeechRecognitionId, SpeechRecognitionConfig),
+    /// Asks the embedder to stop a speech recognition session.
+    StopSpeechRecognition(SpeechRecognitionId),
+    /// Asks the embedder to open the user's camera and/or microphone for a pipeline.
+    RequestUserMedia(PipelineId, MediaConstraints, IpcSender<Result<MediaStreamInfo, MediaError>>),
+    /// Requests the media devices a pipeline may know about.
+    EnumerateMediaDevices(PipelineId, IpcSender<Vec<MediaDeviceInfo>>),
+    /// Asks the embedder to let the user pick a screen, window or tab to share with a pipeline.
+    RequestDisplayCapture(PipelineId, DisplayCaptureConstraints, IpcSender<Result<MediaStreamInfo, MediaError>>),
+    /// Asks the embedder to gather ICE candidates for a pipeline.
+    GatherIceCandidates(PipelineId, IceGatheringId, IceConfig),
+    /// Asks the embedder how many simultaneous touch contacts the device supports.
+    GetMaxTouchPoints(IpcSender<u32>),
+    /// Asks the embedder what pointing devices the user has.
+    GetPointerCapabilities(IpcSender<PointerCapabilities>),
+    /// Asks the embedder what the display a pipeline's window is on can show.
+    GetDisplayCapabilities(PipelineId, IpcSender<DisplayCapabilities>),
+    /// Asks the embedder for the refresh rate of the display a pipeline's window is on.
+    GetDisplayRefreshRate(PipelineId, IpcSender<f32>),
+    /// Asks the embedder for the screens attached to the host.
+    GetScreens(PipelineId, IpcSender<Vec<ScreenInfo>>),
+    /// Moves the window, possibly onto another screen.
+    PlaceWindow(WindowPlacement),
+    /// Tells the embedder which element of a pipeline's document should have focus initially.
+    SetInitialFocus(PipelineId, Option<ElementId>),
+    /// Tells the embedder the `accesskey` shortcuts of a pipeline's document.
+    SetAccessKeys(PipelineId, Vec<(String, ElementId)>),
+    /// Asks the embedder to run its native drag loop for a drag that started in a pipeline.
+    StartDrag(PipelineId, DragData),
+    /// Asks the embedder to highlight the text matched by a pipeline's text fragment directive.
+    HighlightTextFragment(PipelineId, TextFragmentDirective),
+    /// Tells the embedder that a script in a pipeline has run past its time budget.
+    ScriptTimeout(PipelineId, MsDuration),
+    /// Tells the embedder that a pipeline stopped or started answering pings again.
+    SetPipelineResponsive(PipelineId, bool),
+    /// Updates the embedder's performance overlay with a summary of a pipeline's recent frames.
+    UpdatePerformanceOverlay(PipelineId, FrameStatsSummary),
+    /// Asks the embedder how the host platform draws scrollbars.
+    GetScrollbarPreferences(IpcSender<ScrollbarPreferences>),
+    /// Asks the embedder to show a warning interstitial for a blocked navigation.
+    ShowNavigationBlocked(PipelineId, Url, BlockReason),
+    /// Asks the embedder to hide the warning interstitial shown over a pipeline's frame.
+    HideInterstitial(PipelineId),
+    /// Tells the embedder that a pipeline's page lifecycle changed.
+    PageLifecycleChanged(PipelineId, PageLifecycleState),
+    /// Tells the embedder that a pipeline was opened as a new top-level browsing context, with
+    /// the features it should be shown with.
+    AuxiliaryBrowsingContextOpened(PipelineId, WindowFeatures),
+    /// Tells the embedder that a pipeline's document is now laid out for the given media type.
+    MediaTypeChanged(PipelineId, MediaType),
     /// A pipeline was shut down.
     // This message acts as a synchronization point between the constellation,
     // when it shuts down a pipeline, to the compositor; when the compositor
@@ -199,13 +308,17 @@ pub enum Msg {
 impl Debug for Msg {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         match *self {
-            Msg::Exit => write!(f, "Exit"),
+            Msg::Exit(..) => write!(f, "Exit"),
             Msg::ShutdownComplete => write!(f, "ShutdownComplete"),
             Msg::GetNativeDisplay(..) => write!(f, "GetNativeDisplay"),
             Msg::InitializeLayersForPipeline(..) => write!(f, "InitializeLayersForPipeline"),
             Msg::ScrollFragmentPoint(..) => write!(f, "ScrollFragmentPoint"),
+            Msg::ScrollAnchorAdjustment(..) => write!(f, "ScrollAnchorAdjustment"),
             Msg::AssignPaintedBuffers(..) => write!(f, "AssignPaintedBuffers"),
             Msg::ChangeRunningAnimationsState(..) => write!(f, "ChangeRunningAnimationsState"),
+            Msg::SetCompositorAnimations(..) => write!(f, "SetCompositorAnimations"),
+            Msg::SetScrollTimeline(..) => write!(f, "SetScrollTimeline"),
+            Msg::CaptureViewTransition(..) => write!(f, "CaptureViewTransition"),
             Msg::ChangePageTitle(..) => write!(f, "ChangePageTitle"),
             Msg::ChangePageUrl(..) => write!(f, "ChangePageUrl"),
             Msg::SetFrameTree(..) => write!(f, "SetFrameTree"),
@@ -216,19 +329,65 @@ impl Debug for Msg {
             Msg::KeyEvent(..) => write!(f, "KeyEvent"),
             Msg::TouchEventProcessed(..) => write!(f, "TouchEventProcessed"),
             Msg::SetCursor(..) => write!(f, "SetCursor"),
+            Msg::SetNamedCursor(..) => write!(f, "SetNamedCursor"),
             Msg::CreatePng(..) => write!(f, "CreatePng"),
             Msg::PaintThreadExited(..) => write!(f, "PaintThreadExited"),
             Msg::ViewportConstrained(..) => write!(f, "ViewportConstrained"),
             Msg::IsReadyToSaveImageReply(..) => write!(f, "IsReadyToSaveImageReply"),
             Msg::NewFavicon(..) => write!(f, "NewFavicon"),
+            Msg::SetThemeColor(..) => write!(f, "SetThemeColor"),
             Msg::HeadParsed => write!(f, "HeadParsed"),
             Msg::ReturnUnusedNativeSurfaces(..) => write!(f, "ReturnUnusedNativeSurfaces"),
             Msg::CollectMemoryReports(..) => write!(f, "CollectMemoryReports"),
             Msg::Status(..) => write!(f, "Status"),
+            Msg::LinkHover(..) => write!(f, "LinkHover"),
+            Msg::SetTooltip(..) => write!(f, "SetTooltip"),
             Msg::GetClientWindow(..) => write!(f, "GetClientWindow"),
             Msg::MoveTo(..) => write!(f, "MoveTo"),
             Msg::ResizeTo(..) => write!(f, "ResizeTo"),
             Msg::PipelineVisibilityChanged(..) => write!(f, "PipelineVisibilityChanged"),
+            Msg::ChangePageEncoding(..) => write!(f, "ChangePageEncoding"),
+            Msg::ChangePageContentType(..) => write!(f, "ChangePageContentType"),
+            Msg::ChangeSelection(..) => write!(f, "ChangeSelection"),
+            Msg::RequestPictureInPicture(..) => write!(f, "RequestPictureInPicture"),
+            Msg::ExitPictureInPicture(..) => write!(f, "ExitPictureInPicture"),
+            Msg::SetFullscreenState(..) => write!(f, "SetFullscreenState"),
+            Msg::CheckAutoplayAllowed(..) => write!(f, "CheckAutoplayAllowed"),
+            Msg::RequestStorageAccess(..) => write!(f, "RequestStorageAccess"),
+            Msg::SetAppBadge(..) => write!(f, "SetAppBadge"),
+            Msg::Download(..) => write!(f, "Download"),
+            Msg::GetKeyboardLayoutMap(..) => write!(f, "GetKeyboardLayoutMap"),
+            Msg::LockKeyboard(..) => write!(f, "LockKeyboard"),
+            Msg::UnlockKeyboard(..) => write!(f, "UnlockKeyboard"),
+            Msg::SetImeState(..) => write!(f, "SetImeState"),
+            Msg::ShowVirtualKeyboard(..) => write!(f, "ShowVirtualKeyboard"),
+            Msg::HideVirtualKeyboard(..) => write!(f, "HideVirtualKeyboard"),
+            Msg::EnsureFocusVisible(..) => write!(f, "EnsureFocusVisible"),
+            Msg::StartSpeechRecognition(..) => write!(f, "StartSpeechRecognition"),
+            Msg::StopSpeechRecognition(..) => write!(f, "StopSpeechRecognition"),
+            Msg::RequestUserMedia(..) => write!(f, "RequestUserMedia"),
+            Msg::EnumerateMediaDevices(..) => write!(f, "EnumerateMediaDevices"),
+            Msg::RequestDisplayCapture(..) => write!(f, "RequestDisplayCapture"),
+            Msg::GatherIceCandidates(..) => write!(f, "GatherIceCandidates"),
+            Msg::GetMaxTouchPoints(..) => write!(f, "GetMaxTouchPoints"),
+            Msg::GetPointerCapabilities(..) => write!(f, "GetPointerCapabilities"),
+            Msg::GetDisplayCapabilities(..) => write!(f, "GetDisplayCapabilities"),
+            Msg::GetDisplayRefreshRate(..) => write!(f, "GetDisplayRefreshRate"),
+            Msg::GetScreens(..) => write!(f, "GetScreens"),
+            Msg::PlaceWindow(..) => write!(f, "PlaceWindow"),
+            Msg::SetInitialFocus(..) => write!(f, "SetInitialFocus"),
+            Msg::SetAccessKeys(..) => write!(f, "SetAccessKeys"),
+            Msg::StartDrag(..) => write!(f, "StartDrag"),
+            Msg::HighlightTextFragment(..) => write!(f, "HighlightTextFragment"),
+            Msg::ScriptTimeout(..) => write!(f, "ScriptTimeout"),
+            Msg::SetPipelineResponsive(..) => write!(f, "SetPipelineResponsive"),
+            Msg::UpdatePerformanceOverlay(..) => write!(f, "UpdatePerformanceOverlay"),
+            Msg::GetScrollbarPreferences(..) => write!(f, "GetScrollbarPreferences"),
+            Msg::ShowNavigationBlocked(..) => write!(f, "ShowNavigationBlocked"),
+            Msg::HideInterstitial(..) => write!(f, "HideInterstitial"),
+            Msg::PageLifecycleChanged(..) => write!(f, "PageLifecycleChanged"),
+            Msg::AuxiliaryBrowsingContextOpened(..) => write!(f, "AuxiliaryBrowsingContextOpened"),
+            Msg::MediaTypeChanged(..) => write!(f, "MediaTypeChanged"),
             Msg::PipelineExited(..) => write!(f, "PipelineExited"),
             Msg::GetScrollOffset(..) => write!(f, "GetScrollOffset"),
             Msg::NewScrollFrameReady(..) => write!(f, "NewScrollFrameReady"),
@@ -14,6 +14,7 @@
 extern crate app_units;
 
 extern crate azure;
+extern crate cssparser;
 extern crate euclid;
 extern crate gfx_traits;
 extern crate gleam;
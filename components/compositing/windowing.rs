@@ -5,15 +5,22 @@
 //! Abstract windowing methods. The concrete implementations of these can be found in `platform/`.
 
 use compositor_thread::{CompositorProxy, CompositorReceiver};
+use cssparser::RGBA;
 use euclid::point::TypedPoint2D;
 use euclid::scale_factor::ScaleFactor;
 use euclid::size::TypedSize2D;
 use euclid::{Point2D, Size2D};
 use layers::geometry::DevicePixel;
 use layers::platform::surface::NativeDisplay;
-use msg::constellation_msg::{Key, KeyModifiers, KeyState};
+use msg::constellation_msg::{Key, KeyModifiers, KeyState, PipelineId};
 use net_traits::net_error_list::NetError;
-use script_traits::{MouseButton, TouchpadPressurePhase, TouchEventType, TouchId};
+use script_traits::{AutoplayContext, BlockReason, CursorId, DisplayCapabilities};
+use script_traits::{DisplayCaptureConstraints, DragData, ElementId, FrameStatsSummary, FullscreenOptions};
+use script_traits::{IceConfig, IceGatheringId, ImeState, MediaConstraints, MediaDeviceInfo, MediaElementId};
+use script_traits::{MediaError, MediaStreamInfo, MediaType, MouseButton, MsDuration, PageLifecycleState};
+use script_traits::{PointerCapabilities, ScreenInfo, ScrollbarPreferences, SelectionInfo};
+use script_traits::{SpeechRecognitionConfig, SpeechRecognitionId, TextFragmentDirective, Tooltip, TouchEventType};
+use script_traits::{TouchId, TouchpadPressurePhase, VirtualKeyboardGeometry, WindowFeatures, WindowPlacement};
 use std::fmt::{Debug, Error, Formatter};
 use style_traits::cursor::Cursor;
 use url::Url;
@@ -79,6 +86,12 @@ pub enum WindowEvent {
     KeyEvent(Option<char>, Key, KeyState, KeyModifiers),
     /// Sent when Ctr+R/Apple+R is called to reload the current page.
     Reload,
+    /// Sent when the user presses the accelerator for an `accesskey` shortcut.
+    ActivateAccessKey(PipelineId, ElementId),
+    /// Sent when the window becomes the active window.
+    Activated,
+    /// Sent when the user chooses to stop an unresponsive pipeline.
+    KillPipeline(PipelineId),
 }
 
 impl Debug for WindowEvent {
@@ -102,6 +115,9 @@ impl Debug for WindowEvent {
             WindowEvent::Navigation(..) => write!(f, "Navigation"),
             WindowEvent::Quit => write!(f, "Quit"),
             WindowEvent::Reload => write!(f, "Reload"),
+            WindowEvent::ActivateAccessKey(..) => write!(f, "ActivateAccessKey"),
+            WindowEvent::Activated => write!(f, "Activated"),
+            WindowEvent::KillPipeline(..) => write!(f, "KillPipeline"),
         }
     }
 }
@@ -127,6 +143,10 @@ pub trait WindowMethods {
     fn set_page_url(&self, url: Url);
     /// Called when the browser chrome should display a status message.
     fn status(&self, Option<String>);
+    /// Called when the mouse moves over a link, or away from one when `url` is `None`.
+    fn link_hover(&self, url: Option<Url>);
+    /// Called when the chrome should show a tooltip, or hide it when `tooltip` is `None`.
+    fn set_tooltip(&self, tooltip: Option<Tooltip>);
     /// Called when the browser has started loading a frame.
     fn load_start(&self, back: bool, forward: bool);
     /// Called when the browser is done loading a frame.
@@ -166,4 +186,170 @@ pub trait WindowMethods {
 
     /// Add a favicon
     fn set_favicon(&self, url: Url);
+
+    /// Sets the character encoding of the current page, for "view encoding" menus.
+    fn set_page_encoding(&self, encoding: String);
+
+    /// Sets the MIME type of the current page.
+    fn set_page_content_type(&self, content_type: String);
+
+    /// Sets the theme color of the current page, which the chrome may use to tint itself.
+    fn set_theme_color(&self, color: RGBA);
+
+    /// Shows a named cursor at the given position, or hides it when `cursor` is `Cursor::None`.
+    /// The user's own pointer is still set with `set_cursor`.
+    fn set_named_cursor(&self, cursor_id: CursorId, cursor: Cursor, position: TypedPoint2D<DevicePixel, f32>);
+
+    /// Sets the selection of the current page, or clears it when `None`.
+    fn set_selection(&self, selection: Option<SelectionInfo>);
+
+    /// Shows a media element in a picture-in-picture window, or returns why it cannot.
+    fn request_picture_in_picture(&self, pipeline_id: PipelineId, media_id: MediaElementId) -> Result<(), String>;
+
+    /// Closes the picture-in-picture window of a media element.
+    fn exit_picture_in_picture(&self, pipeline_id: PipelineId, media_id: MediaElementId);
+
+    /// Shows the window fullscreen with the given options, or leaves fullscreen when `None`.
+    fn set_fullscreen_state(&self, options: Option<FullscreenOptions>);
+
+    /// Decides whether a media element may start playing without the user asking it to.
+    fn allow_autoplay(&self, pipeline_id: PipelineId, context: AutoplayContext) -> bool;
+
+    /// Decides whether an embedded frame may use first-party storage, prompting the user if the
+    /// policy calls for it.
+    fn allow_storage_access(&self, pipeline_id: PipelineId) -> bool;
+
+    /// Shows a badge on the application's taskbar or dock icon: a number, a plain flag for
+    /// `Some(0)`, or nothing for `None`.
+    fn set_app_badge(&self, badge: Option<u64>);
+
+    /// Saves the resource at the given URL through the embedder's download manager, for a
+    /// navigation whose response is to be downloaded rather than rendered.
+    fn download(&self, url: Url);
+
+    /// Returns the mapping from physical key codes (`"KeyQ"`) to the keys they produce in the
+    /// current keyboard layout.
+    fn keyboard_layout_map(&self) -> Vec<(String, String)>;
+
+    /// Routes the given keys (by `code`, or every key when empty) to the pipeline, including
+    /// those the chrome or the system would normally handle, or returns why it cannot.
+    fn lock_keyboard(&self, pipeline_id: PipelineId, codes: Vec<String>) -> Result<(), String>;
+
+    /// Releases the pipeline's keyboard lock.
+    fn unlock_keyboard(&self, pipeline_id: PipelineId);
+
+    /// Enables or disables the input method editor, configuring it for the given kind of text.
+    fn set_ime_state(&self, pipeline_id: PipelineId, state: ImeState);
+
+    /// Shows the on-screen keyboard, returning the area of the viewport it covers (in CSS
+    /// pixels), which is empty when there is none.
+    fn show_virtual_keyboard(&self, pipeline_id: PipelineId) -> VirtualKeyboardGeometry;
+
+    /// Hides the on-screen keyboard.
+    fn hide_virtual_keyboard(&self, pipeline_id: PipelineId);
+
+    /// Tells assistive technology that focus moved to the given element, which has been
+    /// scrolled into view.
+    fn focus_visible(&self, pipeline_id: PipelineId, element_id: ElementId);
+
+    /// Starts recognizing speech from the microphone, in the given session.
+    fn start_speech_recognition(&self,
+                                pipeline_id: PipelineId,
+                                recognition_id: SpeechRecognitionId,
+                                config: SpeechRecognitionConfig);
+
+    /// Stops a speech recognition session.
+    fn stop_speech_recognition(&self, recognition_id: SpeechRecognitionId);
+
+    /// Opens a stream from the user's camera and/or microphone, asking the user which devices to
+    /// use if needed.
+    fn request_user_media(&self,
+                          pipeline_id: PipelineId,
+                          constraints: MediaConstraints)
+                          -> Result<MediaStreamInfo, MediaError>;
+
+    /// Lists the media input and output devices, leaving the labels empty for kinds of device
+    /// the pipeline has not been granted access to.
+    fn media_devices(&self, pipeline_id: PipelineId) -> Vec<MediaDeviceInfo>;
+
+    /// Shows the source picker and, unless the user cancels it, opens a stream capturing the
+    /// chosen screen, window or tab.
+    fn request_display_capture(&self,
+                               pipeline_id: PipelineId,
+                               constraints: DisplayCaptureConstraints)
+                               -> Result<MediaStreamInfo, MediaError>;
+
+    /// Starts gathering ICE candidates from the host's network interfaces and the configured
+    /// STUN and TURN servers.
+    fn gather_ice_candidates(&self, pipeline_id: PipelineId, gathering_id: IceGatheringId, config: IceConfig);
+
+    /// Returns the largest number of simultaneous touch contacts the device supports, or 0 if it
+    /// has no touch screen. When emulating another device, this is the emulated device's value.
+    fn max_touch_points(&self) -> u32;
+
+    /// Returns the pointing devices attached to the host. When emulating another device, this
+    /// describes the emulated device's instead.
+    fn pointer_capabilities(&self) -> PointerCapabilities;
+
+    /// Returns what the display the window is currently on can show.
+    fn display_capabilities(&self, pipeline_id: PipelineId) -> DisplayCapabilities;
+
+    /// Returns the refresh rate, in Hz, of the display the window is currently on, or 0.0 if
+    /// it is unknown or variable.
+    fn display_refresh_rate(&self, pipeline_id: PipelineId) -> f32;
+
+    /// Returns the screens attached to the host.
+    fn screens(&self, pipeline_id: PipelineId) -> Vec<ScreenInfo>;
+
+    /// Moves and resizes the window to the given bounds, making it fullscreen if asked to.
+    fn place_window(&self, placement: WindowPlacement);
+
+    /// Tells assistive technology which element of a newly loaded document has focus initially,
+    /// if any.
+    fn set_initial_focus(&self, pipeline_id: PipelineId, element_id: Option<ElementId>);
+
+    /// Updates the `accesskey` shortcuts of a pipeline's document. When the user presses the
+    /// accelerator for one, the embedder sends `WindowEvent::ActivateAccessKey`.
+    fn set_access_keys(&self, pipeline_id: PipelineId, access_keys: Vec<(String, ElementId)>);
+
+    /// Runs the native drag loop for a drag that started in a pipeline, showing its drag image.
+    fn start_drag(&self, pipeline_id: PipelineId, data: DragData);
+
+    /// Highlights the text matched by the text fragment directive of a pipeline's URL, which has
+    /// been scrolled into view.
+    fn highlight_text_fragment(&self, pipeline_id: PipelineId, directive: TextFragmentDirective);
+
+    /// Tells the embedder that a script in a pipeline has been running for the given time, past
+    /// its budget. The embedder may ask the user whether to stop the script.
+    fn script_timed_out(&self, pipeline_id: PipelineId, elapsed: MsDuration);
+
+    /// Tells the embedder that a pipeline has stopped responding, or that it responds again.
+    /// For an unresponsive pipeline, the embedder may offer to kill it.
+    fn set_pipeline_responsive(&self, pipeline_id: PipelineId, responsive: bool);
+
+    /// Shows a summary of a pipeline's recent frames in the embedder's performance overlay, if
+    /// it has one.
+    fn update_performance_overlay(&self, pipeline_id: PipelineId, summary: FrameStatsSummary);
+
+    /// Returns how the host platform draws scrollbars, from the OS settings.
+    fn scrollbar_preferences(&self) -> ScrollbarPreferences;
+
+    /// Shows a warning interstitial over the given pipeline's frame, explaining that its
+    /// navigation to the given URL was blocked and why.
+    fn show_navigation_blocked(&self, pipeline_id: PipelineId, url: Url, reason: BlockReason);
+
+    /// Hides the warning interstitial shown over the given pipeline's frame.
+    fn hide_interstitial(&self, pipeline_id: PipelineId);
+
+    /// Called when a pipeline's page lifecycle changes, such as when it enters or leaves the
+    /// back-forward cache.
+    fn page_lifecycle_changed(&self, pipeline_id: PipelineId, state: PageLifecycleState);
+
+    /// Called when `window.open` opened the given pipeline as a new top-level browsing context,
+    /// so that the window can be sized and styled after the requested features.
+    fn auxiliary_browsing_context_opened(&self, pipeline_id: PipelineId, features: WindowFeatures);
+
+    /// Called when a pipeline's document starts or stops being laid out for print. A print
+    /// capture of the page must wait until it has been told `MediaType::Print`.
+    fn media_type_changed(&self, pipeline_id: PipelineId, media_type: MediaType);
 }
@@ -17,12 +17,17 @@ use clipboard::ClipboardContext;
 use compositing::SendableFrameTree;
 use compositing::compositor_thread::CompositorProxy;
 use compositing::compositor_thread::Msg as ToCompositorMsg;
-use devtools_traits::{ChromeToDevtoolsControlMsg, DevtoolsControlMsg};
+use devtools_traits::LogLevel as DevtoolsLogLevel;
+use devtools_traits::{ChromeToDevtoolsControlMsg, ConsoleMessage, DevtoolsControlMsg};
+use devtools_traits::{DevtoolsFrameInfo, ScriptToDevtoolsControlMsg};
+use euclid::point::Point2D;
 use euclid::scale_factor::ScaleFactor;
 use euclid::size::{Size2D, TypedSize2D};
 use gfx::font_cache_thread::FontCacheThread;
-use gfx_traits::Epoch;
-use ipc_channel::ipc::{self, IpcSender};
+use gfx_traits::{Epoch, LayerId};
+use hyper::header::Headers;
+use indexed_db::IndexedDbManager;
+use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
 use layout_traits::LayoutThreadFactory;
 use log::{Log, LogLevel, LogLevelFilter, LogMetadata, LogRecord};
@@ -34,20 +39,27 @@ use msg::constellation_msg::{self, PanicMsg};
 use net_traits::bluetooth_thread::BluetoothMethodMsg;
 use net_traits::filemanager_thread::FileManagerThreadMsg;
 use net_traits::image_cache_thread::ImageCacheThread;
-use net_traits::storage_thread::StorageThreadMsg;
-use net_traits::{self, ResourceThreads, IpcSend};
+use net_traits::storage_thread::{StorageThreadMsg, StorageType};
+use net_traits::LoadData as NetLoadData;
+use net_traits::{self, AsyncResponseTarget, CoreResourceMsg, IpcSend, LoadConsumer, LoadContext};
+use net_traits::{RequestSource, ResourceThreads, ResponseAction};
 use offscreen_gl_context::{GLContextAttributes, GLLimits};
 use pipeline::{ChildProcess, InitialPipelineState, Pipeline};
 use profile_traits::mem;
 use profile_traits::time;
 use rand::{random, Rng, SeedableRng, StdRng};
-use script_traits::webdriver_msg;
-use script_traits::{AnimationState, AnimationTickType, CompositorEvent};
-use script_traits::{ConstellationControlMsg, ConstellationMsg as FromCompositorMsg};
-use script_traits::{DocumentState, LayoutControlMsg};
-use script_traits::{IFrameLoadInfo, IFrameSandboxState, TimerEventRequest};
-use script_traits::{LayoutMsg as FromLayoutMsg, ScriptMsg as FromScriptMsg, ScriptThreadFactory};
-use script_traits::{LogEntry, MozBrowserEvent, MozBrowserErrorType, WebDriverCommandMsg, WindowSizeData};
+use sanitizer::sanitize_html;
+use script_traits::{AnimationState, AnimationTickType, AuxiliaryContextInfo, BlockReason, CLEAR_COOKIES, CLEAR_STORAGE};
+use script_traits::{ClearDataTypes, CompositorEvent, ConstellationControlMsg, ConstellationMsg as FromCompositorMsg};
+use script_traits::{DeprecationInfo, DocumentState, ElementId, FrameInfo, FrameStats, HistoryState, IFrameLoadInfo};
+use script_traits::{IFrameSandboxState, IceConfig, IceGatheringId, IdbConnectionId, InterventionKind, LayoutControlMsg};
+use script_traits::{LayoutMsg as FromLayoutMsg, LogEntry, MediaType, MozBrowserErrorType, MozBrowserEvent};
+use script_traits::{NavigationCapability, NavigationDisposition, NavigationReason, NavigationType, Origin, PingId};
+use script_traits::{PipelineExitReason, ReportBody, ScriptMsg as FromScriptMsg, ScriptThreadFactory};
+use script_traits::{ScrollRestorationMode, SpatialDirection, SpeechRecognitionConfig, SpeechRecognitionId};
+use script_traits::{StorageBreakdown, TimerEventRequest, USER_ACTIVATION_DURATION_MS, WebDriverCommandMsg};
+use script_traits::{WebLockGrant, WebLockMode, WindowSizeData, precise_time_ms, webdriver_msg};
+use std::ascii::AsciiExt;
 use std::borrow::ToOwned;
 use std::collections::{HashMap, VecDeque};
 use std::io::Error as IOError;
@@ -57,6 +69,7 @@ use std::process;
 use std::sync::mpsc::{Sender, channel, Receiver};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 use style_traits::PagePx;
 use style_traits::cursor::Cursor;
 use style_traits::viewport::ViewportConstraints;
@@ -65,6 +78,7 @@ use url::Url;
 use util::opts;
 use util::prefs::PREFS;
 use util::thread::spawn_named;
+use web_locks::WebLockManager;
 use webrender_traits;
 
 #[derive(Debug, PartialEq)]
@@ -73,6 +87,7 @@ enum ReadyToSave {
     PendingFrames,
     WebFontNotLoaded,
     DocumentLoading,
+    FrameHashUnstable,
     EpochMismatch,
     PipelineUnknown,
     Ready,
@@ -106,6 +121,9 @@ pub struct Constellation<Message, LTF, STF> {
     /// Receives panic messages.
     panic_receiver: Receiver<PanicMsg>,
 
+    /// Receives a message every `PING_INTERVAL_MS`, when pipelines should be pinged.
+    ping_timer_receiver: Receiver<()>,
+
     /// A channel (the implementation of which is port-specific) through which messages can be sent
     /// to the compositor.
     compositor_proxy: Box<CompositorProxy>,
@@ -140,12 +158,34 @@ pub struct Constellation<Message, LTF, STF> {
     /// ID of the root frame.
     root_frame_id: Option<FrameId>,
 
+    /// IDs of the top-level frames of the browsing contexts opened by `window.open`. They are
+    /// not part of the root frame tree, and so are not shown in the window.
+    auxiliary_frame_ids: Vec<FrameId>,
+
     /// The next free ID to assign to a pipeline ID namespace.
     next_pipeline_namespace_id: PipelineNamespaceId,
 
     /// The next free ID to assign to a frame.
     next_frame_id: FrameId,
 
+    /// The next free ID to assign to a speech recognition session.
+    next_speech_recognition_id: SpeechRecognitionId,
+
+    /// The next free ID to assign to an ICE candidate gathering session.
+    next_ice_gathering_id: IceGatheringId,
+
+    /// The next free ID to assign to a responsiveness ping.
+    next_ping_id: PingId,
+
+    /// The Web Locks that are held or requested.
+    web_locks: WebLockManager,
+
+    /// The IndexedDB databases that have been opened.
+    indexed_dbs: IndexedDbManager,
+
+    /// The reports waiting to be delivered, oldest first, with the pipeline that queued them.
+    queued_reports: VecDeque<(PipelineId, ReportBody)>,
+
     /// Pipeline ID that has currently focused element for key events.
     focus_pipeline_id: Option<PipelineId>,
 
@@ -245,6 +285,12 @@ impl Frame {
         self.current = pipeline_id;
         replace(&mut self.next, vec!())
     }
+
+    /// Replaces the current session history entry, for a reload, leaving the rest of the
+    /// history alone. Returns the replaced pipeline, which is not kept for the back-forward cache.
+    fn reload(&mut self, pipeline_id: PipelineId) -> Vec<PipelineId> {
+        vec!(replace(&mut self.current, pipeline_id))
+    }
 }
 
 /// Represents a pending change in the frame tree, that will be applied
@@ -406,6 +452,19 @@ fn log_entry(record: &LogRecord) -> Option<LogEntry> {
 
 const WARNINGS_BUFFER_SIZE: usize = 32;
 
+/// How often pipelines are pinged to check that they are responsive, in milliseconds.
+const PING_INTERVAL_MS: u64 = 1000;
+
+/// How long a pipeline may take to answer a ping before it is reported as unresponsive, in
+/// milliseconds.
+const PING_TIMEOUT_MS: u64 = 5000;
+
+/// How many of a pipeline's most recent frames the performance overlay summarizes.
+const FRAME_STATS_WINDOW: usize = 60;
+
+/// How many reports may wait for delivery before the oldest are dropped.
+const MAX_QUEUED_REPORTS: usize = 100;
+
 impl<Message, LTF, STF> Constellation<Message, LTF, STF>
     where LTF: LayoutThreadFactory<Message=Message>,
           STF: ScriptThreadFactory<Message=Message>
@@ -423,6 +482,16 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             let (ipc_panic_sender, ipc_panic_receiver) = ipc::channel().expect("ipc channel failure");
             let panic_receiver = ROUTER.route_ipc_receiver_to_new_mpsc_receiver(ipc_panic_receiver);
 
+            // Pipelines are pinged on a timer, so that a hung pipeline is noticed even when
+            // nothing else is sending the constellation messages. The timer thread exits once
+            // the constellation has gone away.
+            let (ping_timer_sender, ping_timer_receiver) = channel();
+            spawn_named("ConstellationPingTimer".to_owned(), move || {
+                while ping_timer_sender.send(()).is_ok() {
+                    thread::sleep(Duration::from_millis(PING_INTERVAL_MS));
+                }
+            });
+
             let mut constellation: Constellation<Message, LTF, STF> = Constellation {
                 script_sender: ipc_script_sender,
                 layout_sender: ipc_layout_sender,
@@ -431,6 +500,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 compositor_receiver: compositor_receiver,
                 layout_receiver: layout_receiver,
                 panic_receiver: panic_receiver,
+                ping_timer_receiver: ping_timer_receiver,
                 compositor_proxy: state.compositor_proxy,
                 devtools_chan: state.devtools_chan,
                 bluetooth_thread: state.bluetooth_thread,
@@ -444,7 +514,14 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 pending_frames: vec!(),
                 next_pipeline_namespace_id: PipelineNamespaceId(0),
                 root_frame_id: None,
+                auxiliary_frame_ids: vec!(),
                 next_frame_id: FrameId(0),
+                next_speech_recognition_id: SpeechRecognitionId(0),
+                next_ice_gathering_id: IceGatheringId(0),
+                next_ping_id: PingId(0),
+                web_locks: WebLockManager::new(),
+                indexed_dbs: IndexedDbManager::new(),
+                queued_reports: VecDeque::new(),
                 focus_pipeline_id: None,
                 time_profiler_chan: state.time_profiler_chan,
                 mem_profiler_chan: state.mem_profiler_chan,
@@ -509,6 +586,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                     initial_window_size: Option<TypedSize2D<PagePx, f32>>,
                     script_channel: Option<IpcSender<ConstellationControlMsg>>,
                     load_data: LoadData,
+                    preload_response: Option<IpcReceiver<ResponseAction>>,
                     is_private: bool) {
         if self.shutting_down { return; }
 
@@ -542,6 +620,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             window_size: initial_window_size,
             script_chan: script_channel,
             load_data: load_data,
+            preload_response: preload_response,
             device_pixel_ratio: self.window_size.device_pixel_ratio,
             pipeline_namespace_id: self.next_pipeline_namespace_id(),
             parent_visibility: parent_visibility,
@@ -607,6 +686,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             Compositor(FromCompositorMsg),
             Layout(FromLayoutMsg),
             Panic(PanicMsg),
+            PingTimer,
         }
 
         // Get one incoming request.
@@ -625,6 +705,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             let receiver_from_compositor = &self.compositor_receiver;
             let receiver_from_layout = &self.layout_receiver;
             let receiver_from_panic = &self.panic_receiver;
+            let receiver_from_ping_timer = &self.ping_timer_receiver;
             select! {
                 msg = receiver_from_script.recv() =>
                     Request::Script(msg.expect("Unexpected script channel panic in constellation")),
@@ -633,7 +714,11 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 msg = receiver_from_layout.recv() =>
                     Request::Layout(msg.expect("Unexpected layout channel panic in constellation")),
                 msg = receiver_from_panic.recv() =>
-                    Request::Panic(msg.expect("Unexpected panic channel panic in constellation"))
+                    Request::Panic(msg.expect("Unexpected panic channel panic in constellation")),
+                msg = receiver_from_ping_timer.recv() => {
+                    msg.expect("Unexpected ping timer channel panic in constellation");
+                    Request::PingTimer
+                }
             }
         };
 
@@ -650,6 +735,9 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             Request::Panic(message) => {
                 self.handle_request_from_panic(message);
             },
+            Request::PingTimer => {
+                self.ping_pipelines();
+            },
         }
     }
 
@@ -686,7 +774,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             // However, if the id is not encompassed by another change, it will be.
             FromCompositorMsg::LoadUrl(source_id, load_data) => {
                 debug!("constellation got URL load message from compositor");
-                self.handle_load_url_msg(source_id, load_data);
+                self.handle_load_url_msg(source_id, load_data, NavigationReason::Address);
             }
             FromCompositorMsg::IsReadyToSaveImage(pipeline_states) => {
                 let is_ready = self.handle_is_ready_to_save_image(pipeline_states);
@@ -727,13 +815,25 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             FromCompositorMsg::LogEntry(pipeline_id, thread_name, entry) => {
                 self.handle_log_entry(pipeline_id, thread_name, entry);
             }
+            FromCompositorMsg::ActivateAccessKey(pipeline_id, element_id) => {
+                debug!("constellation got activate access key message");
+                self.handle_activate_access_key(pipeline_id, element_id);
+            }
+            FromCompositorMsg::WindowActivated => {
+                debug!("constellation got window activated message");
+                self.handle_window_activated();
+            }
+            FromCompositorMsg::KillPipeline(pipeline_id) => {
+                debug!("constellation got kill pipeline message");
+                self.handle_kill_pipeline(pipeline_id);
+            }
         }
     }
 
     fn handle_request_from_script(&mut self, message: FromScriptMsg) {
         match message {
-            FromScriptMsg::PipelineExited(pipeline_id) => {
-                self.handle_pipeline_exited(pipeline_id);
+            FromScriptMsg::PipelineExited(pipeline_id, reason) => {
+                self.handle_pipeline_exited(pipeline_id, reason);
             }
             FromScriptMsg::ScriptLoadedURLInIFrame(load_info) => {
                 debug!("constellation got iframe URL load message {:?} {:?} {:?}",
@@ -748,9 +848,9 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             // Load a new page from a mouse click
             // If there is already a pending page (self.pending_frames), it will not be overridden;
             // However, if the id is not encompassed by another change, it will be.
-            FromScriptMsg::LoadUrl(source_id, load_data) => {
+            FromScriptMsg::LoadUrl(source_id, load_data, reason) => {
                 debug!("constellation got URL load message from script");
-                self.handle_load_url_msg(source_id, load_data);
+                self.handle_load_url_msg(source_id, load_data, reason);
             }
             // A page loaded has completed all parsing, script, and reflow messages have been sent.
             FromScriptMsg::LoadComplete(pipeline_id) => {
@@ -792,6 +892,22 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 debug!("constellation got focus message");
                 self.handle_focus_msg(pipeline_id);
             }
+            FromScriptMsg::EnsureFocusVisible(pipeline_id, element_id) => {
+                debug!("constellation got ensure focus visible message");
+                self.compositor_proxy.send(ToCompositorMsg::EnsureFocusVisible(pipeline_id, element_id));
+            }
+            FromScriptMsg::SetInitialFocus(pipeline_id, element_id) => {
+                debug!("constellation got set initial focus message");
+                self.handle_set_initial_focus(pipeline_id, element_id);
+            }
+            FromScriptMsg::SetAccessKeys(pipeline_id, access_keys) => {
+                debug!("constellation got set access keys message");
+                self.compositor_proxy.send(ToCompositorMsg::SetAccessKeys(pipeline_id, access_keys));
+            }
+            FromScriptMsg::SpatialNavigate(pipeline_id, direction) => {
+                debug!("constellation got spatial navigate message");
+                self.handle_spatial_navigate(pipeline_id, direction);
+            }
             FromScriptMsg::ForwardMouseButtonEvent(pipeline_id, event_type, button, point) => {
                 let event = CompositorEvent::MouseButtonEvent(event_type, button, point);
                 let msg = ConstellationControlMsg::SendEvent(pipeline_id, event);
@@ -814,27 +930,58 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                     self.handle_send_error(pipeline_id, e);
                 }
             }
+            FromScriptMsg::StartDrag(pipeline_id, data) => {
+                debug!("constellation got start drag message");
+                self.compositor_proxy.send(ToCompositorMsg::StartDrag(pipeline_id, data));
+            }
+            FromScriptMsg::ClearSiteData(pipeline_id, data_types, sender) => {
+                debug!("constellation got clear site data message");
+                self.handle_clear_site_data(pipeline_id, data_types, sender);
+            }
+            FromScriptMsg::SubscribeClipboardChanges(pipeline_id, subscribed) => {
+                debug!("constellation got subscribe clipboard changes message");
+                self.handle_subscribe_clipboard_changes(pipeline_id, subscribed);
+            }
             FromScriptMsg::GetClipboardContents(sender) => {
-                let result = match self.clipboard_ctx {
-                    Some(ref ctx) => match ctx.get_contents() {
-                        Ok(result) => result,
-                        Err(e) => {
-                            warn!("Error getting clipboard contents ({}), defaulting to empty string", e);
-                            "".to_owned()
-                        },
-                    },
-                    None => "".to_owned()
-                };
+                let result = self.get_clipboard_contents();
                 if let Err(e) = sender.send(result) {
                     warn!("Failed to send clipboard ({})", e);
                 }
             }
+            FromScriptMsg::GetClipboardData(mime_type, sanitize, sender) => {
+                debug!("constellation got get clipboard data message");
+                let result = self.get_clipboard_data(&mime_type, sanitize);
+                if let Err(e) = sender.send(result) {
+                    warn!("Failed to send clipboard data ({})", e);
+                }
+            }
             FromScriptMsg::SetClipboardContents(s) => {
                 if let Some(ref mut ctx) = self.clipboard_ctx {
                     if let Err(e) = ctx.set_contents(s) {
                         warn!("Error setting clipboard contents ({})", e);
                     }
                 }
+                self.notify_clipboard_changed();
+            }
+            FromScriptMsg::SetDocumentDomain(pipeline_id, domain) => {
+                debug!("constellation got set document domain message");
+                self.handle_set_document_domain(pipeline_id, domain);
+            }
+            FromScriptMsg::SeverOpener(pipeline_id) => {
+                debug!("constellation got sever opener message");
+                self.handle_sever_opener(pipeline_id);
+            }
+            FromScriptMsg::SetMediaType(pipeline_id, media_type) => {
+                debug!("constellation got set media type message");
+                self.handle_set_media_type(pipeline_id, media_type);
+            }
+            FromScriptMsg::ReportFrameHash(pipeline_id, hash) => {
+                debug!("constellation got frame hash {} for {:?}", hash, pipeline_id);
+                self.handle_report_frame_hash(pipeline_id, hash);
+            }
+            FromScriptMsg::SetNavigationPreload(pipeline_id, enabled, header_value) => {
+                debug!("constellation got set navigation preload message");
+                self.handle_set_navigation_preload(pipeline_id, enabled, header_value);
             }
             FromScriptMsg::SetVisible(pipeline_id, visible) => {
                 debug!("constellation got set visible messsage");
@@ -853,9 +1000,43 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                     }
                 }
             }
-            FromScriptMsg::NewFavicon(url) => {
+            FromScriptMsg::NewFavicon(pipeline_id, url) => {
                 debug!("constellation got new favicon message");
-                self.compositor_proxy.send(ToCompositorMsg::NewFavicon(url));
+                if self.pipeline_is_top_level(pipeline_id) {
+                    self.compositor_proxy.send(ToCompositorMsg::NewFavicon(url));
+                }
+            }
+            FromScriptMsg::SetFullscreenState(pipeline_id, options) => {
+                debug!("constellation got set fullscreen state message from {:?}", pipeline_id);
+                if options.is_some() && !self.has_transient_activation(pipeline_id) {
+                    warn!("Pipeline {:?} requested fullscreen without user activation.", pipeline_id);
+                } else {
+                    self.compositor_proxy.send(ToCompositorMsg::SetFullscreenState(options));
+                }
+            }
+            FromScriptMsg::NotifyUserActivation(pipeline_id) => {
+                debug!("constellation got notify user activation message");
+                self.handle_notify_user_activation(pipeline_id);
+            }
+            FromScriptMsg::CheckAutoplayAllowed(pipeline_id, context, sender) => {
+                debug!("constellation got check autoplay allowed message");
+                self.compositor_proxy.send(ToCompositorMsg::CheckAutoplayAllowed(pipeline_id, context, sender));
+            }
+            FromScriptMsg::RequestStorageAccess(pipeline_id, sender) => {
+                debug!("constellation got request storage access message");
+                if self.has_transient_activation(pipeline_id) {
+                    self.compositor_proxy.send(ToCompositorMsg::RequestStorageAccess(pipeline_id, sender));
+                } else if let Err(e) = sender.send(false) {
+                    warn!("Sending storage access decision failed ({}).", e);
+                }
+            }
+            FromScriptMsg::GetKeyboardLayoutMap(sender) => {
+                debug!("constellation got get keyboard layout map message");
+                self.compositor_proxy.send(ToCompositorMsg::GetKeyboardLayoutMap(sender));
+            }
+            FromScriptMsg::GetStorageBreakdown(pipeline_id, sender) => {
+                debug!("constellation got get storage breakdown message");
+                self.handle_get_storage_breakdown(pipeline_id, sender);
             }
             FromScriptMsg::HeadParsed => {
                 debug!("constellation got head parsed message");
@@ -869,6 +1050,193 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 debug!("constellation got create-WebGL-paint-thread message");
                 self.handle_create_webgl_paint_thread_msg(&size, attributes, sender)
             }
+            FromScriptMsg::LinkHover(pipeline_id, url) => {
+                debug!("constellation got link hover message from {:?}", pipeline_id);
+                self.compositor_proxy.send(ToCompositorMsg::LinkHover(url));
+            }
+            FromScriptMsg::RequestPictureInPicture(pipeline_id, media_id, sender) => {
+                debug!("constellation got request picture-in-picture message");
+                self.compositor_proxy.send(ToCompositorMsg::RequestPictureInPicture(pipeline_id, media_id, sender));
+            }
+            FromScriptMsg::ExitPictureInPicture(pipeline_id, media_id) => {
+                debug!("constellation got exit picture-in-picture message");
+                self.compositor_proxy.send(ToCompositorMsg::ExitPictureInPicture(pipeline_id, media_id));
+            }
+            FromScriptMsg::StartSpeechRecognition(pipeline_id, config, sender) => {
+                debug!("constellation got start speech recognition message");
+                self.handle_start_speech_recognition(pipeline_id, config, sender);
+            }
+            FromScriptMsg::StopSpeechRecognition(recognition_id) => {
+                debug!("constellation got stop speech recognition message");
+                self.compositor_proxy.send(ToCompositorMsg::StopSpeechRecognition(recognition_id));
+            }
+            FromScriptMsg::RequestUserMedia(pipeline_id, constraints, sender) => {
+                debug!("constellation got request user media message");
+                self.compositor_proxy.send(ToCompositorMsg::RequestUserMedia(pipeline_id, constraints, sender));
+            }
+            FromScriptMsg::EnumerateMediaDevices(pipeline_id, sender) => {
+                debug!("constellation got enumerate media devices message");
+                self.compositor_proxy.send(ToCompositorMsg::EnumerateMediaDevices(pipeline_id, sender));
+            }
+            FromScriptMsg::RequestDisplayCapture(pipeline_id, constraints, sender) => {
+                debug!("constellation got request display capture message");
+                self.compositor_proxy.send(ToCompositorMsg::RequestDisplayCapture(pipeline_id, constraints, sender));
+            }
+            FromScriptMsg::GatherIceCandidates(pipeline_id, config, sender) => {
+                debug!("constellation got gather ICE candidates message");
+                self.handle_gather_ice_candidates(pipeline_id, config, sender);
+            }
+            FromScriptMsg::GetMaxTouchPoints(sender) => {
+                debug!("constellation got get max touch points message");
+                self.compositor_proxy.send(ToCompositorMsg::GetMaxTouchPoints(sender));
+            }
+            FromScriptMsg::GetPointerCapabilities(sender) => {
+                debug!("constellation got get pointer capabilities message");
+                self.compositor_proxy.send(ToCompositorMsg::GetPointerCapabilities(sender));
+            }
+            FromScriptMsg::GetDisplayCapabilities(pipeline_id, sender) => {
+                debug!("constellation got get display capabilities message");
+                self.compositor_proxy.send(ToCompositorMsg::GetDisplayCapabilities(pipeline_id, sender));
+            }
+            FromScriptMsg::GetDisplayRefreshRate(pipeline_id, sender) => {
+                debug!("constellation got get display refresh rate message");
+                self.compositor_proxy.send(ToCompositorMsg::GetDisplayRefreshRate(pipeline_id, sender));
+            }
+            FromScriptMsg::GetScreens(pipeline_id, sender) => {
+                debug!("constellation got get screens message");
+                self.compositor_proxy.send(ToCompositorMsg::GetScreens(pipeline_id, sender));
+            }
+            FromScriptMsg::RequestWindowPlacement(pipeline_id, placement) => {
+                debug!("constellation got request window placement message");
+                if self.pipeline_is_top_level(pipeline_id) {
+                    self.compositor_proxy.send(ToCompositorMsg::PlaceWindow(placement));
+                }
+            }
+            FromScriptMsg::DocumentWriteAfterLoad(pipeline_id) => {
+                debug!("constellation got document write after load message");
+                warn!("Pipeline {:?} called document.write after loading, replacing its document.", pipeline_id);
+            }
+            FromScriptMsg::ReportFrameInfo(pipeline_id, frame_info) => {
+                debug!("constellation got report frame info message");
+                self.handle_report_frame_info(pipeline_id, frame_info);
+            }
+            FromScriptMsg::ReportCodeCoverage(pipeline_id, report) => {
+                debug!("constellation got report code coverage message");
+                if let Some(ref chan) = self.devtools_chan {
+                    let msg = ChromeToDevtoolsControlMsg::CodeCoverage(pipeline_id, report.url, report.total_bytes,
+                                                                       report.used_ranges);
+                    if let Err(e) = chan.send(DevtoolsControlMsg::FromChrome(msg)) {
+                        warn!("Sending code coverage to devtools failed ({}).", e);
+                    }
+                }
+            }
+            FromScriptMsg::ReportFrameStats(pipeline_id, frame_stats) => {
+                debug!("constellation got report frame stats message");
+                self.handle_report_frame_stats(pipeline_id, frame_stats);
+            }
+            FromScriptMsg::ResolveLinkForContextMenu(pipeline_id, element_id, sender) => {
+                debug!("constellation got resolve link for context menu message");
+                self.handle_resolve_link_for_context_menu(pipeline_id, element_id, sender);
+            }
+            FromScriptMsg::GetScrollbarPreferences(sender) => {
+                debug!("constellation got get scrollbar preferences message");
+                self.compositor_proxy.send(ToCompositorMsg::GetScrollbarPreferences(sender));
+            }
+            FromScriptMsg::IntegrityCheckFailed(pipeline_id, url, summary) => {
+                debug!("constellation got integrity check failed message");
+                warn!("Pipeline {:?} blocked {} for failing its integrity check ({}).", pipeline_id, url, summary);
+            }
+            FromScriptMsg::NavigationBlocked(pipeline_id, url, reason) => {
+                debug!("constellation got navigation blocked message");
+                self.handle_navigation_blocked(pipeline_id, url, reason);
+            }
+            FromScriptMsg::InterstitialDecision(pipeline_id, proceed) => {
+                debug!("constellation got interstitial decision message");
+                self.handle_interstitial_decision(pipeline_id, proceed);
+            }
+            FromScriptMsg::GetHistoryState(pipeline_id, sender) => {
+                debug!("constellation got get history state message");
+                self.handle_get_history_state(pipeline_id, sender);
+            }
+            FromScriptMsg::GetNavigationState(pipeline_id, sender) => {
+                debug!("constellation got get navigation state message");
+                self.handle_get_navigation_state(pipeline_id, sender);
+            }
+            FromScriptMsg::ReportPageLifecycle(pipeline_id, state) => {
+                debug!("constellation got report page lifecycle message");
+                self.compositor_proxy.send(ToCompositorMsg::PageLifecycleChanged(pipeline_id, state));
+            }
+            FromScriptMsg::SetScrollRestoration(pipeline_id, mode) => {
+                debug!("constellation got set scroll restoration message");
+                self.handle_set_scroll_restoration(pipeline_id, mode);
+            }
+            FromScriptMsg::SaveScrollPosition(pipeline_id, layer_id, point) => {
+                debug!("constellation got save scroll position message");
+                self.handle_save_scroll_position(pipeline_id, layer_id, point);
+            }
+            FromScriptMsg::SameDocumentNavigation(pipeline_id, url, navigation_type) => {
+                debug!("constellation got same document navigation message");
+                self.handle_same_document_navigation(pipeline_id, url, navigation_type);
+            }
+            FromScriptMsg::ResolveNavigationDisposition(pipeline_id, url, disposition) => {
+                debug!("constellation got resolve navigation disposition message");
+                self.handle_resolve_navigation_disposition(pipeline_id, url, disposition);
+            }
+            FromScriptMsg::AcquireWebLock(pipeline_id, name, mode, sender) => {
+                debug!("constellation got acquire web lock message");
+                self.handle_acquire_web_lock(pipeline_id, name, mode, sender);
+            }
+            FromScriptMsg::ReleaseWebLock(pipeline_id, token) => {
+                debug!("constellation got release web lock message");
+                self.web_locks.release(pipeline_id, token);
+            }
+            FromScriptMsg::OpenIndexedDb(pipeline_id, name, version, sender) => {
+                debug!("constellation got open indexed db message");
+                self.handle_open_indexed_db(pipeline_id, name, version, sender);
+            }
+            FromScriptMsg::OpenAuxiliaryBrowsingContext(info, sender) => {
+                debug!("constellation got open auxiliary browsing context message");
+                self.handle_open_auxiliary_browsing_context(info, sender);
+            }
+            FromScriptMsg::FindNamedBrowsingContext(pipeline_id, name, sender) => {
+                debug!("constellation got find named browsing context message");
+                let found = self.find_named_browsing_context(pipeline_id, &name);
+                if let Err(e) = sender.send(found) {
+                    warn!("Sending named browsing context failed ({}).", e);
+                }
+            }
+            FromScriptMsg::QueueReport(pipeline_id, report) => {
+                debug!("constellation got queue report message");
+                self.handle_queue_report(pipeline_id, report);
+            }
+            FromScriptMsg::InterventionReport(pipeline_id, kind, message) => {
+                debug!("constellation got intervention report message");
+                self.handle_intervention_report(pipeline_id, kind, message);
+            }
+            FromScriptMsg::DeprecationReport(pipeline_id, info) => {
+                debug!("constellation got deprecation report message");
+                self.handle_deprecation_report(pipeline_id, info);
+            }
+            FromScriptMsg::ShowVirtualKeyboard(pipeline_id, sender) => {
+                debug!("constellation got show virtual keyboard message");
+                self.compositor_proxy.send(ToCompositorMsg::ShowVirtualKeyboard(pipeline_id, sender));
+            }
+            FromScriptMsg::HideVirtualKeyboard(pipeline_id) => {
+                debug!("constellation got hide virtual keyboard message");
+                self.compositor_proxy.send(ToCompositorMsg::HideVirtualKeyboard(pipeline_id));
+            }
+            FromScriptMsg::SetImeState(pipeline_id, state) => {
+                debug!("constellation got set IME state message");
+                self.compositor_proxy.send(ToCompositorMsg::SetImeState(pipeline_id, state));
+            }
+            FromScriptMsg::LockKeyboard(pipeline_id, codes, sender) => {
+                debug!("constellation got lock keyboard message");
+                self.compositor_proxy.send(ToCompositorMsg::LockKeyboard(pipeline_id, codes, sender));
+            }
+            FromScriptMsg::UnlockKeyboard(pipeline_id) => {
+                debug!("constellation got unlock keyboard message");
+                self.compositor_proxy.send(ToCompositorMsg::UnlockKeyboard(pipeline_id));
+            }
             FromScriptMsg::NodeStatus(message) => {
                 debug!("constellation got NodeStatus message");
                 self.compositor_proxy.send(ToCompositorMsg::Status(message));
@@ -889,6 +1257,11 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                                                                smooth));
             }
 
+            FromScriptMsg::ScrollToTextFragment(pipeline_id, directive) => {
+                debug!("constellation got scroll to text fragment message");
+                self.compositor_proxy.send(ToCompositorMsg::HighlightTextFragment(pipeline_id, directive));
+            }
+
             FromScriptMsg::GetClientWindow(send) => {
                 self.compositor_proxy.send(ToCompositorMsg::GetClientWindow(send));
             }
@@ -901,15 +1274,58 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 self.compositor_proxy.send(ToCompositorMsg::ResizeTo(size));
             }
 
-            FromScriptMsg::Exit => {
-                self.compositor_proxy.send(ToCompositorMsg::Exit);
+            FromScriptMsg::Exit(sender) => {
+                self.compositor_proxy.send(ToCompositorMsg::Exit(sender));
             }
             FromScriptMsg::LogEntry(pipeline_id, thread_name, entry) => {
                 self.handle_log_entry(pipeline_id, thread_name, entry);
             }
 
+            FromScriptMsg::ScriptTimeout(pipeline_id, elapsed) => {
+                debug!("constellation got script timeout message");
+                self.compositor_proxy.send(ToCompositorMsg::ScriptTimeout(pipeline_id, elapsed));
+            }
+
+            FromScriptMsg::Pong(pipeline_id, ping_id) => {
+                self.handle_pong(pipeline_id, ping_id);
+            }
+
+            FromScriptMsg::SetThemeColor(pipeline_id, color) => {
+                debug!("constellation got set theme color message");
+                if self.pipeline_is_top_level(pipeline_id) {
+                    self.compositor_proxy.send(ToCompositorMsg::SetThemeColor(color));
+                }
+            }
+
+            FromScriptMsg::SelectionChanged(pipeline_id, selection) => {
+                debug!("constellation got selection changed message");
+                self.compositor_proxy.send(ToCompositorMsg::ChangeSelection(pipeline_id, selection))
+            }
+
+            FromScriptMsg::SetTooltip(pipeline_id, tooltip) => {
+                debug!("constellation got set tooltip message from {:?}", pipeline_id);
+                self.compositor_proxy.send(ToCompositorMsg::SetTooltip(tooltip))
+            }
+
+            FromScriptMsg::SetAppBadge(pipeline_id, badge) => {
+                debug!("constellation got set app badge message");
+                if self.pipeline_is_top_level(pipeline_id) {
+                    self.compositor_proxy.send(ToCompositorMsg::SetAppBadge(badge));
+                }
+            }
+
             FromScriptMsg::SetTitle(pipeline_id, title) => {
-                self.compositor_proxy.send(ToCompositorMsg::ChangePageTitle(pipeline_id, title))
+                self.handle_set_title(pipeline_id, title);
+            }
+
+            FromScriptMsg::SetDocumentEncoding(pipeline_id, encoding) => {
+                debug!("constellation got set document encoding message");
+                self.compositor_proxy.send(ToCompositorMsg::ChangePageEncoding(pipeline_id, encoding))
+            }
+
+            FromScriptMsg::SetContentType(pipeline_id, content_type) => {
+                debug!("constellation got set content type message");
+                self.compositor_proxy.send(ToCompositorMsg::ChangePageContentType(pipeline_id, content_type))
             }
 
             FromScriptMsg::SendKeyEvent(ch, key, key_state, key_modifiers) => {
@@ -931,9 +1347,26 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             FromLayoutMsg::ChangeRunningAnimationsState(pipeline_id, animation_state) => {
                 self.handle_change_running_animations_state(pipeline_id, animation_state)
             }
+            FromLayoutMsg::SetCompositorAnimations(pipeline_id, animations) => {
+                debug!("constellation got {} compositor animations for {:?}", animations.len(), pipeline_id);
+                self.compositor_proxy.send(ToCompositorMsg::SetCompositorAnimations(pipeline_id, animations))
+            }
+            FromLayoutMsg::SetScrollTimeline(pipeline_id, layer_id, timeline) => {
+                self.compositor_proxy.send(ToCompositorMsg::SetScrollTimeline(pipeline_id, layer_id, timeline))
+            }
+            FromLayoutMsg::ScrollAnchorAdjustment(pipeline_id, layer_id, delta) => {
+                self.compositor_proxy.send(ToCompositorMsg::ScrollAnchorAdjustment(pipeline_id, layer_id, delta))
+            }
             FromLayoutMsg::SetCursor(cursor) => {
                 self.handle_set_cursor_msg(cursor)
             }
+            FromLayoutMsg::SetNamedCursor(pipeline_id, cursor_id, cursor, point) => {
+                self.compositor_proxy.send(ToCompositorMsg::SetNamedCursor(pipeline_id, cursor_id, cursor, point))
+            }
+            FromLayoutMsg::CaptureViewTransition(pipeline_id, sender) => {
+                debug!("constellation got capture view transition message");
+                self.compositor_proxy.send(ToCompositorMsg::CaptureViewTransition(pipeline_id, sender))
+            }
             FromLayoutMsg::ViewportConstrained(pipeline_id, constraints) => {
                 debug!("constellation got viewport-constrained event message");
                 self.handle_viewport_constrained_msg(pipeline_id, constraints);
@@ -959,6 +1392,9 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         if let Some(root_id) = self.root_frame_id {
             self.close_frame(root_id, ExitPipelineMode::Normal);
         }
+        for frame_id in replace(&mut self.auxiliary_frame_ids, vec!()) {
+            self.close_frame(frame_id, ExitPipelineMode::Normal);
+        }
     }
 
     fn handle_shutdown(&mut self) {
@@ -1014,9 +1450,426 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         self.compositor_proxy.send(ToCompositorMsg::ShutdownComplete);
     }
 
-    fn handle_pipeline_exited(&mut self, pipeline_id: PipelineId) {
-        debug!("Pipeline {:?} exited.", pipeline_id);
+    fn handle_pipeline_exited(&mut self, pipeline_id: PipelineId, reason: PipelineExitReason) {
+        debug!("Pipeline {:?} exited ({:?}).", pipeline_id, reason);
         self.pipelines.remove(&pipeline_id);
+        // Windows it opened see `null` as their opener once it is gone.
+        self.replace_opener(pipeline_id, None);
+        self.web_locks.release_all(pipeline_id);
+    }
+
+    fn handle_report_frame_info(&mut self, pipeline_id: PipelineId, frame_info: FrameInfo) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.name = frame_info.name.clone().unwrap_or_else(String::new),
+            None => return warn!("Frame info reported for closed pipeline {:?}.", pipeline_id),
+        }
+        if let Some(ref chan) = self.devtools_chan {
+            let frame_info = DevtoolsFrameInfo {
+                url: frame_info.url,
+                origin: frame_info.origin.0.ascii_serialization(),
+                name: frame_info.name,
+                parent: frame_info.parent,
+                is_cross_origin: frame_info.is_cross_origin,
+            };
+            let msg = ChromeToDevtoolsControlMsg::FrameInfo(pipeline_id, frame_info);
+            if let Err(e) = chan.send(DevtoolsControlMsg::FromChrome(msg)) {
+                warn!("Sending frame info to devtools failed ({}).", e);
+            }
+        }
+    }
+
+    fn handle_report_frame_stats(&mut self, pipeline_id: PipelineId, frame_stats: Vec<FrameStats>) {
+        let pipeline = match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline,
+            None => return warn!("Frame stats reported for closed pipeline {:?}.", pipeline_id),
+        };
+        let mut summary = None;
+        for frame_stats in frame_stats {
+            summary = Some(pipeline.add_frame_stats(frame_stats, FRAME_STATS_WINDOW));
+        }
+        if let Some(summary) = summary {
+            self.compositor_proxy.send(ToCompositorMsg::UpdatePerformanceOverlay(pipeline_id, summary));
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#activation-notification
+    fn handle_notify_user_activation(&mut self, pipeline_id: PipelineId) {
+        let now = precise_time_ms().get();
+        let mut current = Some(pipeline_id);
+        while let Some(pipeline_id) = current {
+            current = match self.pipelines.get_mut(&pipeline_id) {
+                Some(pipeline) => {
+                    pipeline.last_activation = Some(now);
+                    pipeline.parent_info.map(|(parent_id, _, _)| parent_id)
+                }
+                None => None,
+            };
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#transient-activation
+    fn has_transient_activation(&self, pipeline_id: PipelineId) -> bool {
+        let now = precise_time_ms().get();
+        self.pipelines.get(&pipeline_id)
+                      .and_then(|pipeline| pipeline.last_activation)
+                      .map_or(false, |activated_at| now < activated_at + USER_ACTIVATION_DURATION_MS)
+    }
+
+    fn handle_navigation_blocked(&mut self, pipeline_id: PipelineId, url: Url, reason: BlockReason) {
+        warn!("Navigation of pipeline {:?} to {} blocked ({:?}).", pipeline_id, url, reason);
+        self.close_pending_frames_replacing(pipeline_id);
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.blocked_navigation = Some(url.clone()),
+            None => return warn!("Pipeline {:?} navigation blocked after closure.", pipeline_id),
+        }
+        self.compositor_proxy.send(ToCompositorMsg::ShowNavigationBlocked(pipeline_id, url, reason));
+    }
+
+    fn handle_interstitial_decision(&mut self, pipeline_id: PipelineId, proceed: bool) {
+        let blocked_url = match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.blocked_navigation.take(),
+            None => return warn!("Pipeline {:?} interstitial decision after closure.", pipeline_id),
+        };
+        let blocked_url = match blocked_url {
+            Some(blocked_url) => blocked_url,
+            None => return warn!("Pipeline {:?} interstitial decision without an interstitial.", pipeline_id),
+        };
+        self.compositor_proxy.send(ToCompositorMsg::HideInterstitial(pipeline_id));
+        if proceed {
+            debug!("User proceeded to {} in pipeline {:?}.", blocked_url, pipeline_id);
+            // Proceeding is the user's own choice to go to the URL.
+            self.load_url(pipeline_id, LoadData::new(blocked_url, None, None), NavigationReason::Address);
+        }
+    }
+
+    fn handle_get_history_state(&self, pipeline_id: PipelineId, sender: IpcSender<HistoryState>) {
+        let (previous, next) = match self.session_history_entries(pipeline_id) {
+            Some(entries) => entries,
+            None => return warn!("Pipeline {:?} asked for history state after closure.", pipeline_id),
+        };
+        let state = HistoryState {
+            length: (previous + 1 + next) as u32,
+            current_index: previous as u32,
+        };
+        if let Err(e) = sender.send(state) {
+            warn!("Sending history state failed ({}).", e);
+        }
+    }
+
+    fn handle_get_navigation_state(&self, pipeline_id: PipelineId, sender: IpcSender<NavigationCapability>) {
+        let (previous, next) = match self.session_history_entries(pipeline_id) {
+            Some(entries) => entries,
+            None => return warn!("Pipeline {:?} asked for navigation state after closure.", pipeline_id),
+        };
+        let capability = NavigationCapability {
+            can_go_back: previous > 0,
+            can_go_forward: next > 0,
+        };
+        if let Err(e) = sender.send(capability) {
+            warn!("Sending navigation state failed ({}).", e);
+        }
+    }
+
+    // Counts the entries before and after the current entry of the joint session history, as
+    // far as the given pipeline may see it: a pipeline whose origin differs from the top-level
+    // document's only sees its own frame's entries.
+    // https://html.spec.whatwg.org/multipage/#joint-session-history
+    fn session_history_entries(&self, pipeline_id: PipelineId) -> Option<(usize, usize)> {
+        let (frame_id, origin) = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => (pipeline.frame, pipeline.url.origin()),
+            None => return None,
+        };
+        let root_origin = self.root_frame_id
+            .and_then(|root_frame_id| self.frames.get(&root_frame_id))
+            .and_then(|root_frame| self.pipelines.get(&root_frame.current))
+            .map(|root_pipeline| root_pipeline.url.origin());
+
+        let frames: Vec<&Frame> = if root_origin == Some(origin) {
+            self.current_frame_tree_iter(self.root_frame_id).collect()
+        } else {
+            frame_id.and_then(|frame_id| self.frames.get(&frame_id)).into_iter().collect()
+        };
+        // Every frame's previous and next documents are entries, besides the current entry.
+        let previous = frames.iter().map(|frame| frame.prev.len()).sum::<usize>();
+        let next = frames.iter().map(|frame| frame.next.len()).sum::<usize>();
+        Some((previous, next))
+    }
+
+    fn handle_set_scroll_restoration(&mut self, pipeline_id: PipelineId, mode: ScrollRestorationMode) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.scroll_restoration = mode,
+            None => warn!("Pipeline {:?} set scroll restoration after closure.", pipeline_id),
+        }
+    }
+
+    fn handle_save_scroll_position(&mut self, pipeline_id: PipelineId, layer_id: LayerId, point: Point2D<f32>) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.saved_scroll_position = Some((layer_id, point)),
+            None => warn!("Pipeline {:?} saved scroll position after closure.", pipeline_id),
+        }
+    }
+
+    fn handle_same_document_navigation(&mut self,
+                                       pipeline_id: PipelineId,
+                                       url: Url,
+                                       navigation_type: NavigationType) {
+        debug!("Pipeline {:?} navigated within its document to {} ({:?}).", pipeline_id, url, navigation_type);
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.url = url.clone(),
+            None => return warn!("Pipeline {:?} navigated within its document after closure.", pipeline_id),
+        }
+        // Only the top-level document's URL is shown in the address bar.
+        let root_pipeline_id = self.root_frame_id
+            .and_then(|root_frame_id| self.frames.get(&root_frame_id))
+            .map(|root_frame| root_frame.current);
+        if root_pipeline_id == Some(pipeline_id) {
+            self.compositor_proxy.send(ToCompositorMsg::ChangePageUrl(pipeline_id, url));
+        }
+    }
+
+    fn handle_resolve_navigation_disposition(&mut self,
+                                             pipeline_id: PipelineId,
+                                             url: Url,
+                                             disposition: NavigationDisposition) {
+        match disposition {
+            NavigationDisposition::Navigate => return,
+            NavigationDisposition::Download => self.compositor_proxy.send(ToCompositorMsg::Download(url)),
+            NavigationDisposition::Ignore => debug!("Ignoring the response to the navigation to {}.", url),
+        }
+        // The response does not replace the current document, so the pipeline that was created to
+        // load it is dropped. Initial loads have no document to keep, and carry on regardless.
+        let replaces_document = self.pending_frames.iter().any(|frame| {
+            frame.new_pipeline_id == pipeline_id && frame.old_pipeline_id.is_some()
+        });
+        if replaces_document {
+            self.close_pipeline(pipeline_id, ExitPipelineMode::Normal);
+        }
+    }
+
+    // https://w3c.github.io/IndexedDB/#open-a-database
+    fn handle_acquire_web_lock(&mut self,
+                               pipeline_id: PipelineId,
+                               name: String,
+                               mode: WebLockMode,
+                               sender: IpcSender<WebLockGrant>) {
+        let origin = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => Origin::new(&pipeline.url),
+            None => return warn!("Pipeline {:?} requested a web lock after closure.", pipeline_id),
+        };
+        self.web_locks.acquire(pipeline_id, origin, name, mode, sender);
+    }
+
+    fn handle_open_indexed_db(&mut self,
+                              pipeline_id: PipelineId,
+                              name: String,
+                              version: u64,
+                              sender: IpcSender<Result<IdbConnectionId, String>>) {
+        let origin = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => Origin::new(&pipeline.url),
+            None => return warn!("Pipeline {:?} opened an IndexedDB database after closure.", pipeline_id),
+        };
+        let result = self.indexed_dbs.open(origin, name, version);
+        if let Err(e) = sender.send(result) {
+            warn!("Sending IndexedDB connection failed ({}).", e);
+        }
+    }
+
+    fn handle_open_auxiliary_browsing_context(&mut self,
+                                              info: AuxiliaryContextInfo,
+                                              sender: IpcSender<PipelineId>) {
+        let (window_size, is_private) = match self.pipelines.get(&info.opener) {
+            Some(opener) => (opener.size, opener.is_private),
+            None => return warn!("Pipeline {:?} opened a window after closure.", info.opener),
+        };
+
+        // The new context gets a frame tree of its own straight away, so that activating its
+        // document does not make it the root frame in place of its opener's page.
+        let pipeline_id = PipelineId::new();
+        self.new_pipeline(pipeline_id, None, window_size, None, info.url, None, is_private);
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => {
+                pipeline.name = info.name;
+                pipeline.opener = if info.noopener { None } else { Some(info.opener) };
+            }
+            // Spawning failed, and dropping the sender tells script that no window was opened.
+            None => return,
+        }
+        let frame_id = self.new_frame(pipeline_id);
+        self.auxiliary_frame_ids.push(frame_id);
+
+        self.compositor_proxy.send(ToCompositorMsg::AuxiliaryBrowsingContextOpened(pipeline_id, info.features));
+        if let Err(e) = sender.send(pipeline_id) {
+            warn!("Sending auxiliary browsing context failed ({}).", e);
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/#the-rules-for-choosing-a-browsing-context-given-a-browsing-context-name
+    fn find_named_browsing_context(&self, pipeline_id: PipelineId, name: &str) -> Option<PipelineId> {
+        let parent_id = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline.parent_info.map(|(parent_id, _, _)| parent_id),
+            None => {
+                warn!("Pipeline {:?} looked up a browsing context after closure.", pipeline_id);
+                return None;
+            }
+        };
+        match &*name.to_ascii_lowercase() {
+            "" | "_self" => return Some(pipeline_id),
+            "_parent" => return Some(parent_id.unwrap_or(pipeline_id)),
+            "_top" => {
+                return self.root_frame_id
+                    .and_then(|root_frame_id| self.frames.get(&root_frame_id))
+                    .map(|root_frame| root_frame.current)
+            }
+            "_blank" => return None,
+            _ => {}
+        }
+        self.top_level_frame_ids().into_iter()
+            .flat_map(|frame_id| self.current_frame_tree_iter(Some(frame_id)))
+            .map(|frame| frame.current)
+            .find(|id| {
+                self.pipelines.get(id).map_or(false, |pipeline| pipeline.name == name) &&
+                    self.is_familiar_with(pipeline_id, *id)
+            })
+    }
+
+    // A pipeline may look up the name of a context that has a document, or an ancestor with a
+    // document, of the same origin as its own, and of its top-level context. Documents that
+    // relaxed their origin through `document.domain` are compared by that domain instead.
+    // https://html.spec.whatwg.org/multipage/#familiar-with
+    fn is_familiar_with(&self, pipeline_id: PipelineId, other_pipeline_id: PipelineId) -> bool {
+        let source = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline,
+            None => return false,
+        };
+        let mut current = Some(other_pipeline_id);
+        while let Some(id) = current {
+            let pipeline = match self.pipelines.get(&id) {
+                Some(pipeline) => pipeline,
+                None => return false,
+            };
+            if pipeline.is_same_origin_domain(source) {
+                return true;
+            }
+            current = pipeline.parent_info.map(|(parent_id, _, _)| parent_id);
+        }
+        // Every context shares a single top-level context.
+        self.pipeline_is_top_level(other_pipeline_id)
+    }
+
+    // TODO: Deliver the queued reports to the endpoints of the origin's `Reporting-Endpoints`
+    // header, once that header is parsed.
+    // https://w3c.github.io/reporting/#deliver-reports
+    fn handle_queue_report(&mut self, pipeline_id: PipelineId, report: ReportBody) {
+        // VecDeque::truncate is unstable
+        if MAX_QUEUED_REPORTS <= self.queued_reports.len() {
+            self.queued_reports.pop_front();
+        }
+        self.queued_reports.push_back((pipeline_id, report));
+    }
+
+    fn handle_intervention_report(&mut self, pipeline_id: PipelineId, kind: InterventionKind, message: String) {
+        if let Some(ref chan) = self.devtools_chan {
+            let console_message = ConsoleMessage {
+                message: message.clone(),
+                logLevel: DevtoolsLogLevel::Info,
+                filename: String::new(),
+                lineNumber: 0,
+                columnNumber: 0,
+            };
+            let msg = ScriptToDevtoolsControlMsg::ConsoleAPI(pipeline_id, console_message, None);
+            if let Err(e) = chan.send(DevtoolsControlMsg::FromScript(msg)) {
+                warn!("Sending intervention to devtools failed ({}).", e);
+            }
+        }
+        let report = ReportBody::Intervention {
+            id: String::from(kind.id()),
+            message: message,
+            source: None,
+        };
+        self.handle_queue_report(pipeline_id, report);
+    }
+
+    fn handle_deprecation_report(&mut self, pipeline_id: PipelineId, info: DeprecationInfo) {
+        if let Some(ref chan) = self.devtools_chan {
+            let (filename, line, column) = match info.source {
+                Some(ref source) => (source.url.to_string(), source.line as usize, source.column as usize),
+                None => (String::new(), 0, 0),
+            };
+            let console_message = ConsoleMessage {
+                message: info.message.clone(),
+                logLevel: DevtoolsLogLevel::Warn,
+                filename: filename,
+                lineNumber: line,
+                columnNumber: column,
+            };
+            let msg = ScriptToDevtoolsControlMsg::ConsoleAPI(pipeline_id, console_message, None);
+            if let Err(e) = chan.send(DevtoolsControlMsg::FromScript(msg)) {
+                warn!("Sending deprecation to devtools failed ({}).", e);
+            }
+        }
+        let report = ReportBody::Deprecation {
+            id: info.feature_id,
+            message: info.message,
+            source: info.source,
+            anticipated_removal: info.anticipated_removal,
+        };
+        self.handle_queue_report(pipeline_id, report);
+    }
+
+    fn handle_pong(&mut self, pipeline_id: PipelineId, ping_id: PingId) {
+        let pipeline = match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline,
+            None => return debug!("Pipeline {:?} answered a ping after closure.", pipeline_id),
+        };
+        match pipeline.pending_ping {
+            Some((pending_id, sent_at)) if pending_id == ping_id => {
+                debug!("Pipeline {:?} answered a ping in {}ms.", pipeline_id, precise_time_ms().get() - sent_at);
+            }
+            _ => return warn!("Pipeline {:?} answered a ping it was not sent.", pipeline_id),
+        }
+        pipeline.pending_ping = None;
+        if pipeline.unresponsive {
+            pipeline.unresponsive = false;
+            self.compositor_proxy.send(ToCompositorMsg::SetPipelineResponsive(pipeline_id, true));
+        }
+    }
+
+    fn handle_kill_pipeline(&mut self, pipeline_id: PipelineId) {
+        let (parent_info, window_size) = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => (pipeline.parent_info, pipeline.size),
+            None => return warn!("Pipeline {:?} killed after closure.", pipeline_id),
+        };
+
+        // The script thread may be stuck, so it is neither waited for nor asked to confirm
+        // that the pipeline exited.
+        self.close_pipeline(pipeline_id, ExitPipelineMode::Force);
+        self.handle_pipeline_exited(pipeline_id, PipelineExitReason::Killed);
+        self.close_pending_frames_replacing(pipeline_id);
+
+        self.load_failure_page(pipeline_id, parent_info, window_size);
+    }
+
+    /// Abandons any pending navigations away from a pipeline that failed or was killed.
+    fn close_pending_frames_replacing(&mut self, pipeline_id: PipelineId) {
+        while let Some(pending_pipeline_id) = self.pending_frames.iter().find(|pending| {
+            pending.old_pipeline_id == Some(pipeline_id)
+        }).map(|frame| frame.new_pipeline_id) {
+            warn!("removing pending frame change for failed pipeline");
+            self.close_pipeline(pending_pipeline_id, ExitPipelineMode::Force);
+        }
+    }
+
+    /// Replaces a pipeline that failed or was killed with `about:failure`.
+    fn load_failure_page(&mut self,
+                         pipeline_id: PipelineId,
+                         parent_info: Option<(PipelineId, SubpageId, FrameType)>,
+                         window_size: Option<TypedSize2D<PagePx, f32>>) {
+        let failure_url = Url::parse("about:failure").expect("infallible");
+        let new_pipeline_id = PipelineId::new();
+        let load_data = LoadData::new(failure_url, None, None);
+        self.new_pipeline(new_pipeline_id, parent_info, window_size, None, load_data, None, false);
+
+        self.push_pending_frame(new_pipeline_id, Some(pipeline_id));
     }
 
     fn handle_send_error(&mut self, pipeline_id: PipelineId, err: IOError) {
@@ -1053,13 +1906,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
 
             self.close_pipeline(pipeline_id, ExitPipelineMode::Force);
             self.pipelines.remove(&pipeline_id);
-
-            while let Some(pending_pipeline_id) = self.pending_frames.iter().find(|pending| {
-                pending.old_pipeline_id == Some(pipeline_id)
-            }).map(|frame| frame.new_pipeline_id) {
-                warn!("removing pending frame change for failed pipeline");
-                self.close_pipeline(pending_pipeline_id, ExitPipelineMode::Force);
-            }
+            self.close_pending_frames_replacing(pipeline_id);
 
             let failure_url = Url::parse("about:failure").expect("infallible");
 
@@ -1071,12 +1918,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
 
             warn!("creating replacement pipeline for about:failure");
 
-            let new_pipeline_id = PipelineId::new();
-            let load_data = LoadData::new(failure_url, None, None);
-            self.new_pipeline(new_pipeline_id, parent_info, window_size, None, load_data, false);
-
-            self.push_pending_frame(new_pipeline_id, Some(pipeline_id));
-
+            self.load_failure_page(pipeline_id, parent_info, window_size);
         }
 
         self.handled_panic = true;
@@ -1102,7 +1944,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         let root_pipeline_id = PipelineId::new();
         debug_assert!(PipelineId::fake_root_pipeline_id() == root_pipeline_id);
         self.new_pipeline(root_pipeline_id, None, Some(window_size), None,
-                          LoadData::new(url.clone(), None, None), false);
+                          LoadData::new(url.clone(), None, None), None, false);
         self.handle_load_start_msg(&root_pipeline_id);
         self.push_pending_frame(root_pipeline_id, None);
         self.compositor_proxy.send(ToCompositorMsg::ChangePageUrl(root_pipeline_id, url));
@@ -1227,6 +2069,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                           window_size,
                           script_chan,
                           load_data,
+                          None,
                           is_private);
 
         self.subpage_map.insert((load_info.containing_pipeline_id, load_info.new_subpage_id),
@@ -1306,11 +2149,232 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         }
     }
 
-    fn handle_load_url_msg(&mut self, source_id: PipelineId, load_data: LoadData) {
-        self.load_url(source_id, load_data);
+    fn handle_load_url_msg(&mut self, source_id: PipelineId, load_data: LoadData, reason: NavigationReason) {
+        self.load_url(source_id, load_data, reason);
+    }
+
+    fn handle_get_storage_breakdown(&mut self, pipeline_id: PipelineId, sender: IpcSender<StorageBreakdown>) {
+        let url = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline.url.clone(),
+            None => return warn!("Pipeline {:?} asked for its storage after closure.", pipeline_id),
+        };
+        // Servo has no Cache API, IndexedDB or service worker storage yet, so `localStorage`
+        // is the only backend that can be holding any data. The storage thread's reply is
+        // turned into the breakdown on the router thread, so the constellation never waits on it.
+        let (size_sender, size_receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        ROUTER.add_route(size_receiver.to_opaque(), box move |message| {
+            let breakdown = StorageBreakdown {
+                localstorage_bytes: message.to::<usize>().unwrap_or(0) as u64,
+                ..StorageBreakdown::default()
+            };
+            if let Err(e) = sender.send(breakdown) {
+                warn!("Sending storage breakdown failed ({}).", e);
+            }
+        });
+        let msg = StorageThreadMsg::Size(size_sender, url, StorageType::Local);
+        if let Err(e) = self.public_resource_threads.send(msg) {
+            warn!("Sending storage size request failed ({}).", e);
+        }
+    }
+
+    fn handle_clear_site_data(&mut self, pipeline_id: PipelineId, data_types: ClearDataTypes,
+                              sender: IpcSender<()>) {
+        let url = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline.url.clone(),
+            None => return warn!("Pipeline {:?} cleared site data after closure.", pipeline_id),
+        };
+        // Servo has no Cache API or service worker storage yet, so there is nothing to
+        // remove for `CLEAR_CACHE` or `CLEAR_SERVICE_WORKERS`. Each removal acknowledges
+        // itself on `done_sender`, and the router thread replies once the last one is in,
+        // so the constellation never waits on the resource threads.
+        let (done_sender, done_receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let mut pending = 0;
+        if data_types.contains(CLEAR_COOKIES) {
+            let msg = net_traits::CoreResourceMsg::DeleteCookiesForUrl(url.clone(), done_sender.clone());
+            match self.public_resource_threads.send(msg) {
+                Ok(()) => pending += 1,
+                Err(e) => warn!("Sending cookie removal failed ({}).", e),
+            }
+        }
+        if data_types.contains(CLEAR_STORAGE) {
+            for storage_type in &[StorageType::Local, StorageType::Session] {
+                let (cleared_sender, cleared_receiver) = ipc::channel().expect("Failed to create IPC channel!");
+                let done_sender = done_sender.clone();
+                ROUTER.add_route(cleared_receiver.to_opaque(), box move |_| {
+                    let _ = done_sender.send(());
+                });
+                let msg = StorageThreadMsg::Clear(cleared_sender, url.clone(), *storage_type);
+                match self.public_resource_threads.send(msg) {
+                    Ok(()) => pending += 1,
+                    Err(e) => warn!("Sending storage removal failed ({}).", e),
+                }
+            }
+        }
+        if pending == 0 {
+            if let Err(e) = sender.send(()) {
+                warn!("Error replying to clear site data ({})", e);
+            }
+            return;
+        }
+        ROUTER.add_route(done_receiver.to_opaque(), box move |_| {
+            pending -= 1;
+            if pending == 0 {
+                if let Err(e) = sender.send(()) {
+                    warn!("Error replying to clear site data ({})", e);
+                }
+            }
+        });
+    }
+
+    fn get_clipboard_contents(&self) -> String {
+        match self.clipboard_ctx {
+            Some(ref ctx) => match ctx.get_contents() {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!("Error getting clipboard contents ({}), defaulting to empty string", e);
+                    "".to_owned()
+                },
+            },
+            None => "".to_owned()
+        }
+    }
+
+    fn get_clipboard_data(&self, mime_type: &str, sanitize: bool) -> Option<String> {
+        // The clipboard crate only exposes a single text flavor, which we hand out for both
+        // plain text and HTML.
+        let contents = self.get_clipboard_contents();
+        match mime_type {
+            "text/plain" => Some(contents),
+            "text/html" if sanitize => Some(sanitize_html(&contents)),
+            "text/html" => Some(contents),
+            _ => None,
+        }
+    }
+
+    fn handle_subscribe_clipboard_changes(&mut self, pipeline_id: PipelineId, subscribed: bool) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.clipboard_change_subscribed = subscribed,
+            None => warn!("Pipeline {:?} subscribed to clipboard changes after closure.", pipeline_id),
+        }
+    }
+
+    fn notify_clipboard_changed(&mut self) {
+        let subscribed: Vec<PipelineId> = self.pipelines.values()
+            .filter(|pipeline| pipeline.clipboard_change_subscribed)
+            .map(|pipeline| pipeline.id)
+            .collect();
+        for pipeline_id in subscribed {
+            let msg = ConstellationControlMsg::ClipboardChanged(pipeline_id);
+            let result = match self.pipelines.get(&pipeline_id) {
+                Some(pipeline) => pipeline.script_chan.send(msg),
+                None => continue,
+            };
+            if let Err(e) = result {
+                self.handle_send_error(pipeline_id, e);
+            }
+        }
+    }
+
+    fn handle_set_document_domain(&mut self, pipeline_id: PipelineId, domain: Option<String>) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.document_domain = domain,
+            None => warn!("Pipeline {:?} set its document domain after closure.", pipeline_id),
+        }
+    }
+
+    fn handle_set_initial_focus(&mut self, pipeline_id: PipelineId, element_id: Option<ElementId>) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.initial_focus = element_id.clone(),
+            None => return warn!("Pipeline {:?} set its initial focus after closure.", pipeline_id),
+        }
+        self.compositor_proxy.send(ToCompositorMsg::SetInitialFocus(pipeline_id, element_id));
+    }
+
+    fn handle_sever_opener(&mut self, pipeline_id: PipelineId) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.opener = None,
+            None => return warn!("Pipeline {:?} severed its opener after closure.", pipeline_id),
+        }
+        self.replace_opener(pipeline_id, None);
+    }
+
+    // Makes the windows opened by the given pipeline refer to another opener, or to none.
+    fn replace_opener(&mut self, opener_id: PipelineId, new_opener_id: Option<PipelineId>) {
+        for pipeline in self.pipelines.values_mut() {
+            if pipeline.opener == Some(opener_id) {
+                pipeline.opener = new_opener_id;
+            }
+        }
+    }
+
+    fn handle_set_media_type(&mut self, pipeline_id: PipelineId, media_type: MediaType) {
+        if !self.pipelines.contains_key(&pipeline_id) {
+            return warn!("Pipeline {:?} set its media type after closure.", pipeline_id);
+        }
+        self.compositor_proxy.send(ToCompositorMsg::MediaTypeChanged(pipeline_id, media_type));
+    }
+
+    fn handle_report_frame_hash(&mut self, pipeline_id: PipelineId, hash: u64) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.set_frame_hash(hash),
+            None => warn!("Pipeline {:?} reported a frame hash after closure.", pipeline_id),
+        }
+    }
+
+    fn handle_start_speech_recognition(&mut self,
+                                       pipeline_id: PipelineId,
+                                       config: SpeechRecognitionConfig,
+                                       sender: IpcSender<SpeechRecognitionId>) {
+        let recognition_id = self.next_speech_recognition_id;
+        let SpeechRecognitionId(ref mut i) = self.next_speech_recognition_id;
+        *i += 1;
+
+        if let Err(e) = sender.send(recognition_id) {
+            return warn!("Sending speech recognition id failed ({}).", e);
+        }
+        let msg = ToCompositorMsg::StartSpeechRecognition(pipeline_id, recognition_id, config);
+        self.compositor_proxy.send(msg);
+    }
+
+    fn handle_gather_ice_candidates(&mut self,
+                                    pipeline_id: PipelineId,
+                                    config: IceConfig,
+                                    sender: IpcSender<IceGatheringId>) {
+        let gathering_id = self.next_ice_gathering_id;
+        let IceGatheringId(ref mut i) = self.next_ice_gathering_id;
+        *i += 1;
+
+        if let Err(e) = sender.send(gathering_id) {
+            return warn!("Sending ICE gathering id failed ({}).", e);
+        }
+        let msg = ToCompositorMsg::GatherIceCandidates(pipeline_id, gathering_id, config);
+        self.compositor_proxy.send(msg);
+    }
+
+    fn handle_set_navigation_preload(&mut self,
+                                     pipeline_id: PipelineId,
+                                     enabled: bool,
+                                     header_value: Option<String>) {
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => {
+                pipeline.navigation_preload = if enabled {
+                    Some(header_value.unwrap_or_else(|| "true".to_owned()))
+                } else {
+                    None
+                };
+            }
+            None => warn!("Pipeline {:?} set navigation preload after closure.", pipeline_id),
+        }
     }
 
-    fn load_url(&mut self, source_id: PipelineId, load_data: LoadData) -> Option<PipelineId> {
+    fn load_url(&mut self,
+                source_id: PipelineId,
+                mut load_data: LoadData,
+                reason: NavigationReason)
+                -> Option<PipelineId> {
+        let navigation_preload = self.pipelines.get(&source_id)
+                                               .and_then(|source| source.navigation_preload.clone());
+
         // If this load targets an iframe, its framing element may exist
         // in a separate script thread than the framed document that initiated
         // the new load. The framing element must be notified about the
@@ -1319,9 +2383,15 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         match parent_info {
             Some((parent_pipeline_id, subpage_id, _)) => {
                 self.handle_load_start_msg(&source_id);
+                // The parent's script thread fetches the frame's new page as soon as it gets
+                // this message, so there is nothing to start early; the header still tells the
+                // server that this is a preload.
+                if let Some(header_value) = navigation_preload {
+                    load_data.headers.set_raw("Service-Worker-Navigation-Preload", vec![header_value.into_bytes()]);
+                }
                 // Message the constellation to find the script thread for this iframe
                 // and issue an iframe load through there.
-                let msg = ConstellationControlMsg::Navigate(parent_pipeline_id, subpage_id, load_data);
+                let msg = ConstellationControlMsg::Navigate(parent_pipeline_id, subpage_id, load_data, reason);
                 let result = match self.pipelines.get(&parent_pipeline_id) {
                     Some(parent_pipeline) => parent_pipeline.script_chan.send(msg),
                     None => {
@@ -1358,7 +2428,13 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 // Create the new pipeline
                 let window_size = self.pipelines.get(&source_id).and_then(|source| source.size);
                 let new_pipeline_id = PipelineId::new();
-                self.new_pipeline(new_pipeline_id, None, window_size, None, load_data, false);
+                let preload_response = navigation_preload.and_then(|header_value| {
+                    self.start_navigation_preload(new_pipeline_id, &load_data, header_value)
+                });
+                self.new_pipeline(new_pipeline_id, None, window_size, None, load_data, preload_response, false);
+                if let Some(new_pipeline) = self.pipelines.get_mut(&new_pipeline_id) {
+                    new_pipeline.navigation_reason = Some(reason);
+                }
                 self.push_pending_frame(new_pipeline_id, Some(source_id));
 
                 // Send message to ScriptThread that will suspend all timers
@@ -1371,6 +2447,38 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         }
     }
 
+    /// Starts fetching the page of a top-level navigation with the `Service-Worker-Navigation-Preload`
+    /// header, in parallel with setting up the new pipeline's threads. The new script thread then
+    /// reads the response from the returned receiver instead of starting the fetch itself.
+    fn start_navigation_preload(&self,
+                                pipeline_id: PipelineId,
+                                load_data: &LoadData,
+                                header_value: String)
+                                -> Option<IpcReceiver<ResponseAction>> {
+        let mut headers = load_data.headers.clone();
+        headers.set_raw("Service-Worker-Navigation-Preload", vec![header_value.into_bytes()]);
+        let (action_sender, action_receiver) = ipc::channel().expect("Failed to create IPC channel!");
+        let msg = CoreResourceMsg::Load(NetLoadData {
+            context: LoadContext::Browsing,
+            url: load_data.url.clone(),
+            method: load_data.method.clone(),
+            headers: Headers::new(),
+            preserved_headers: headers,
+            data: load_data.data.clone(),
+            cors: None,
+            pipeline_id: Some(pipeline_id),
+            credentials_flag: true,
+            referrer_policy: load_data.referrer_policy.clone(),
+            referrer_url: load_data.referrer_url.clone(),
+            source: RequestSource::None,
+        }, LoadConsumer::Listener(AsyncResponseTarget { sender: action_sender }), None);
+        if let Err(e) = self.public_resource_threads.send(msg) {
+            warn!("Sending navigation preload request failed ({}).", e);
+            return None;
+        }
+        Some(action_receiver)
+    }
+
     fn handle_load_start_msg(&mut self, pipeline_id: &PipelineId) {
         if let Some(frame_id) = self.pipelines.get(pipeline_id).and_then(|pipeline| pipeline.frame) {
             if let Some(frame) = self.frames.get(&frame_id) {
@@ -1492,6 +2600,18 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         self.revoke_paint_permission(prev_pipeline_id);
         self.send_frame_tree_and_grant_paint_permission();
 
+        // Restore the scroll position saved when the new pipeline was navigated away from,
+        // unless the page asked to restore it itself.
+        // https://html.spec.whatwg.org/multipage/#restore-persisted-user-state
+        if let Some(next_pipeline) = self.pipelines.get(&next_pipeline_id) {
+            if next_pipeline.scroll_restoration == ScrollRestorationMode::Auto {
+                if let Some((layer_id, point)) = next_pipeline.saved_scroll_position {
+                    let msg = ToCompositorMsg::ScrollFragmentPoint(next_pipeline_id, layer_id, point, false);
+                    self.compositor_proxy.send(msg);
+                }
+            }
+        }
+
         // Update the owning iframe to point to the new subpage id.
         // This makes things like contentDocument work correctly.
         if let Some((parent_pipeline_id, subpage_id)) = pipeline_info {
@@ -1566,10 +2686,95 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         }
     }
 
+    fn handle_activate_access_key(&mut self, pipeline_id: PipelineId, element_id: ElementId) {
+        let msg = ConstellationControlMsg::ActivateAccessKey(pipeline_id, element_id);
+        let result = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline.script_chan.send(msg),
+            None => return warn!("Pipeline {:?} got access key activation after closure.", pipeline_id),
+        };
+        if let Err(e) = result {
+            self.handle_send_error(pipeline_id, e);
+        }
+    }
+
+    // Gives focus back to the initial focus of the focused pipeline, or of the root frame's
+    // pipeline, if the document has not focused anything else since.
+    fn handle_window_activated(&mut self) {
+        let root_pipeline_id = self.root_frame_id
+            .and_then(|root_frame_id| self.frames.get(&root_frame_id))
+            .map(|root_frame| root_frame.current);
+        let pipeline_id = match self.focus_pipeline_id.or(root_pipeline_id) {
+            Some(pipeline_id) => pipeline_id,
+            None => return,
+        };
+        let result = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => match pipeline.initial_focus {
+                Some(ref element_id) => {
+                    let msg = ConstellationControlMsg::RestoreFocus(pipeline_id, element_id.clone());
+                    pipeline.script_chan.send(msg)
+                }
+                None => return,
+            },
+            None => return warn!("Pipeline {:?} got window activation after closure.", pipeline_id),
+        };
+        if let Err(e) = result {
+            self.handle_send_error(pipeline_id, e);
+        }
+    }
+
+    fn handle_resolve_link_for_context_menu(&mut self,
+                                            pipeline_id: PipelineId,
+                                            element_id: ElementId,
+                                            sender: IpcSender<Option<Url>>) {
+        let result = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => {
+                let msg = ConstellationControlMsg::ResolveLinkForContextMenu(pipeline_id, element_id, sender);
+                pipeline.script_chan.send(msg)
+            }
+            None => {
+                warn!("Pipeline {:?} got link resolution request after closure.", pipeline_id);
+                if let Err(e) = sender.send(None) {
+                    warn!("Sending link resolution failed ({}).", e);
+                }
+                return;
+            }
+        };
+        if let Err(e) = result {
+            self.handle_send_error(pipeline_id, e);
+        }
+    }
+
+    fn handle_spatial_navigate(&mut self, pipeline_id: PipelineId, direction: SpatialDirection) {
+        let parent_info = match self.pipelines.get(&pipeline_id) {
+            Some(pipeline) => pipeline.parent_info,
+            None => return warn!("Pipeline {:?} navigated spatially after closure.", pipeline_id),
+        };
+        let (parent_id, subpage_id) = match parent_info {
+            Some((parent_id, subpage_id, _)) => (parent_id, subpage_id),
+            None => return debug!("Spatial navigation found no target in top-level pipeline {:?}.", pipeline_id),
+        };
+        let msg = ConstellationControlMsg::SpatialNavigate(parent_id, subpage_id, direction);
+        let result = match self.pipelines.get(&parent_id) {
+            Some(pipeline) => pipeline.script_chan.send(msg),
+            None => return warn!("Pipeline {:?} navigated spatially after closure.", parent_id),
+        };
+        if let Err(e) = result {
+            self.handle_send_error(parent_id, e);
+        }
+    }
+
+    fn handle_set_title(&mut self, pipeline_id: PipelineId, title: Option<String>) {
+        // Update the session history entry even if the title changed after the document loaded.
+        match self.pipelines.get_mut(&pipeline_id) {
+            Some(pipeline) => pipeline.set_title(title),
+            None => warn!("Pipeline {:?} set its title after closure.", pipeline_id),
+        }
+    }
+
     fn handle_get_pipeline_title_msg(&mut self, pipeline_id: PipelineId) {
         let result = match self.pipelines.get(&pipeline_id) {
             None => return self.compositor_proxy.send(ToCompositorMsg::ChangePageTitle(pipeline_id, None)),
-            Some(pipeline) => pipeline.script_chan.send(ConstellationControlMsg::GetTitle(pipeline_id)),
+            Some(pipeline) => pipeline.send_title(),
         };
         if let Err(e) = result {
             self.handle_send_error(pipeline_id, e);
@@ -1779,7 +2984,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                               pipeline_id: PipelineId,
                               load_data: LoadData,
                               reply: IpcSender<webdriver_msg::LoadStatus>) {
-        let new_pipeline_id = self.load_url(pipeline_id, load_data);
+        let new_pipeline_id = self.load_url(pipeline_id, load_data, NavigationReason::Address);
         if let Some(id) = new_pipeline_id {
             self.webdriver.load_channel = Some((id, reply));
         }
@@ -1797,19 +3002,31 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
             }
         }
 
+        // Windows opened by the old document keep their opener as it navigates.
+        if let Some(old_pipeline_id) = frame_change.old_pipeline_id {
+            self.replace_opener(old_pipeline_id, Some(frame_change.new_pipeline_id));
+        }
+
         let evicted_frames = frame_change.old_pipeline_id.and_then(|old_pipeline_id| {
             // The new pipeline is replacing an old one.
             // Remove paint permissions for the pipeline being replaced.
             self.revoke_paint_permission(old_pipeline_id);
 
             // Add new pipeline to navigation frame, and return frames evicted from history.
+            // A reload replaces the old document, so it is evicted too.
+            let is_reload = self.pipelines.get(&frame_change.new_pipeline_id)
+                                .map_or(false, |pipeline| pipeline.navigation_reason == Some(NavigationReason::Reload));
             self.pipelines
                 .get(&old_pipeline_id)
                 .and_then(|pipeline| pipeline.frame)
                 .and_then(|frame_id| {
                     self.pipelines.get_mut(&frame_change.new_pipeline_id)
                                   .map(|pipeline| pipeline.frame = Some(frame_id));
-                    self.frames.get_mut(&frame_id).map(|frame| frame.load(frame_change.new_pipeline_id))
+                    self.frames.get_mut(&frame_id).map(|frame| if is_reload {
+                        frame.reload(frame_change.new_pipeline_id)
+                    } else {
+                        frame.load(frame_change.new_pipeline_id)
+                    })
                 })
         });
 
@@ -2024,6 +3241,12 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
                 }
             }
 
+            // If the harness reports frame hashes for this pipeline, wait until two consecutive
+            // frames are identical.
+            if pipeline.frame_hash.is_some() && !pipeline.frame_hash_stable {
+                return ReadyToSave::FrameHashUnstable;
+            }
+
             // Check the visible rectangle for this pipeline. If the constellation has received a
             // size for the pipeline, then its painting should be up to date. If the constellation
             // *hasn't* received a size, it could be that the layer was hidden by script before the
@@ -2097,6 +3320,7 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         if self.frames.remove(&frame_id).is_none() {
             warn!("Closing frame {:?} twice.", frame_id);
         }
+        self.auxiliary_frame_ids.retain(|&id| id != frame_id);
 
         if let Some((parent_pipeline_id, _, _)) = parent_info {
             let parent_pipeline = match self.pipelines.get_mut(&parent_pipeline_id) {
@@ -2156,6 +3380,31 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
         }
     }
 
+    // Ping pipelines that have answered their last ping, and report those that have not
+    // answered in time as unresponsive. This runs every time the ping timer fires.
+    fn ping_pipelines(&mut self) {
+        let now = precise_time_ms().get();
+        let mut failures = vec![];
+        for pipeline in self.pipelines.values_mut() {
+            if pipeline.pending_ping.is_some() {
+                if pipeline.check_ping_timeout(now, PING_TIMEOUT_MS) {
+                    warn!("Pipeline {:?} is not responding.", pipeline.id);
+                    self.compositor_proxy.send(ToCompositorMsg::SetPipelineResponsive(pipeline.id, false));
+                }
+            } else {
+                let ping_id = self.next_ping_id;
+                let PingId(ref mut i) = self.next_ping_id;
+                *i += 1;
+                if let Err(e) = pipeline.ping(ping_id, now) {
+                    failures.push((pipeline.id, e));
+                }
+            }
+        }
+        for (pipeline_id, e) in failures {
+            self.handle_send_error(pipeline_id, e);
+        }
+    }
+
     // Randomly close a pipeline -if --random-pipeline-closure-probability is set
     fn maybe_close_random_pipeline(&mut self) {
         match self.random_pipeline_closure {
@@ -2318,7 +3567,21 @@ impl<Message, LTF, STF> Constellation<Message, LTF, STF>
     }
 
     fn pipeline_is_in_current_frame(&self, pipeline_id: PipelineId) -> bool {
-        self.pipeline_exists_in_tree(pipeline_id, self.root_frame_id)
+        self.top_level_frame_ids().into_iter()
+            .any(|frame_id| self.pipeline_exists_in_tree(pipeline_id, Some(frame_id)))
+    }
+
+    /// The root frame, followed by the top-level frames of the auxiliary browsing contexts.
+    fn top_level_frame_ids(&self) -> Vec<FrameId> {
+        self.root_frame_id.into_iter().chain(self.auxiliary_frame_ids.iter().cloned()).collect()
+    }
+
+    /// Whether the pipeline is the document currently shown in the root frame, which is the
+    /// only one allowed to change the window chrome (favicon, theme color, app badge).
+    fn pipeline_is_top_level(&self, pipeline_id: PipelineId) -> bool {
+        self.root_frame_id
+            .and_then(|root_frame_id| self.frames.get(&root_frame_id))
+            .map_or(false, |root_frame| root_frame.current == pipeline_id)
     }
 
     fn pipeline_exists_in_tree(&self,
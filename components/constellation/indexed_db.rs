@@ -0,0 +1,48 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Bookkeeping for the IndexedDB databases opened through `indexedDB.open`, which are shared by
+//! every same-origin context.
+//!
+//! https://w3c.github.io/IndexedDB/
+
+use script_traits::{IdbConnectionId, Origin};
+use std::collections::HashMap;
+
+pub struct IndexedDbManager {
+    /// The current version of each origin's databases, by name.
+    versions: HashMap<(Origin, String), u64>,
+    next_connection_id: IdbConnectionId,
+}
+
+impl IndexedDbManager {
+    pub fn new() -> IndexedDbManager {
+        IndexedDbManager {
+            versions: HashMap::new(),
+            next_connection_id: IdbConnectionId(0),
+        }
+    }
+
+    /// Opens a connection to the database, upgrading it to the requested version if that is
+    /// above its current one. The error is the message of the exception to fail the open with.
+    /// https://w3c.github.io/IndexedDB/#open-a-database
+    pub fn open(&mut self, origin: Origin, name: String, version: u64) -> Result<IdbConnectionId, String> {
+        // https://w3c.github.io/IndexedDB/#dom-idbfactory-open
+        if version == 0 {
+            return Err("TypeError: the version must be at least 1.".to_owned());
+        }
+        let current_version = self.versions.entry((origin, name)).or_insert(0);
+        if version < *current_version {
+            return Err(format!("VersionError: the database is at version {}, above the requested {}.",
+                               *current_version, version));
+        }
+        // TODO: Fire upgradeneeded through the connection when the version increases,
+        // once there is a backend to store object stores in.
+        *current_version = version;
+        let connection_id = self.next_connection_id;
+        let IdbConnectionId(ref mut i) = self.next_connection_id;
+        *i += 1;
+        Ok(connection_id)
+    }
+}
@@ -23,6 +23,8 @@ extern crate euclid;
 extern crate gaol;
 extern crate gfx;
 extern crate gfx_traits;
+extern crate html5ever;
+extern crate hyper;
 extern crate ipc_channel;
 extern crate layers;
 extern crate layout_traits;
@@ -43,12 +45,15 @@ extern crate util;
 extern crate webrender_traits;
 
 mod constellation;
+pub mod indexed_db;
 mod pipeline;
+pub mod sanitizer;
 #[cfg(not(target_os = "windows"))]
 mod sandboxing;
 mod timer_scheduler;
+pub mod web_locks;
 
 pub use constellation::{Constellation, FromCompositorLogger, FromScriptLogger, InitialConstellationState};
-pub use pipeline::UnprivilegedPipelineContent;
+pub use pipeline::{Pipeline, UnprivilegedPipelineContent};
 #[cfg(not(target_os = "windows"))]
 pub use sandboxing::content_process_sandbox_profile;
@@ -6,28 +6,29 @@ use compositing::CompositionPipeline;
 use compositing::CompositorProxy;
 use compositing::compositor_thread::Msg as CompositorMsg;
 use devtools_traits::{DevtoolsControlMsg, ScriptToDevtoolsControlMsg};
+use euclid::point::Point2D;
 use euclid::scale_factor::ScaleFactor;
 use euclid::size::TypedSize2D;
 #[cfg(not(target_os = "windows"))]
 use gaol;
 use gfx::font_cache_thread::FontCacheThread;
 use gfx::paint_thread::{LayoutToPaintMsg, PaintThread};
-use gfx_traits::ChromeToPaintMsg;
+use gfx_traits::{ChromeToPaintMsg, LayerId};
 use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
 use layers::geometry::DevicePixel;
 use layout_traits::LayoutThreadFactory;
 use msg::constellation_msg::{FrameId, FrameType, LoadData, PanicMsg, PipelineId};
 use msg::constellation_msg::{PipelineNamespaceId, SubpageId};
-use net_traits::ResourceThreads;
+use net_traits::{ResourceThreads, ResponseAction};
 use net_traits::bluetooth_thread::BluetoothMethodMsg;
 use net_traits::image_cache_thread::ImageCacheThread;
 use profile_traits::mem as profile_mem;
 use profile_traits::time;
-use script_traits::{ConstellationControlMsg, InitialScriptState, MozBrowserEvent};
-use script_traits::{LayoutControlMsg, LayoutMsg, NewLayoutInfo, ScriptMsg};
-use script_traits::{ScriptThreadFactory, TimerEventRequest, WindowSizeData};
-use std::collections::HashMap;
+use script_traits::{ConstellationControlMsg, ElementId, FrameStats, FrameStatsSummary, InitialScriptState};
+use script_traits::{LayoutControlMsg, LayoutMsg, MozBrowserEvent, NavigationReason, NewLayoutInfo, PingId, ScriptMsg};
+use script_traits::{ScriptThreadFactory, ScrollRestorationMode, TimerEventRequest, WindowSizeData};
+use std::collections::{HashMap, VecDeque};
 use std::io::Error as IOError;
 use std::process;
 use std::sync::mpsc::{Sender, channel};
@@ -58,7 +59,8 @@ pub struct Pipeline {
     pub chrome_to_paint_chan: Sender<ChromeToPaintMsg>,
     /// URL corresponding to the most recently-loaded page.
     pub url: Url,
-    /// The title of the most recently-loaded page.
+    /// The title of the most recently-loaded page, which labels the pipeline's session history
+    /// entry. It is kept up to date when the title changes after the page has loaded.
     pub title: Option<String>,
     pub size: Option<TypedSize2D<PagePx, f32>>,
     /// Whether this pipeline is currently running animations. Pipelines that are running
@@ -73,6 +75,50 @@ pub struct Pipeline {
     /// Frame that contains this Pipeline. Can be `None` if the pipeline is not apart of the
     /// frame tree.
     pub frame: Option<FrameId>,
+    /// The `Service-Worker-Navigation-Preload` header value to send with navigations started by
+    /// this pipeline, if navigation preload is enabled.
+    pub navigation_preload: Option<String>,
+    /// Whether this pipeline wants to hear about changes to the system clipboard.
+    pub clipboard_change_subscribed: bool,
+    /// The effective domain set through `document.domain`, if any, which takes the place of the
+    /// URL's host when checking whether frames are same origin-domain.
+    pub document_domain: Option<String>,
+    /// The pipeline whose document opened this one's window, and which `window.opener` refers
+    /// to, unless the relationship was severed by `noopener` or `Cross-Origin-Opener-Policy`.
+    /// It follows the opener's context as that navigates, and is cleared once it is closed.
+    pub opener: Option<PipelineId>,
+    /// The element that should be focused when the window is activated, as reported once the
+    /// document has loaded.
+    pub initial_focus: Option<ElementId>,
+    /// The hash of the latest composited frame, if one has been reported for reftests.
+    pub frame_hash: Option<u64>,
+    /// Whether the latest frame hash matched the one reported before it.
+    pub frame_hash_stable: bool,
+    /// The responsiveness ping that has not been answered yet, if any, with the time in
+    /// milliseconds at which it was sent.
+    pub pending_ping: Option<(PingId, u64)>,
+    /// Whether the pipeline has been reported as unresponsive, for not answering a ping in time.
+    pub unresponsive: bool,
+    /// The statistics of the pipeline's most recent animation frames, oldest first.
+    pub frame_stats: VecDeque<FrameStats>,
+    /// When the user last interacted with the pipeline's document, in milliseconds.
+    pub last_activation: Option<u64>,
+    /// The URL of a navigation that was blocked, while its interstitial awaits the user's
+    /// decision.
+    pub blocked_navigation: Option<Url>,
+    /// Why the navigation that created the pipeline's session history entry happened, or `None`
+    /// for the initial document of a frame. A pipeline created by a reload replaces the entry of
+    /// the pipeline it reloads instead of adding an entry after it.
+    pub navigation_reason: Option<NavigationReason>,
+    /// Whether the pipeline's scroll position is restored when session history is traversed back
+    /// to it.
+    pub scroll_restoration: ScrollRestorationMode,
+    /// The scroll offset saved when the pipeline was navigated away from, with the layer it
+    /// applies to.
+    pub saved_scroll_position: Option<(LayerId, Point2D<f32>)>,
+    /// The name of the pipeline's browsing context, as given by its iframe's `name` attribute or
+    /// by `window.open`, which is empty if it has none.
+    pub name: String,
 }
 
 /// Initial setup data needed to construct a pipeline.
@@ -118,6 +164,9 @@ pub struct InitialPipelineState {
     pub script_chan: Option<IpcSender<ConstellationControlMsg>>,
     /// Information about the page to load.
     pub load_data: LoadData,
+    /// The response to the page load, if the constellation already started fetching it as a
+    /// navigation preload. Only used when the pipeline gets a new script thread.
+    pub preload_response: Option<IpcReceiver<ResponseAction>>,
     /// The ID of the pipeline namespace for this script thread.
     pub pipeline_namespace_id: PipelineNamespaceId,
     /// Pipeline visibility is inherited from parent
@@ -231,6 +280,7 @@ impl Pipeline {
                 layout_to_constellation_chan: state.layout_to_constellation_chan,
                 script_chan: script_chan.clone(),
                 load_data: state.load_data.clone(),
+                preload_response: state.preload_response,
                 panic_chan: state.panic_chan,
                 script_port: script_port,
                 opts: (*opts::get()).clone(),
@@ -271,17 +321,17 @@ impl Pipeline {
         Ok((pipeline, child_process))
     }
 
-    fn new(id: PipelineId,
-           parent_info: Option<(PipelineId, SubpageId, FrameType)>,
-           script_chan: IpcSender<ConstellationControlMsg>,
-           layout_chan: IpcSender<LayoutControlMsg>,
-           compositor_proxy: Box<CompositorProxy + 'static + Send>,
-           chrome_to_paint_chan: Sender<ChromeToPaintMsg>,
-           is_private: bool,
-           url: Url,
-           size: Option<TypedSize2D<PagePx, f32>>,
-           visible: bool)
-           -> Pipeline {
+    pub fn new(id: PipelineId,
+               parent_info: Option<(PipelineId, SubpageId, FrameType)>,
+               script_chan: IpcSender<ConstellationControlMsg>,
+               layout_chan: IpcSender<LayoutControlMsg>,
+               compositor_proxy: Box<CompositorProxy + 'static + Send>,
+               chrome_to_paint_chan: Sender<ChromeToPaintMsg>,
+               is_private: bool,
+               url: Url,
+               size: Option<TypedSize2D<PagePx, f32>>,
+               visible: bool)
+               -> Pipeline {
         Pipeline {
             id: id,
             parent_info: parent_info,
@@ -291,12 +341,28 @@ impl Pipeline {
             chrome_to_paint_chan: chrome_to_paint_chan,
             url: url,
             title: None,
+            pending_ping: None,
+            unresponsive: false,
+            frame_stats: VecDeque::new(),
+            last_activation: None,
+            blocked_navigation: None,
+            navigation_reason: None,
+            scroll_restoration: ScrollRestorationMode::Auto,
+            saved_scroll_position: None,
+            name: String::new(),
             children: vec!(),
             size: size,
             running_animations: false,
             visible: visible,
             is_private: is_private,
             frame: None,
+            navigation_preload: None,
+            clipboard_change_subscribed: false,
+            document_domain: None,
+            opener: None,
+            initial_focus: None,
+            frame_hash: None,
+            frame_hash_stable: false,
         }
     }
 
@@ -355,6 +421,91 @@ impl Pipeline {
         }
     }
 
+    /// Records a new title for the pipeline's document and passes it on to the compositor.
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title.clone();
+        self.compositor_proxy.send(CompositorMsg::ChangePageTitle(self.id, title));
+    }
+
+    /// Sends the pipeline's title to the compositor, asking script for it if none has been
+    /// recorded yet.
+    pub fn send_title(&self) -> Result<(), IOError> {
+        match self.title {
+            Some(ref title) => {
+                self.compositor_proxy.send(CompositorMsg::ChangePageTitle(self.id, Some(title.clone())));
+                Ok(())
+            }
+            None => self.script_chan.send(ConstellationControlMsg::GetTitle(self.id)),
+        }
+    }
+
+    /// Sends a responsiveness ping to script, recording when it was sent.
+    pub fn ping(&mut self, ping_id: PingId, now: u64) -> Result<(), IOError> {
+        self.pending_ping = Some((ping_id, now));
+        self.script_chan.send(ConstellationControlMsg::Ping(self.id, ping_id))
+    }
+
+    /// Marks the pipeline as unresponsive if its pending ping has gone unanswered for `timeout`
+    /// milliseconds. Returns true only when the pipeline has just become unresponsive.
+    pub fn check_ping_timeout(&mut self, now: u64, timeout: u64) -> bool {
+        match self.pending_ping {
+            Some((_, sent_at)) if !self.unresponsive && now - sent_at >= timeout => {
+                self.unresponsive = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Records the statistics of a new frame, keeping those of the `window` most recent frames,
+    /// and returns a summary of the frames kept.
+    pub fn add_frame_stats(&mut self, frame_stats: FrameStats, window: usize) -> FrameStatsSummary {
+        // VecDeque::truncate is unstable
+        if window <= self.frame_stats.len() {
+            self.frame_stats.pop_front();
+        }
+        self.frame_stats.push_back(frame_stats);
+
+        let frames = self.frame_stats.len() as f32;
+        let mut summary = FrameStatsSummary {
+            frames: self.frame_stats.len() as u32,
+            dropped_frames: 0,
+            raf_callbacks: 0.0,
+            script_ms: 0.0,
+            layout_ms: 0.0,
+        };
+        for frame_stats in &self.frame_stats {
+            if frame_stats.dropped {
+                summary.dropped_frames += 1;
+            }
+            summary.raf_callbacks += frame_stats.raf_callbacks as f32 / frames;
+            summary.script_ms += frame_stats.script_ms / frames;
+            summary.layout_ms += frame_stats.layout_ms / frames;
+        }
+        summary
+    }
+
+    /// Records the hash of the pipeline's latest frame, noting whether it matches the one
+    /// reported before it.
+    pub fn set_frame_hash(&mut self, hash: u64) {
+        self.frame_hash_stable = self.frame_hash == Some(hash);
+        self.frame_hash = Some(hash);
+    }
+
+    /// Whether this pipeline's document and the other's are same origin-domain: both set
+    /// `document.domain` to the same value and share a scheme, or neither set it and they are
+    /// same origin.
+    /// https://html.spec.whatwg.org/multipage/#same-origin-domain
+    pub fn is_same_origin_domain(&self, other: &Pipeline) -> bool {
+        match (&self.document_domain, &other.document_domain) {
+            (&Some(ref domain), &Some(ref other_domain)) => {
+                self.url.scheme() == other.url.scheme() && domain == other_domain
+            }
+            (&None, &None) => self.url.origin() == other.url.origin(),
+            _ => false,
+        }
+    }
+
     pub fn to_sendable(&self) -> CompositionPipeline {
         CompositionPipeline {
             id: self.id.clone(),
@@ -422,6 +573,7 @@ pub struct UnprivilegedPipelineContent {
     window_size: Option<WindowSizeData>,
     script_chan: IpcSender<ConstellationControlMsg>,
     load_data: LoadData,
+    preload_response: Option<IpcReceiver<ResponseAction>>,
     panic_chan: IpcSender<PanicMsg>,
     script_port: IpcReceiver<ConstellationControlMsg>,
     layout_to_paint_chan: OptionalIpcSender<LayoutToPaintMsg>,
@@ -458,6 +610,7 @@ impl UnprivilegedPipelineContent {
             window_size: self.window_size,
             pipeline_namespace_id: self.pipeline_namespace_id,
             content_process_shutdown_chan: self.script_content_process_shutdown_chan,
+            preload_response: self.preload_response,
         }, self.load_data.clone());
 
         LTF::create(self.id,
@@ -0,0 +1,167 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Cleaning up HTML read from the clipboard before it is handed to content.
+//!
+//! `sanitize_html` drops:
+//!
+//! * `script`, `style`, `template`, `noscript`, `iframe`, `frame`, `frameset`, `object`,
+//!   `embed` and `applet` elements, along with everything inside them;
+//! * `base`, `link` and `meta` elements;
+//! * comments and doctypes;
+//! * every attribute whose name starts with `on` (event handlers), and `style` attributes;
+//! * `href`, `src`, `action`, `formaction`, `xlink:href` and `srcset` attributes whose value is
+//!   a `javascript:`, `vbscript:` or `data:` URL. Leading whitespace and control characters, and
+//!   tabs and newlines anywhere, are ignored when looking for the scheme, as URL parsing does.
+//!
+//! Everything else is kept, and re-serialized with its text and attribute values escaped.
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{Attribute, Tag, TagKind, Token, TokenSink, Tokenizer};
+use std::ascii::AsciiExt;
+
+/// Elements that are removed together with their contents.
+const DROPPED_ELEMENTS: &'static [&'static str] = &[
+    "applet", "embed", "frame", "frameset", "iframe", "noscript", "object", "script", "style", "template",
+];
+
+/// Void elements that are removed on their own.
+const DROPPED_VOID_ELEMENTS: &'static [&'static str] = &["base", "link", "meta"];
+
+/// Attributes that hold URLs, and so may carry a script URL.
+const URL_ATTRIBUTES: &'static [&'static str] = &["action", "formaction", "href", "src", "srcset", "xlink:href"];
+
+/// URL schemes that are dropped from those attributes: the first two run script, and a `data:`
+/// URL can hold a whole document that does.
+const DROPPED_SCHEMES: &'static [&'static str] = &["javascript:", "vbscript:", "data:"];
+
+/// Removes anything that could run script from an HTML fragment.
+pub fn sanitize_html(html: &str) -> String {
+    let sink = Sanitizer {
+        output: String::with_capacity(html.len()),
+        dropped_element: None,
+        depth: 0,
+    };
+    let mut tokenizer = Tokenizer::new(sink, Default::default());
+    tokenizer.feed(StrTendril::from(html));
+    tokenizer.end();
+    tokenizer.unwrap().output
+}
+
+struct Sanitizer {
+    /// The sanitized HTML written so far.
+    output: String,
+    /// The element whose contents are currently being skipped, if any.
+    dropped_element: Option<String>,
+    /// How many elements of that name are open inside it, so that nesting is handled.
+    depth: usize,
+}
+
+impl Sanitizer {
+    fn process_tag(&mut self, tag: Tag) {
+        let name = tag.name.to_ascii_lowercase();
+
+        if let Some(dropped) = self.dropped_element.clone() {
+            if name == dropped {
+                match tag.kind {
+                    TagKind::StartTag if !tag.self_closing => self.depth += 1,
+                    TagKind::StartTag => {}
+                    TagKind::EndTag => {
+                        self.depth -= 1;
+                        if self.depth == 0 {
+                            self.dropped_element = None;
+                        }
+                    }
+                }
+            }
+            return;
+        }
+
+        if DROPPED_ELEMENTS.contains(&&*name) {
+            if tag.kind == TagKind::StartTag && !tag.self_closing {
+                self.dropped_element = Some(name);
+                self.depth = 1;
+            }
+            return;
+        }
+        if DROPPED_VOID_ELEMENTS.contains(&&*name) {
+            return;
+        }
+
+        match tag.kind {
+            TagKind::StartTag => {
+                self.output.push('<');
+                self.output.push_str(&name);
+                for attribute in tag.attrs.iter().filter(|attribute| is_safe_attribute(attribute)) {
+                    self.output.push(' ');
+                    self.output.push_str(&attribute.name.local.to_ascii_lowercase());
+                    self.output.push_str("=\"");
+                    escape(&attribute.value, true, &mut self.output);
+                    self.output.push('"');
+                }
+                if tag.self_closing {
+                    self.output.push_str(" /");
+                }
+                self.output.push('>');
+            }
+            TagKind::EndTag => {
+                self.output.push_str("</");
+                self.output.push_str(&name);
+                self.output.push('>');
+            }
+        }
+    }
+}
+
+impl TokenSink for Sanitizer {
+    fn process_token(&mut self, token: Token) {
+        match token {
+            Token::TagToken(tag) => self.process_tag(tag),
+            Token::CharacterTokens(text) => {
+                if self.dropped_element.is_none() {
+                    escape(&text, false, &mut self.output);
+                }
+            }
+            Token::DoctypeToken(_) |
+            Token::CommentToken(_) |
+            Token::NullCharacterToken |
+            Token::EOFToken |
+            Token::ParseError(_) => {}
+        }
+    }
+}
+
+fn is_safe_attribute(attribute: &Attribute) -> bool {
+    let name = attribute.name.local.to_ascii_lowercase();
+    if name.starts_with("on") || name == "style" {
+        return false;
+    }
+    if URL_ATTRIBUTES.contains(&&*name) {
+        // URL parsing skips leading whitespace and control characters, and removes tabs and
+        // newlines wherever they are, so `java\tscript:` is still a script URL.
+        let value = attribute.value
+                             .trim_left_matches(|c: char| c <= ' ')
+                             .chars()
+                             .filter(|&c| c != '\t' && c != '\n' && c != '\r')
+                             .collect::<String>()
+                             .to_ascii_lowercase();
+        if DROPPED_SCHEMES.iter().any(|scheme| value.starts_with(scheme)) {
+            return false;
+        }
+    }
+    true
+}
+
+fn escape(text: &str, in_attribute: bool, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '\u{a0}' => output.push_str("&nbsp;"),
+            '"' if in_attribute => output.push_str("&quot;"),
+            '<' if !in_attribute => output.push_str("&lt;"),
+            '>' if !in_attribute => output.push_str("&gt;"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Arbitration of the locks requested through `navigator.locks`, which are shared by every
+//! same-origin context.
+//!
+//! https://w3c.github.io/web-locks/
+
+use ipc_channel::ipc::IpcSender;
+use msg::constellation_msg::PipelineId;
+use script_traits::{Origin, WebLockGrant, WebLockMode, WebLockToken};
+use std::collections::{HashMap, VecDeque};
+
+/// A request that is waiting for the lock to become available.
+struct PendingRequest {
+    pipeline_id: PipelineId,
+    mode: WebLockMode,
+    sender: IpcSender<WebLockGrant>,
+}
+
+/// The holders and the queue of a single lock.
+#[derive(Default)]
+struct LockState {
+    /// The grants currently holding the lock: a single exclusive one, or any number of shared
+    /// ones.
+    held: Vec<(WebLockToken, WebLockMode)>,
+    /// The requests waiting for the lock, in the order they were made.
+    queue: VecDeque<PendingRequest>,
+}
+
+impl LockState {
+    fn is_grantable(&self, mode: WebLockMode) -> bool {
+        match mode {
+            WebLockMode::Exclusive => self.held.is_empty(),
+            WebLockMode::Shared => self.held.iter().all(|&(_, held_mode)| held_mode == WebLockMode::Shared),
+        }
+    }
+}
+
+pub struct WebLockManager {
+    locks: HashMap<(Origin, String), LockState>,
+    /// The pipeline holding each outstanding grant, and the lock it holds.
+    holders: HashMap<WebLockToken, (PipelineId, (Origin, String))>,
+    next_token: WebLockToken,
+}
+
+impl WebLockManager {
+    pub fn new() -> WebLockManager {
+        WebLockManager {
+            locks: HashMap::new(),
+            holders: HashMap::new(),
+            next_token: WebLockToken(0),
+        }
+    }
+
+    /// Grants the lock straight away if it is available, and queues the request otherwise.
+    /// A request is only granted once every request queued before it has been, so that a stream
+    /// of shared requests cannot starve an exclusive one. Locks are scoped to `origin`, which
+    /// must be the origin of the requesting pipeline.
+    pub fn acquire(&mut self,
+                   pipeline_id: PipelineId,
+                   origin: Origin,
+                   name: String,
+                   mode: WebLockMode,
+                   sender: IpcSender<WebLockGrant>) {
+        let key = (origin, name);
+        self.locks.entry(key.clone()).or_insert_with(Default::default).queue.push_back(PendingRequest {
+            pipeline_id: pipeline_id,
+            mode: mode,
+            sender: sender,
+        });
+        self.grant_pending(key);
+    }
+
+    /// Releases a grant held by the given pipeline. Grants held by other pipelines are left alone.
+    pub fn release(&mut self, pipeline_id: PipelineId, token: WebLockToken) {
+        match self.holders.get(&token) {
+            Some(&(holder_id, _)) if holder_id == pipeline_id => {},
+            _ => return warn!("Pipeline {:?} released web lock {:?} it does not hold.", pipeline_id, token),
+        }
+        if let Some((_, key)) = self.holders.remove(&token) {
+            if let Some(lock) = self.locks.get_mut(&key) {
+                lock.held.retain(|&(held_token, _)| held_token != token);
+            }
+            self.grant_pending(key);
+        }
+    }
+
+    /// Drops the requests of a pipeline that has gone away, and releases the grants it held.
+    pub fn release_all(&mut self, pipeline_id: PipelineId) {
+        for lock in self.locks.values_mut() {
+            lock.queue.retain(|request| request.pipeline_id != pipeline_id);
+        }
+        let tokens: Vec<WebLockToken> = self.holders.iter()
+                                                    .filter(|&(_, &(holder_id, _))| holder_id == pipeline_id)
+                                                    .map(|(&token, _)| token)
+                                                    .collect();
+        for token in tokens {
+            self.release(pipeline_id, token);
+        }
+        // Dropping queued requests may have unblocked the requests behind them.
+        let keys: Vec<(Origin, String)> = self.locks.keys().cloned().collect();
+        for key in keys {
+            self.grant_pending(key);
+        }
+    }
+
+    fn grant_pending(&mut self, key: (Origin, String)) {
+        loop {
+            let request = match self.locks.get_mut(&key) {
+                Some(lock) => {
+                    let grantable = lock.queue.front().map_or(false, |request| lock.is_grantable(request.mode));
+                    if !grantable {
+                        break;
+                    }
+                    lock.queue.pop_front().expect("queue is not empty")
+                }
+                None => return,
+            };
+
+            let token = self.next_token;
+            let WebLockToken(ref mut i) = self.next_token;
+            *i += 1;
+
+            // A requester that has gone away never gets to release its lock, so it is not held.
+            if let Err(e) = request.sender.send(WebLockGrant { token: token }) {
+                warn!("Sending web lock grant failed ({}).", e);
+                continue;
+            }
+            if let Some(lock) = self.locks.get_mut(&key) {
+                lock.held.push((token, request.mode));
+            }
+            self.holders.insert(token, (request.pipeline_id, key.clone()));
+        }
+
+        let unused = self.locks.get(&key).map_or(false, |lock| lock.held.is_empty() && lock.queue.is_empty());
+        if unused {
+            self.locks.remove(&key);
+        }
+    }
+}
@@ -44,13 +44,14 @@ use actors::thread::ThreadActor;
 use actors::timeline::TimelineActor;
 use actors::worker::WorkerActor;
 use devtools_traits::{ChromeToDevtoolsControlMsg, ConsoleMessage, DevtoolsControlMsg};
-use devtools_traits::{DevtoolScriptControlMsg, DevtoolsPageInfo, LogLevel, NetworkEvent};
+use devtools_traits::{DevtoolScriptControlMsg, DevtoolsFrameInfo, DevtoolsPageInfo, LogLevel, NetworkEvent};
 use devtools_traits::{ScriptToDevtoolsControlMsg, WorkerId};
 use ipc_channel::ipc::IpcSender;
 use msg::constellation_msg::PipelineId;
 use protocol::JsonPacketStream;
 use std::borrow::ToOwned;
 use std::cell::RefCell;
+use std::cmp::max;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::net::{Shutdown, TcpListener, TcpStream};
@@ -166,6 +167,9 @@ fn run_server(sender: Sender<DevtoolsControlMsg>,
 
     let mut actor_workers: HashMap<(PipelineId, WorkerId), String> = HashMap::new();
 
+    // The frame tree, as each frame's latest information, which links to its parent.
+    let mut frames: HashMap<PipelineId, DevtoolsFrameInfo> = HashMap::new();
+
 
     /// Process the input from a single devtools client until EOF.
     fn handle_client(actors: Arc<Mutex<ActorRegistry>>, mut stream: TcpStream) {
@@ -210,6 +214,24 @@ fn run_server(sender: Sender<DevtoolsControlMsg>,
     // We need separate actor representations for each script global that exists;
     // clients can theoretically connect to multiple globals simultaneously.
     // TODO: move this into the root or tab modules?
+    fn unused_bytes_percentage(total_bytes: u64, mut used_ranges: Vec<(u32, u32)>) -> f64 {
+        if total_bytes == 0 {
+            return 0.0;
+        }
+        // Count each used byte once, even where ranges overlap.
+        used_ranges.sort();
+        let mut used = 0;
+        let mut counted_up_to = 0;
+        for (start, end) in used_ranges {
+            let start = max(start, counted_up_to);
+            if end > start {
+                used += (end - start) as u64;
+                counted_up_to = end;
+            }
+        }
+        100.0 * total_bytes.saturating_sub(used) as f64 / total_bytes as f64
+    }
+
     fn handle_new_global(actors: Arc<Mutex<ActorRegistry>>,
                          ids: (PipelineId, Option<WorkerId>),
                          script_sender: IpcSender<DevtoolScriptControlMsg>,
@@ -540,6 +562,15 @@ fn run_server(sender: Sender<DevtoolsControlMsg>,
                 handle_network_event(actors.clone(), connections, &actor_pipelines, &mut actor_requests,
                                      &actor_workers, PipelineId::fake_root_pipeline_id(), request_id, network_event);
             },
+            DevtoolsControlMsg::FromChrome(ChromeToDevtoolsControlMsg::FrameInfo(pipeline_id, frame_info)) => {
+                frames.insert(pipeline_id, frame_info);
+            }
+            DevtoolsControlMsg::FromChrome(ChromeToDevtoolsControlMsg::CodeCoverage(
+                        pipeline_id, url, total_bytes, used_ranges)) => {
+                // TODO: expose this through a coverage actor once the protocol has one.
+                let percentage = unused_bytes_percentage(total_bytes, used_ranges);
+                debug!("{}% of {} is unused by pipeline {:?}", percentage, url, pipeline_id);
+            }
             DevtoolsControlMsg::FromChrome(ChromeToDevtoolsControlMsg::ServerExitMsg) => break
         }
     }
@@ -44,6 +44,16 @@ pub struct DevtoolsPageInfo {
     pub url: Url
 }
 
+// Information about a frame, which is sent by the constellation to build the frame tree.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DevtoolsFrameInfo {
+    pub url: Url,
+    pub origin: String,
+    pub name: Option<String>,
+    pub parent: Option<PipelineId>,
+    pub is_cross_origin: bool,
+}
+
 #[derive(Deserialize, HeapSizeOf, Serialize, Clone)]
 pub struct CSSError {
     pub filename: String,
@@ -70,6 +80,11 @@ pub enum ChromeToDevtoolsControlMsg {
     /// A network event occurred (request, reply, etc.). The actor with the
     /// provided name should be notified.
     NetworkEvent(String, NetworkEvent),
+    /// A document was loaded into the frame of the given pipeline.
+    FrameInfo(PipelineId, DevtoolsFrameInfo),
+    /// Code coverage of a resource loaded by the given pipeline: its URL, its size in bytes,
+    /// and the `(start, end)` byte ranges of it that were used.
+    CodeCoverage(PipelineId, Url, u64, Vec<(u32, u32)>),
 }
 
 #[derive(Deserialize, Serialize)]
@@ -50,6 +50,17 @@ impl CookieStorage {
         }
     }
 
+    /// Removes every cookie that would be sent to the given host, whatever its path.
+    pub fn remove_for_host(&mut self, host: &str) {
+        self.cookies.retain(|c| {
+            match c.cookie.domain {
+                Some(ref domain) if c.host_only => domain != host,
+                Some(ref domain) => !Cookie::domain_match(host, domain),
+                None => true,
+            }
+        });
+    }
+
     // http://tools.ietf.org/html/rfc6265#section-5.3
     pub fn push(&mut self, mut cookie: Cookie, source: CookieSource) {
         let old_cookie = self.remove(&cookie, source);
@@ -284,6 +284,11 @@ impl ResourceChannelManager {
                 let cookies = cookie_jar.cookies_data_for_url(&url, source).collect();
                 consumer.send(cookies).unwrap();
             }
+            CoreResourceMsg::DeleteCookiesForUrl(url, sender) => {
+                let mut cookie_jar = group.cookie_jar.write().unwrap();
+                cookie_jar.remove_for_host(url.host_str().unwrap_or(""));
+                let _ = sender.send(());
+            }
             CoreResourceMsg::Cancel(res_id) => {
                 if let Some(cancel_sender) = self.resource_manager.cancel_load_map.get(&res_id) {
                     let _ = cancel_sender.send(());
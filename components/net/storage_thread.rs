@@ -71,6 +71,9 @@ impl StorageManager {
                 StorageThreadMsg::RemoveItem(sender, url, storage_type, name) => {
                     self.remove_item(sender, url, storage_type, name)
                 }
+                StorageThreadMsg::Size(sender, url, storage_type) => {
+                    self.size(sender, url, storage_type)
+                }
                 StorageThreadMsg::Clear(sender, url, storage_type) => {
                     self.clear(sender, url, storage_type)
                 }
@@ -107,6 +110,12 @@ impl StorageManager {
         sender.send(data.get(&origin).map_or(0, |&(_, ref entry)| entry.len())).unwrap();
     }
 
+    fn size(&self, sender: IpcSender<usize>, url: Url, storage_type: StorageType) {
+        let origin = self.origin_as_string(url);
+        let data = self.select_data(storage_type);
+        sender.send(data.get(&origin).map_or(0, |&(total, _)| total)).unwrap();
+    }
+
     fn key(&self,
            sender: IpcSender<Option<String>>,
            url: Url,
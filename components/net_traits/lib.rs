@@ -432,6 +432,9 @@ pub enum CoreResourceMsg {
     GetCookiesForUrl(Url, IpcSender<Option<String>>, CookieSource),
     /// Get a cookie by name for a given originating URL
     GetCookiesDataForUrl(Url, IpcSender<Vec<Cookie>>, CookieSource),
+    /// Delete the stored cookies that would be sent to the given URL's host, regardless of path,
+    /// and send a reply when done
+    DeleteCookiesForUrl(Url, IpcSender<()>),
     /// Cancel a network request corresponding to a given `ResourceId`
     Cancel(ResourceId),
     /// Synchronization message solely for knowing the state of the ResourceChannelManager loop
@@ -32,6 +32,9 @@ pub enum StorageThreadMsg {
     /// removes the key/value pair for the given key in the associated storage data
     RemoveItem(IpcSender<Option<String>>, Url, StorageType, String),
 
+    /// gets the number of bytes used by the keys and values of the associated storage data
+    Size(IpcSender<usize>, Url, StorageType),
+
     /// clears the associated storage data by removing all the key/value pairs
     Clear(IpcSender<bool>, Url, StorageType),
 
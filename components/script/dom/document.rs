@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use app_units::Au;
 use document_loader::{DocumentLoader, LoadType};
 use dom::activation::{ActivationSource, synthetic_click_activation};
 use dom::attr::Attr;
@@ -13,11 +14,12 @@ use dom::bindings::codegen::Bindings::ElementBinding::ElementMethods;
 use dom::bindings::codegen::Bindings::EventBinding::EventMethods;
 use dom::bindings::codegen::Bindings::EventHandlerBinding::EventHandlerNonNull;
 use dom::bindings::codegen::Bindings::EventHandlerBinding::OnErrorEventHandlerNonNull;
+use dom::bindings::codegen::Bindings::HTMLTextAreaElementBinding::HTMLTextAreaElementMethods;
 use dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use dom::bindings::codegen::Bindings::NodeFilterBinding::NodeFilter;
 use dom::bindings::codegen::Bindings::PerformanceBinding::PerformanceMethods;
 use dom::bindings::codegen::Bindings::TouchBinding::TouchMethods;
-use dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
+use dom::bindings::codegen::Bindings::WindowBinding::{ScrollBehavior, WindowMethods};
 use dom::bindings::codegen::UnionTypes::NodeOrString;
 use dom::bindings::error::{Error, ErrorResult, Fallible};
 use dom::bindings::global::GlobalRef;
@@ -31,6 +33,7 @@ use dom::bindings::str::{DOMString, USVString};
 use dom::bindings::xmlname::XMLName::InvalidXMLName;
 use dom::bindings::xmlname::{validate_and_extract, namespace_from_domstring, xml_name_type};
 use dom::browsingcontext::BrowsingContext;
+use dom::characterdata::CharacterData;
 use dom::closeevent::CloseEvent;
 use dom::comment::Comment;
 use dom::customevent::CustomEvent;
@@ -57,10 +60,12 @@ use dom::htmlheadelement::HTMLHeadElement;
 use dom::htmlhtmlelement::HTMLHtmlElement;
 use dom::htmliframeelement::HTMLIFrameElement;
 use dom::htmlimageelement::HTMLImageElement;
+use dom::htmlinputelement::HTMLInputElement;
 use dom::htmllinkelement::HTMLLinkElement;
 use dom::htmlmetaelement::HTMLMetaElement;
 use dom::htmlscriptelement::HTMLScriptElement;
 use dom::htmlstyleelement::HTMLStyleElement;
+use dom::htmltextareaelement::HTMLTextAreaElement;
 use dom::htmltitleelement::HTMLTitleElement;
 use dom::keyboardevent::KeyboardEvent;
 use dom::location::Location;
@@ -87,6 +92,8 @@ use dom::window::{ReflowReason, Window};
 use encoding::EncodingRef;
 use encoding::all::UTF_8;
 use euclid::point::Point2D;
+use euclid::rect::Rect;
+use euclid::size::Size2D;
 use html5ever::tree_builder::{LimitedQuirks, NoQuirks, Quirks, QuirksMode};
 use ipc_channel::ipc::{self, IpcSender};
 use js::jsapi::JS_GetRuntime;
@@ -104,15 +111,16 @@ use parse::{ParserRoot, ParserRef, MutNullableParserField};
 use script_layout_interface::message::{Msg, ReflowQueryType};
 use script_thread::{MainThreadScriptMsg, Runnable};
 use script_traits::UntrustedNodeAddress;
-use script_traits::{AnimationState, MouseButton, MouseEventType, MozBrowserEvent};
-use script_traits::{ScriptMsg as ConstellationMsg, TouchpadPressurePhase};
-use script_traits::{TouchEventType, TouchId};
+use script_traits::{AnimationState, DeprecationInfo, ElementId, FrameStats, ImeState, InputType};
+use script_traits::{MouseButton, MouseEventType, MozBrowserEvent, ScriptMsg as ConstellationMsg};
+use script_traits::{SpatialDirection, TextFragmentDirective, TouchEventType, TouchId, TouchpadPressurePhase};
+use script_traits::{USER_ACTIVATION_DURATION_MS, precise_time_ms};
 use std::ascii::AsciiExt;
 use std::borrow::ToOwned;
 use std::boxed::FnBox;
 use std::cell::{Cell, Ref, RefMut};
-use std::collections::HashMap;
 use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::iter::once;
 use std::mem;
@@ -130,6 +138,12 @@ use url::Url;
 use url::percent_encoding::percent_decode;
 use util::prefs::PREFS;
 
+/// How long an animation frame may take at 60Hz, in nanoseconds.
+const FRAME_BUDGET_NS: u64 = 16_666_667;
+
+/// How many animation frames' statistics to collect before reporting them to the constellation.
+const FRAME_STATS_BATCH: usize = 15;
+
 #[derive(JSTraceable, PartialEq, HeapSizeOf)]
 pub enum IsHTMLDocument {
     HTMLDocument,
@@ -173,6 +187,9 @@ pub struct Document {
     stylesheets: DOMRefCell<Option<Vec<(JS<Node>, Arc<Stylesheet>)>>>,
     /// Whether the list of stylesheets has changed since the last reflow was triggered.
     stylesheets_changed_since_reflow: Cell<bool>,
+    /// Whether an element with an `accesskey` attribute was added, removed or changed since the
+    /// shortcuts were last sent to the embedder.
+    access_keys_changed_since_reflow: Cell<bool>,
     ready_state: Cell<DocumentReadyState>,
     /// Whether the DOMContentLoaded event has already been dispatched.
     domcontentloaded_dispatched: Cell<bool>,
@@ -207,6 +224,8 @@ pub struct Document {
     /// Tracking this is not necessary for correctness. Instead, it is an optimization to avoid
     /// sending needless `ChangeRunningAnimationsState` messages to the compositor.
     running_animation_callbacks: Cell<bool>,
+    /// The statistics of the animation frames not yet reported to the constellation.
+    pending_frame_stats: DOMRefCell<Vec<FrameStats>>,
     /// Tracks all outstanding loads related to this document.
     loader: DOMRefCell<DocumentLoader>,
     /// The current active HTML parser, to allow resuming after interruptions.
@@ -241,6 +260,29 @@ pub struct Document {
     referrer_policy: Cell<Option<ReferrerPolicy>>,
     /// https://html.spec.whatwg.org/multipage/#dom-document-referrer
     referrer: Option<String>,
+    /// The deprecated features that have been reported for this document.
+    reported_deprecations: DOMRefCell<HashSet<String>>,
+    /// When the user last interacted with this document, in milliseconds.
+    last_activation: Cell<Option<u64>>,
+}
+
+/// How far `candidate` is from `from` for spatial navigation in the given direction, with
+/// misalignment across the direction counting double, or `None` if it is not in that direction.
+fn spatial_navigation_distance(from: &Rect<Au>, candidate: &Rect<Au>, direction: SpatialDirection) -> Option<i64> {
+    let center = |rect: &Rect<Au>| {
+        Point2D::new(rect.origin.x + rect.size.width / 2, rect.origin.y + rect.size.height / 2)
+    };
+    let (from_center, candidate_center) = (center(from), center(candidate));
+    let (distance, offset) = match direction {
+        SpatialDirection::Up => (from.origin.y - candidate.max_y(), candidate_center.x - from_center.x),
+        SpatialDirection::Down => (candidate.origin.y - from.max_y(), candidate_center.x - from_center.x),
+        SpatialDirection::Left => (from.origin.x - candidate.max_x(), candidate_center.y - from_center.y),
+        SpatialDirection::Right => (candidate.origin.x - from.max_x(), candidate_center.y - from_center.y),
+    };
+    if distance < Au(0) {
+        return None;
+    }
+    Some(distance.0 as i64 + 2 * (offset.0 as i64).abs())
 }
 
 #[derive(JSTraceable, HeapSizeOf)]
@@ -613,6 +655,16 @@ impl Document {
 
         self.focused.set(self.possibly_focused.get().r());
 
+        // Only enable the embedder's input method while an editable field has focus.
+        let input_type = self.focused.get().map_or(InputType::None, |elem| ime_input_type(&elem));
+        let ime_state = ImeState {
+            enabled: input_type != InputType::None,
+            input_type: input_type,
+            caret_rect: None,
+        };
+        let event = ConstellationMsg::SetImeState(self.window.pipeline(), ime_state);
+        self.window.constellation_chan().send(event).unwrap();
+
         if let Some(ref elem) = self.focused.get() {
             elem.set_focus_state(true);
             let node = elem.upcast::<Node>();
@@ -623,10 +675,101 @@ impl Document {
             if focus_type == FocusType::Element {
                 let event = ConstellationMsg::Focus(self.window.pipeline());
                 self.window.constellation_chan().send(event).unwrap();
+                self.ensure_focus_visible(elem);
             }
         }
     }
 
+    /// Moves focus to the nearest focusable element in the given direction from `from`, or from
+    /// the focused element when `from` is `None`. If there is no such element, the search
+    /// continues in the parent frame's document.
+    // https://drafts.csswg.org/css-nav-1/#nav
+    pub fn navigate_spatially(&self, direction: SpatialDirection, from: Option<Rect<Au>>) {
+        let focused = self.get_focused_element();
+        let from = match from.or_else(|| focused.as_ref().map(|elem| elem.upcast::<Node>().bounding_content_box())) {
+            Some(rect) => rect,
+            None => {
+                // With nothing focused, start from the edge of the viewport the navigation
+                // moves away from.
+                let (x, y) = (Au::from_px(self.window.ScrollX()), Au::from_px(self.window.ScrollY()));
+                let (width, height) = (Au::from_px(self.window.InnerWidth()), Au::from_px(self.window.InnerHeight()));
+                match direction {
+                    SpatialDirection::Up => Rect::new(Point2D::new(x, y + height), Size2D::new(width, Au(0))),
+                    SpatialDirection::Down => Rect::new(Point2D::new(x, y), Size2D::new(width, Au(0))),
+                    SpatialDirection::Left => Rect::new(Point2D::new(x + width, y), Size2D::new(Au(0), height)),
+                    SpatialDirection::Right => Rect::new(Point2D::new(x, y), Size2D::new(Au(0), height)),
+                }
+            }
+        };
+
+        let target = self.upcast::<Node>()
+            .traverse_preorder()
+            .filter_map(Root::downcast::<Element>)
+            .filter(|elem| elem.is_focusable_area() && focused.as_ref() != Some(elem))
+            .filter_map(|elem| {
+                let rect = elem.upcast::<Node>().bounding_content_box();
+                spatial_navigation_distance(&from, &rect, direction).map(|distance| (distance, elem))
+            })
+            .min_by_key(|&(distance, _)| distance);
+
+        match target {
+            Some((_, elem)) => {
+                self.begin_focus_transaction();
+                self.request_focus(&elem);
+                self.commit_focus_transaction(FocusType::Element);
+            }
+            None => {
+                let event = ConstellationMsg::SpatialNavigate(self.window.pipeline(), direction);
+                self.window.constellation_chan().send(event).unwrap();
+            }
+        }
+    }
+
+    /// Scrolls the newly focused element into view if it is outside the viewport, and reports it
+    /// to the embedder for assistive technology.
+    fn ensure_focus_visible(&self, elem: &Element) {
+        let node = elem.upcast::<Node>();
+        let rect = node.bounding_content_box();
+        let (x, y) = (rect.origin.x.to_f64_px(), rect.origin.y.to_f64_px());
+        let (right, bottom) = (rect.max_x().to_f64_px(), rect.max_y().to_f64_px());
+        let (scroll_x, scroll_y) = (self.window.ScrollX() as f64, self.window.ScrollY() as f64);
+        if x < scroll_x || y < scroll_y ||
+           right > scroll_x + self.window.InnerWidth() as f64 ||
+           bottom > scroll_y + self.window.InnerHeight() as f64 {
+            self.window.scroll(x, y, ScrollBehavior::Instant);
+        }
+
+        let event = ConstellationMsg::EnsureFocusVisible(self.window.pipeline(), ElementId(node.unique_id()));
+        self.window.constellation_chan().send(event).unwrap();
+    }
+
+    /// Notes that an element with an `accesskey` attribute was added, removed or changed, so that
+    /// the document's shortcuts are sent to the embedder at the next reflow.
+    pub fn access_keys_changed(&self) {
+        self.access_keys_changed_since_reflow.set(true);
+    }
+
+    /// Sends the document's `accesskey` shortcuts to the embedder if they may have changed since
+    /// the last reflow.
+    pub fn send_access_keys_if_changed(&self) {
+        if !self.access_keys_changed_since_reflow.get() || self.browsing_context().is_none() {
+            return;
+        }
+        self.access_keys_changed_since_reflow.set(false);
+        // https://html.spec.whatwg.org/multipage/#assigned-access-key
+        let access_keys = self.upcast::<Node>()
+            .traverse_preorder()
+            .filter_map(Root::downcast::<Element>)
+            .filter_map(|elem| {
+                let value = elem.get_string_attribute(&atom!("accesskey"));
+                let key = value.split_whitespace().find(|key| key.chars().count() == 1).map(String::from);
+                key.map(|key| (key, ElementId(elem.upcast::<Node>().unique_id())))
+            })
+            .collect();
+        let event = ConstellationMsg::SetAccessKeys(self.window.pipeline(), access_keys);
+        self.window.constellation_chan().send(event).unwrap();
+    }
+
     /// Handles any updates when the document's title has changed.
     pub fn title_changed(&self) {
         if self.browsing_context().is_some() {
@@ -1169,6 +1312,17 @@ impl Document {
                     }
                 }
             }
+            Key::Up | Key::Down | Key::Left | Key::Right
+                if !prevented && state != KeyState::Released &&
+                   PREFS.get("dom.spatial_navigation.enabled").as_boolean().unwrap_or(false) => {
+                let direction = match key {
+                    Key::Up => SpatialDirection::Up,
+                    Key::Down => SpatialDirection::Down,
+                    Key::Left => SpatialDirection::Left,
+                    _ => SpatialDirection::Right,
+                };
+                self.navigate_spatially(direction, None);
+            }
             _ => (),
         }
 
@@ -1329,6 +1483,8 @@ impl Document {
         let performance = performance.r();
         let timing = performance.Now();
 
+        let frame_start = time::precise_time_ns();
+        let raf_callbacks = animation_frame_list.iter().filter(|&&(_, ref callback)| callback.is_some()).count();
         for (_, callback) in animation_frame_list.drain(..) {
             if let Some(callback) = callback {
                 callback(*timing);
@@ -1348,10 +1504,26 @@ impl Document {
         }
 
         self.running_animation_callbacks.set(false);
+        let script_end = time::precise_time_ns();
 
         self.window.reflow(ReflowGoal::ForDisplay,
                            ReflowQueryType::NoQuery,
                            ReflowReason::RequestAnimationFrame);
+        let layout_end = time::precise_time_ns();
+
+        let frame_stats = FrameStats {
+            raf_callbacks: raf_callbacks as u32,
+            script_ms: (script_end - frame_start) as f32 / 1_000_000.0,
+            layout_ms: (layout_end - script_end) as f32 / 1_000_000.0,
+            dropped: layout_end - frame_start > FRAME_BUDGET_NS,
+        };
+        let mut pending_frame_stats = self.pending_frame_stats.borrow_mut();
+        pending_frame_stats.push(frame_stats);
+        if pending_frame_stats.len() >= FRAME_STATS_BATCH {
+            let frame_stats = mem::replace(&mut *pending_frame_stats, vec![]);
+            let event = ConstellationMsg::ReportFrameStats(self.window.pipeline(), frame_stats);
+            self.window.constellation_chan().send(event).unwrap();
+        }
     }
 
     /// Add a load to the list of loads blocking this document's load.
@@ -1502,11 +1674,36 @@ impl Document {
 
     pub fn notify_constellation_load(&self) {
         let pipeline_id = self.window.pipeline();
+        let initial_focus = self.initial_focus().map(|elem| ElementId(elem.upcast::<Node>().unique_id()));
+        let event = ConstellationMsg::SetInitialFocus(pipeline_id, initial_focus);
+        self.window.constellation_chan().send(event).unwrap();
+
         let event = ConstellationMsg::DOMLoad(pipeline_id);
         self.window.constellation_chan().send(event).unwrap();
 
     }
 
+    /// The element that should have focus once the document has loaded: the focused element,
+    /// if script focused one, or else the first element with an `autofocus` attribute.
+    fn initial_focus(&self) -> Option<Root<Element>> {
+        self.focused.get().or_else(|| {
+            self.upcast::<Node>()
+                .traverse_preorder()
+                .filter_map(Root::downcast::<Element>)
+                .find(|elem| elem.has_attribute(&atom!("autofocus")))
+        })
+    }
+
+    /// Finds the element containing the first text that matches a text fragment directive.
+    /// Matching is case-insensitive, and only looks within single text nodes.
+    pub fn find_text_fragment(&self, directive: &TextFragmentDirective) -> Option<Root<Element>> {
+        self.upcast::<Node>()
+            .traverse_preorder()
+            .filter_map(Root::downcast::<Text>)
+            .find(|text| text_directive_matches(&text.upcast::<CharacterData>().data().to_lowercase(), directive))
+            .and_then(|text| text.upcast::<Node>().GetParentElement())
+    }
+
     pub fn set_current_parser(&self, script: Option<ParserRef>) {
         self.current_parser.set(script);
     }
@@ -1624,6 +1821,17 @@ fn url_has_network_scheme(url: &Url) -> bool {
     }
 }
 
+/// The kind of text entry the embedder's input method should offer while the element has focus.
+fn ime_input_type(element: &Element) -> InputType {
+    if let Some(input) = element.downcast::<HTMLInputElement>() {
+        return input.ime_input_type();
+    }
+    match element.downcast::<HTMLTextAreaElement>() {
+        Some(textarea) if !textarea.ReadOnly() => InputType::Text,
+        _ => InputType::None,
+    }
+}
+
 impl Document {
     pub fn new_inherited(window: &Window,
                          browsing_context: Option<&BrowsingContext>,
@@ -1699,6 +1907,7 @@ impl Document {
             applets: Default::default(),
             stylesheets: DOMRefCell::new(None),
             stylesheets_changed_since_reflow: Cell::new(false),
+            access_keys_changed_since_reflow: Cell::new(false),
             ready_state: Cell::new(ready_state),
             domcontentloaded_dispatched: Cell::new(domcontentloaded_dispatched),
             possibly_focused: Default::default(),
@@ -1713,6 +1922,7 @@ impl Document {
             animation_frame_ident: Cell::new(0),
             animation_frame_list: DOMRefCell::new(vec![]),
             running_animation_callbacks: Cell::new(false),
+            pending_frame_stats: DOMRefCell::new(vec![]),
             loader: DOMRefCell::new(doc_loader),
             current_parser: Default::default(),
             reflow_timeout: Cell::new(None),
@@ -1732,6 +1942,8 @@ impl Document {
             origin: origin,
             referrer: referrer,
             referrer_policy: Cell::new(referrer_policy),
+            reported_deprecations: DOMRefCell::new(HashSet::new()),
+            last_activation: Cell::new(None),
         }
     }
 
@@ -1877,6 +2089,39 @@ impl Document {
     pub fn get_referrer_policy(&self) -> Option<ReferrerPolicy> {
         return self.referrer_policy.get();
     }
+
+    // https://html.spec.whatwg.org/multipage/#activation-notification
+    pub fn notify_user_activation(&self) {
+        self.last_activation.set(Some(precise_time_ms().get()));
+    }
+
+    // https://html.spec.whatwg.org/multipage/#transient-activation
+    pub fn has_transient_activation(&self) -> bool {
+        self.last_activation.get().map_or(false, |activated_at| {
+            precise_time_ms().get() < activated_at + USER_ACTIVATION_DURATION_MS
+        })
+    }
+
+    /// Reports the use of a deprecated feature to the constellation, unless it was already
+    /// reported for this document.
+    pub fn report_deprecation(&self, info: DeprecationInfo) {
+        if !self.reported_deprecations.borrow_mut().insert(info.feature_id.clone()) {
+            return;
+        }
+        let msg = ConstellationMsg::DeprecationReport(self.window.pipeline(), info);
+        self.window.constellation_chan().send(msg).unwrap();
+    }
+
+    /// Reports a call to one of the `captureEvents()` and `releaseEvents()` methods, which have
+    /// done nothing since event capture was standardized.
+    pub fn report_legacy_event_capture(&self, method: &str) {
+        self.report_deprecation(DeprecationInfo {
+            feature_id: String::from("legacy-event-capture"),
+            message: format!("{} does nothing, and can be removed.", method),
+            source: None,
+            anticipated_removal: None,
+        });
+    }
 }
 
 
@@ -2762,11 +3007,13 @@ impl DocumentMethods for Document {
     // https://html.spec.whatwg.org/multipage/#dom-document-captureevents
     fn CaptureEvents(&self) {
         // This method intentionally does nothing
+        self.report_legacy_event_capture("document.captureEvents()");
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-document-releaseevents
     fn ReleaseEvents(&self) {
         // This method intentionally does nothing
+        self.report_legacy_event_capture("document.releaseEvents()");
     }
 
     // https://html.spec.whatwg.org/multipage/#globaleventhandlers
@@ -2928,6 +3175,71 @@ impl Runnable for DocumentProgressHandler {
     }
 }
 
+/// Parses the directives after the `:~:` of a URL fragment, returning the first text directive.
+/// https://wicg.github.io/scroll-to-text-fragment/#parsing-the-fragment-directive
+pub fn parse_text_directive(directives: &str) -> Option<TextFragmentDirective> {
+    let value = match directives.split('&').find(|directive| directive.starts_with("text=")) {
+        Some(directive) => &directive["text=".len()..],
+        None => return None,
+    };
+    let decode = |term: &str| percent_decode(term.as_bytes()).decode_utf8_lossy().into_owned();
+
+    let mut terms: Vec<&str> = value.split(',').collect();
+    let prefix = match terms.first() {
+        Some(term) if term.ends_with('-') => Some(decode(&term[..term.len() - 1])),
+        _ => None,
+    };
+    if prefix.is_some() {
+        terms.remove(0);
+    }
+    let suffix = match terms.last() {
+        Some(term) if term.starts_with('-') => Some(decode(&term[1..])),
+        _ => None,
+    };
+    if suffix.is_some() {
+        terms.pop();
+    }
+    let (start, end) = match terms.len() {
+        1 => (decode(terms[0]), None),
+        2 => (decode(terms[0]), Some(decode(terms[1]))),
+        _ => return None,
+    };
+    if start.is_empty() {
+        return None;
+    }
+
+    Some(TextFragmentDirective {
+        prefix: prefix,
+        start: start,
+        end: end,
+        suffix: suffix,
+    })
+}
+
+/// Whether some text, already lowercased, contains a match for a text fragment directive.
+fn text_directive_matches(text: &str, directive: &TextFragmentDirective) -> bool {
+    let start = directive.start.to_lowercase();
+    text.match_indices(&*start).any(|(index, _)| {
+        if let Some(ref prefix) = directive.prefix {
+            if !text[..index].trim_right().ends_with(&*prefix.to_lowercase()) {
+                return false;
+            }
+        }
+        let mut rest = &text[index + start.len()..];
+        if let Some(ref end) = directive.end {
+            let end = end.to_lowercase();
+            rest = match rest.find(&*end) {
+                Some(end_index) => &rest[end_index + end.len()..],
+                None => return false,
+            };
+        }
+        match directive.suffix {
+            Some(ref suffix) => rest.trim_left().starts_with(&*suffix.to_lowercase()),
+            None => true,
+        }
+    })
+}
+
 /// Specifies the type of focus event that is sent to a pipeline
 #[derive(Copy, Clone, PartialEq)]
 pub enum FocusType {
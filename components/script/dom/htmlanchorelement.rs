@@ -24,7 +24,7 @@ use dom::node::{Node, document_from_node, window_from_node};
 use dom::urlhelper::UrlHelper;
 use dom::virtualmethods::VirtualMethods;
 use num_traits::ToPrimitive;
-use script_traits::MozBrowserEvent;
+use script_traits::{MozBrowserEvent, NavigationReason};
 use std::default::Default;
 use string_cache::Atom;
 use style::attr::AttrValue;
@@ -588,5 +588,5 @@ fn follow_hyperlink(subject: &Element, hyperlink_suffix: Option<String>) {
 
     debug!("following hyperlink to {}", url);
     let window = document.window();
-    window.load_url(url);
+    window.load_url(url, NavigationReason::LinkClick);
 }
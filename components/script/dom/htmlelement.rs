@@ -25,7 +25,7 @@ use dom::htmlframesetelement::HTMLFrameSetElement;
 use dom::htmlhtmlelement::HTMLHtmlElement;
 use dom::htmlinputelement::HTMLInputElement;
 use dom::htmllabelelement::HTMLLabelElement;
-use dom::node::{Node, SEQUENTIALLY_FOCUSABLE};
+use dom::node::{Node, SEQUENTIALLY_FOCUSABLE, UnbindContext};
 use dom::node::{document_from_node, window_from_node};
 use dom::nodelist::NodeList;
 use dom::virtualmethods::VirtualMethods;
@@ -516,6 +516,11 @@ impl VirtualMethods for HTMLElement {
                                                       // FIXME(ajeffrey): Convert directly from AttrValue to DOMString
                                                       DOMString::from(&**attr.value()));
             },
+            (&atom!("accesskey"), _) => {
+                if self.upcast::<Node>().is_in_doc() {
+                    document_from_node(self).access_keys_changed();
+                }
+            },
             _ => {}
         }
     }
@@ -525,5 +530,15 @@ impl VirtualMethods for HTMLElement {
             s.bind_to_tree(tree_in_doc);
         }
         self.update_sequentially_focusable_status();
+        if tree_in_doc && self.upcast::<Element>().has_attribute(&atom!("accesskey")) {
+            document_from_node(self).access_keys_changed();
+        }
+    }
+
+    fn unbind_from_tree(&self, context: &UnbindContext) {
+        self.super_type().unwrap().unbind_from_tree(context);
+        if context.tree_in_doc && self.upcast::<Element>().has_attribute(&atom!("accesskey")) {
+            document_from_node(self).access_keys_changed();
+        }
     }
 }
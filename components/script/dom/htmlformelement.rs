@@ -47,6 +47,7 @@ use hyper::method::Method;
 use msg::constellation_msg::{LoadData, PipelineId};
 use rand::random;
 use script_thread::{MainThreadScriptMsg, Runnable};
+use script_traits::NavigationReason;
 use std::borrow::ToOwned;
 use std::cell::Cell;
 use std::sync::mpsc::Sender;
@@ -942,7 +943,8 @@ impl Runnable for PlannedNavigation {
     fn handler(self: Box<PlannedNavigation>) {
         if self.generation_id == self.form.root().generation_id.get() {
             let script_chan = self.script_chan.clone();
-            script_chan.send(MainThreadScriptMsg::Navigate(self.pipeline_id, self.load_data)).unwrap();
+            let msg = MainThreadScriptMsg::Navigate(self.pipeline_id, self.load_data, NavigationReason::FormSubmit);
+            script_chan.send(msg).unwrap();
         }
     }
 }
@@ -37,6 +37,7 @@ use msg::constellation_msg::Key;
 use net_traits::IpcSend;
 use net_traits::blob_url_store::get_blob_origin;
 use net_traits::filemanager_thread::{FileManagerThreadMsg, FilterPattern};
+use script_traits::InputType as ImeInputType;
 use script_traits::ScriptMsg as ConstellationMsg;
 use std::borrow::ToOwned;
 use std::cell::Cell;
@@ -173,6 +174,23 @@ impl HTMLInputElement {
             InputType::InputFile => ValueMode::Filename,
         }
     }
+
+    /// The kind of text entry the embedder's input method should offer while this input has
+    /// focus.
+    pub fn ime_input_type(&self) -> ImeInputType {
+        if self.ReadOnly() {
+            return ImeInputType::None;
+        }
+        match self.input_type.get() {
+            InputType::InputPassword => ImeInputType::Password,
+            InputType::InputText => match &*self.type_() {
+                "email" => ImeInputType::Email,
+                "number" => ImeInputType::Number,
+                _ => ImeInputType::Text,
+            },
+            _ => ImeInputType::None,
+        }
+    }
 }
 
 pub trait LayoutHTMLInputElementHelpers {
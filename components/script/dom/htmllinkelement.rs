@@ -249,8 +249,9 @@ impl HTMLLinkElement {
         let document = document_from_node(self);
         match document.base_url().join(href) {
             Ok(url) => {
-                let event = ConstellationMsg::NewFavicon(url.clone());
-                document.window().constellation_chan().send(event).unwrap();
+                let window = document.window();
+                let event = ConstellationMsg::NewFavicon(window.pipeline(), url.clone());
+                window.constellation_chan().send(event).unwrap();
 
                 let mozbrowser_event = match *sizes {
                     Some(ref sizes) => MozBrowserEvent::IconChange(rel.to_owned(), url.to_string(), sizes.to_owned()),
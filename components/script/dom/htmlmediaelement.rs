@@ -29,6 +29,7 @@ use ipc_channel::router::ROUTER;
 use net_traits::{AsyncResponseListener, AsyncResponseTarget, Metadata, NetworkError};
 use network_listener::{NetworkListener, PreInvoke};
 use script_thread::{Runnable, ScriptThread};
+use script_traits::{AutoplayContext, InterventionKind, ScriptMsg as ConstellationMsg};
 use std::cell::Cell;
 use std::sync::{Arc, Mutex};
 use string_cache::Atom;
@@ -355,15 +356,7 @@ impl HTMLMediaElement {
                 if self.autoplaying.get() &&
                    self.Paused() &&
                    self.Autoplay() {
-                    // Step 1
-                    self.paused.set(false);
-                    // TODO step 2: show poster
-                    // Step 3
-                    self.queue_fire_simple_event("play");
-                    // Step 4
-                    self.queue_notify_about_playing();
-                    // Step 5
-                    self.autoplaying.set(false);
+                    self.check_autoplay_policy();
                 }
 
                 self.queue_fire_simple_event("canplaythrough");
@@ -375,6 +368,62 @@ impl HTMLMediaElement {
         // TODO Step 2: media controller
     }
 
+    /// Asks the embedder's autoplay policy whether this element may start playing on its own.
+    /// The answer arrives as a task, which autoplays the element if it still should.
+    fn check_autoplay_policy(&self) {
+        let document = document_from_node(self);
+        // The `muted` IDL attribute isn't implemented, so the content attribute (which sets its
+        // default) stands in for it.
+        let context = AutoplayContext {
+            muted: self.upcast::<Element>().has_attribute(&Atom::from("muted")),
+            has_user_gesture: document.has_transient_activation(),
+        };
+        let window = document.window();
+        let (sender, receiver) = ipc::channel().unwrap();
+        let trusted_elem = Trusted::new(self);
+        let generation_id = self.generation_id.get();
+        let task_source = window.dom_manipulation_task_source();
+        let wrapper = window.get_runnable_wrapper();
+        ROUTER.add_route(receiver.to_opaque(), box move |message| {
+            let task = box AutoplayPolicyTask {
+                elem: trusted_elem.clone(),
+                generation_id: generation_id,
+                allowed: message.to().unwrap_or(false),
+            };
+            let _ = task_source.queue_with_wrapper(task, &wrapper);
+        });
+        let msg = ConstellationMsg::CheckAutoplayAllowed(window.pipeline(), context, sender);
+        window.constellation_chan().send(msg).unwrap();
+    }
+
+    // https://html.spec.whatwg.org/multipage/#ready-states
+    // The autoplay steps for a new ready state of HAVE_ENOUGH_DATA, once the autoplay policy
+    // has been consulted.
+    fn autoplay(&self, allowed: bool) {
+        if !self.autoplaying.get() || !self.Paused() || !self.Autoplay() {
+            return;
+        }
+
+        if !allowed {
+            let window = window_from_node(self);
+            let message = String::from("Autoplay was blocked by the autoplay policy.");
+            let kind = InterventionKind::BlockedAutoplay;
+            let msg = ConstellationMsg::InterventionReport(window.pipeline(), kind, message);
+            window.constellation_chan().send(msg).unwrap();
+            return;
+        }
+
+        // Step 1
+        self.paused.set(false);
+        // TODO step 2: show poster
+        // Step 3
+        self.queue_fire_simple_event("play");
+        // Step 4
+        self.queue_notify_about_playing();
+        // Step 5
+        self.autoplaying.set(false);
+    }
+
     // https://html.spec.whatwg.org/multipage/#concept-media-load-algorithm
     fn invoke_resource_selection_algorithm(&self) {
         // Step 1
@@ -809,6 +858,24 @@ impl Runnable for PauseIfNotInDocumentTask {
     }
 }
 
+struct AutoplayPolicyTask {
+    elem: Trusted<HTMLMediaElement>,
+    /// The generation of the media element when the autoplay policy was consulted.
+    generation_id: u32,
+    allowed: bool,
+}
+
+impl Runnable for AutoplayPolicyTask {
+    fn name(&self) -> &'static str { "AutoplayPolicyTask" }
+
+    fn handler(self: Box<AutoplayPolicyTask>) {
+        let elem = self.elem.root();
+        if elem.generation_id.get() == self.generation_id {
+            elem.autoplay(self.allowed);
+        }
+    }
+}
+
 enum ResourceSelectionMode {
     Object,
     Attribute(String),
@@ -2,6 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use cssparser::{Color, Parser};
 use dom::attr::Attr;
 use dom::bindings::cell::DOMRefCell;
 use dom::bindings::codegen::Bindings::HTMLMetaElementBinding;
@@ -14,8 +15,9 @@ use dom::document::Document;
 use dom::element::{AttributeMutation, Element};
 use dom::htmlelement::HTMLElement;
 use dom::htmlheadelement::HTMLHeadElement;
-use dom::node::{Node, UnbindContext, document_from_node};
+use dom::node::{Node, UnbindContext, document_from_node, window_from_node};
 use dom::virtualmethods::VirtualMethods;
+use script_traits::ScriptMsg as ConstellationMsg;
 use std::ascii::AsciiExt;
 use std::sync::Arc;
 use string_cache::Atom;
@@ -67,6 +69,10 @@ impl HTMLMetaElement {
             if name == "referrer" {
                 self.apply_referrer();
             }
+
+            if name == "theme-color" {
+                self.apply_theme_color();
+            }
         }
     }
 
@@ -94,6 +100,24 @@ impl HTMLMetaElement {
         }
     }
 
+    /// https://html.spec.whatwg.org/multipage/#meta-theme-color
+    fn apply_theme_color(&self) {
+        let element = self.upcast::<Element>();
+        if let Some(content) = element.get_attribute(&ns!(), &atom!("content")).r() {
+            let content = content.value();
+            let mut parser = Parser::new(content.trim_matches(HTML_SPACE_CHARACTERS));
+            let color = Color::parse(&mut parser);
+            if !parser.is_exhausted() {
+                return;
+            }
+            if let Ok(Color::RGBA(color)) = color {
+                let window = window_from_node(self);
+                let event = ConstellationMsg::SetThemeColor(window.pipeline(), color);
+                window.constellation_chan().send(event).unwrap();
+            }
+        }
+    }
+
     fn process_referrer_attribute(&self) {
         let element = self.upcast::<Element>();
         if let Some(name) = element.get_attribute(&ns!(), &atom!("name")).r() {
@@ -10,6 +10,7 @@ use dom::bindings::reflector::{Reflector, reflect_dom_object};
 use dom::bindings::str::{DOMString, USVString};
 use dom::urlhelper::UrlHelper;
 use dom::window::Window;
+use script_traits::NavigationReason;
 use url::Url;
 
 #[dom_struct]
@@ -40,7 +41,7 @@ impl Location {
                          setter: fn(&mut Url, USVString)) {
         let mut url = self.window.get_url();
         setter(&mut url, value);
-        self.window.load_url(url);
+        self.window.load_url(url, NavigationReason::Script);
     }
 }
 
@@ -51,13 +52,13 @@ impl LocationMethods for Location {
         //       _entry settings object_.
         let base_url = self.window.get_url();
         if let Ok(url) = base_url.join(&url.0) {
-            self.window.load_url(url);
+            self.window.load_url(url, NavigationReason::Script);
         }
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-location-reload
     fn Reload(&self) {
-        self.window.load_url(self.get_url());
+        self.window.load_url(self.get_url(), NavigationReason::Reload);
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-location-hash
@@ -106,7 +107,7 @@ impl LocationMethods for Location {
     // https://html.spec.whatwg.org/multipage/#dom-location-href
     fn SetHref(&self, value: USVString) {
         if let Ok(url) = self.window.get_url().join(&value.0) {
-            self.window.load_url(url);
+            self.window.load_url(url, NavigationReason::Script);
         }
     }
 
@@ -186,6 +186,11 @@ impl AsyncResponseListener for ParserContext {
 }
 
 impl PreInvoke for ParserContext {
+    fn should_invoke(&self) -> bool {
+        // The constellation closes a pipeline whose response is downloaded or ignored instead of
+        // replacing the current document, so the rest of the response goes unparsed.
+        !ScriptThread::is_pipeline_closed(self.id)
+    }
 }
 
 #[dom_struct]
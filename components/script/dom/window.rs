@@ -63,9 +63,9 @@ use script_runtime::{ScriptChan, ScriptPort, maybe_take_panic_result};
 use script_thread::SendableMainThreadScriptChan;
 use script_thread::{MainThreadScriptChan, MainThreadScriptMsg, RunnableWrapper};
 use script_traits::webdriver_msg::{WebDriverJSError, WebDriverJSResult};
-use script_traits::{ConstellationControlMsg, UntrustedNodeAddress};
-use script_traits::{DocumentState, MsDuration, TimerEvent, TimerEventId};
-use script_traits::{ScriptMsg as ConstellationMsg, TimerEventRequest, TimerSource, WindowSizeData};
+use script_traits::{ConstellationControlMsg, DocumentState, MsDuration, NavigationReason};
+use script_traits::{ScriptMsg as ConstellationMsg, TimerEvent, TimerEventId};
+use script_traits::{TimerEventRequest, TimerSource, UntrustedNodeAddress, WindowSizeData};
 use std::ascii::AsciiExt;
 use std::borrow::ToOwned;
 use std::cell::Cell;
@@ -651,11 +651,13 @@ impl WindowMethods for Window {
     // https://html.spec.whatwg.org/multipage/#dom-window-captureevents
     fn CaptureEvents(&self) {
         // This method intentionally does nothing
+        self.Document().report_legacy_event_capture("window.captureEvents()");
     }
 
     // https://html.spec.whatwg.org/multipage/#dom-window-releaseevents
     fn ReleaseEvents(&self) {
         // This method intentionally does nothing
+        self.Document().report_legacy_event_capture("window.releaseEvents()");
     }
 
     // check-tidy: no specs after this line
@@ -1164,6 +1166,10 @@ impl Window {
             debug!("Document doesn't need reflow - skipping it (reason {:?})", reason);
         }
 
+        if for_display {
+            self.Document().send_access_keys_if_changed();
+        }
+
         // If writing a screenshot, check if the script has reached a state
         // where it's safe to write the image. This means that:
         // 1) The reflow is for display (otherwise it could be a query)
@@ -1360,11 +1366,21 @@ impl Window {
     }
 
     /// Commence a new URL load which will either replace this window or scroll to a fragment.
-    pub fn load_url(&self, url: Url) {
+    pub fn load_url(&self, url: Url, reason: NavigationReason) {
         let doc = self.Document();
         self.main_thread_script_chan().send(
             MainThreadScriptMsg::Navigate(self.id,
-                LoadData::new(url, doc.get_referrer_policy(), Some(doc.url().clone())))).unwrap();
+                LoadData::new(url, doc.get_referrer_policy(), Some(doc.url().clone())),
+                reason)).unwrap();
+    }
+
+    /// Save the scroll position on the outgoing session history entry, for it to be restored
+    /// if history is traversed back to it.
+    pub fn save_scroll_position(&self) {
+        let origin = self.current_viewport.get().origin;
+        let point = Point2D::new(origin.x.to_f32_px(), origin.y.to_f32_px());
+        let msg = ConstellationMsg::SaveScrollPosition(self.pipeline(), LayerId::null(), point);
+        self.constellation_chan.send(msg).unwrap();
     }
 
     pub fn handle_fire_timer(&self, timer_id: TimerEventId) {
@@ -24,7 +24,9 @@ use devtools_traits::{ScriptToDevtoolsControlMsg, WorkerId};
 use document_loader::DocumentLoader;
 use dom::bindings::cell::DOMRefCell;
 use dom::bindings::codegen::Bindings::DocumentBinding::{DocumentMethods, DocumentReadyState};
+use dom::bindings::codegen::Bindings::HTMLElementBinding::HTMLElementMethods;
 use dom::bindings::codegen::Bindings::LocationBinding::LocationMethods;
+use dom::bindings::codegen::Bindings::NodeBinding::NodeMethods;
 use dom::bindings::codegen::Bindings::WindowBinding::WindowMethods;
 use dom::bindings::conversions::{FromJSValConvertible, StringificationBehavior};
 use dom::bindings::global::GlobalRef;
@@ -37,10 +39,13 @@ use dom::bindings::str::DOMString;
 use dom::bindings::trace::JSTraceable;
 use dom::bindings::utils::WRAP_CALLBACKS;
 use dom::browsingcontext::BrowsingContext;
+use dom::document::parse_text_directive;
 use dom::document::{Document, DocumentProgressHandler, DocumentSource, FocusType, IsHTMLDocument};
 use dom::element::Element;
 use dom::event::{Event, EventBubbles, EventCancelable};
 use dom::htmlanchorelement::HTMLAnchorElement;
+use dom::htmlareaelement::HTMLAreaElement;
+use dom::htmlelement::HTMLElement;
 use dom::node::{Node, NodeDamage, window_from_node};
 use dom::serviceworker::TrustedServiceWorkerAddress;
 use dom::serviceworkerregistration::ServiceWorkerRegistration;
@@ -51,11 +56,12 @@ use dom::worker::TrustedWorkerAddress;
 use euclid::Rect;
 use euclid::point::Point2D;
 use gfx_traits::LayerId;
-use hyper::header::{ContentType, Headers, HttpDate, LastModified};
+use hyper::header::{ContentDisposition, ContentType, DispositionType, Headers, HttpDate, LastModified};
 use hyper::header::{ReferrerPolicy as ReferrerPolicyHeader};
+use hyper::http::RawStatus;
 use hyper::method::Method;
 use hyper::mime::{Mime, SubLevel, TopLevel};
-use ipc_channel::ipc::{self, IpcSender};
+use ipc_channel::ipc::{self, IpcReceiver, IpcSender};
 use ipc_channel::router::ROUTER;
 use js::glue::GetWindowProxyClass;
 use js::jsapi::{DOMProxyShadowsResult, HandleId, HandleObject};
@@ -64,13 +70,13 @@ use js::jsapi::{JSTracer, SetWindowProxyClass};
 use js::jsval::UndefinedValue;
 use js::rust::Runtime;
 use mem::heap_size_of_self_and_children;
-use msg::constellation_msg::{FrameType, LoadData, PanicMsg, PipelineId, PipelineNamespace};
+use msg::constellation_msg::{FrameType, Key, KeyState, LoadData, PanicMsg, PipelineId, PipelineNamespace};
 use msg::constellation_msg::{ReferrerPolicy, SubpageId, WindowSizeType};
 use net_traits::LoadData as NetLoadData;
 use net_traits::bluetooth_thread::BluetoothMethodMsg;
 use net_traits::image_cache_thread::{ImageCacheChan, ImageCacheResult, ImageCacheThread};
 use net_traits::{AsyncResponseTarget, CoreResourceMsg, LoadConsumer, LoadContext, Metadata, ResourceThreads};
-use net_traits::{RequestSource, CustomResponse, CustomResponseSender, IpcSend};
+use net_traits::{RequestSource, CustomResponse, CustomResponseSender, IpcSend, ResponseAction};
 use network_listener::NetworkListener;
 use parse::ParserRoot;
 use parse::html::{ParseContext, parse_html};
@@ -83,11 +89,12 @@ use script_runtime::{ScriptPort, StackRootTLS, new_rt_and_cx, get_reports};
 use script_traits::CompositorEvent::{KeyEvent, MouseButtonEvent, MouseMoveEvent, ResizeEvent};
 use script_traits::CompositorEvent::{TouchEvent, TouchpadPressureEvent};
 use script_traits::webdriver_msg::WebDriverScriptCommand;
-use script_traits::{CompositorEvent, ConstellationControlMsg, EventResult};
-use script_traits::{InitialScriptState, MouseButton, MouseEventType, MozBrowserEvent};
-use script_traits::{NewLayoutInfo, ScriptMsg as ConstellationMsg};
-use script_traits::{ScriptThreadFactory, TimerEvent, TimerEventRequest, TimerSource};
-use script_traits::{TouchEventType, TouchId, UntrustedNodeAddress, WindowSizeData};
+use script_traits::{CompositorEvent, ConstellationControlMsg, ElementId, EventResult, FrameInfo, InitialScriptState};
+use script_traits::{MouseButton, MouseEventType, MozBrowserEvent, NavigationDisposition, NavigationReason};
+use script_traits::{NavigationType, NewLayoutInfo, Origin, PageLifecycleState, PipelineExitReason};
+use script_traits::{ScriptMsg as ConstellationMsg, ScriptThreadFactory, SpatialDirection, TimerEvent};
+use script_traits::{TimerEventRequest, TimerSource, Tooltip, TouchEventType, TouchId, UntrustedNodeAddress};
+use script_traits::WindowSizeData;
 use std::borrow::ToOwned;
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
@@ -98,7 +105,9 @@ use std::result::Result;
 use std::sync::atomic::{Ordering, AtomicBool};
 use std::sync::mpsc::{Receiver, Select, Sender, channel};
 use std::sync::{Arc, Mutex};
+use string_cache::Atom;
 use style::context::ReflowGoal;
+use style::str::split_html_space_chars;
 use task_source::TaskSource;
 use task_source::dom_manipulation::{DOMManipulationTaskSource, DOMManipulationTask};
 use task_source::file_reading::FileReadingTaskSource;
@@ -231,7 +240,7 @@ pub enum MainThreadScriptMsg {
     ExitWindow(PipelineId),
     /// Begins a content-initiated load on the specified pipeline (only
     /// dispatched to ScriptThread).
-    Navigate(PipelineId, LoadData),
+    Navigate(PipelineId, LoadData, NavigationReason),
     /// Tasks that originate from the DOM manipulation task source
     DOMManipulation(DOMManipulationTask),
     /// Tasks that originate from the user interaction task source
@@ -393,6 +402,9 @@ pub struct ScriptThread {
     /// The topmost element over the mouse.
     topmost_mouse_over_target: MutNullableHeap<JS<Element>>,
 
+    /// The element whose `title` the last tooltip came from.
+    tooltip_element: MutNullableHeap<JS<Element>>,
+
     /// List of pipelines that have been owned and closed by this script thread.
     closed_pipelines: DOMRefCell<HashSet<PipelineId>>,
 
@@ -446,10 +458,11 @@ impl<'a> Drop for ScriptMemoryFailsafe<'a> {
 impl ScriptThreadFactory for ScriptThread {
     type Message = message::Msg;
 
-    fn create(state: InitialScriptState,
+    fn create(mut state: InitialScriptState,
               load_data: LoadData)
               -> (Sender<message::Msg>, Receiver<message::Msg>) {
         let panic_chan = state.panic_chan.clone();
+        let preload_response = state.preload_response.take();
         let (script_chan, script_port) = channel();
 
         let (sender, receiver) = channel();
@@ -477,7 +490,7 @@ impl ScriptThreadFactory for ScriptThread {
 
             let new_load = InProgressLoad::new(id, parent_info, layout_chan, window_size,
                                                load_data.url.clone());
-            script_thread.start_page_load(new_load, load_data);
+            script_thread.start_page_load(new_load, load_data, preload_response);
 
             let reporter_name = format!("script-reporter-{}", id);
             mem_profiler_chan.run_with_memory_reporting(|| {
@@ -516,6 +529,13 @@ impl ScriptThread {
         });
     }
 
+    pub fn is_pipeline_closed(id: PipelineId) -> bool {
+        SCRIPT_THREAD_ROOT.with(|root| {
+            let script_thread = unsafe { &*root.get().unwrap() };
+            script_thread.closed_pipelines.borrow().contains(&id)
+        })
+    }
+
     pub fn parsing_complete(id: PipelineId) {
         SCRIPT_THREAD_ROOT.with(|root| {
             let script_thread = unsafe { &*root.get().unwrap() };
@@ -613,6 +633,7 @@ impl ScriptThread {
 
             js_runtime: Rc::new(runtime),
             topmost_mouse_over_target: MutNullableHeap::new(Default::default()),
+            tooltip_element: MutNullableHeap::new(Default::default()),
             closed_pipelines: DOMRefCell::new(HashSet::new()),
 
             scheduler_chan: state.scheduler_chan,
@@ -910,8 +931,8 @@ impl ScriptThread {
         match msg {
             ConstellationControlMsg::AttachLayout(_) =>
                 panic!("should have handled AttachLayout already"),
-            ConstellationControlMsg::Navigate(pipeline_id, subpage_id, load_data) =>
-                self.handle_navigate(pipeline_id, Some(subpage_id), load_data),
+            ConstellationControlMsg::Navigate(pipeline_id, subpage_id, load_data, reason) =>
+                self.handle_navigate(pipeline_id, Some(subpage_id), load_data, reason),
             ConstellationControlMsg::SendEvent(id, event) =>
                 self.handle_event(id, event),
             ConstellationControlMsg::ResizeInactive(id, new_size) =>
@@ -965,13 +986,26 @@ impl ScriptThread {
                 self.handle_css_error_reporting(pipeline_id, filename, line, column, msg),
             ConstellationControlMsg::Reload(pipeline_id) =>
                 self.handle_reload(pipeline_id),
+            ConstellationControlMsg::ActivateAccessKey(pipeline_id, element_id) =>
+                self.handle_activate_access_key(pipeline_id, element_id),
+            ConstellationControlMsg::ResolveLinkForContextMenu(pipeline_id, element_id, sender) =>
+                self.handle_resolve_link_for_context_menu(pipeline_id, element_id, sender),
+            ConstellationControlMsg::SpatialNavigate(pipeline_id, subpage_id, direction) =>
+                self.handle_spatial_navigate(pipeline_id, subpage_id, direction),
+            ConstellationControlMsg::Ping(pipeline_id, ping_id) => {
+                let _ = self.constellation_chan.send(ConstellationMsg::Pong(pipeline_id, ping_id));
+            }
+            ConstellationControlMsg::ClipboardChanged(pipeline_id) =>
+                self.handle_clipboard_changed(pipeline_id),
+            ConstellationControlMsg::RestoreFocus(pipeline_id, element_id) =>
+                self.handle_restore_focus(pipeline_id, element_id),
         }
     }
 
     fn handle_msg_from_script(&self, msg: MainThreadScriptMsg) {
         match msg {
-            MainThreadScriptMsg::Navigate(id, load_data) =>
-                self.handle_navigate(id, None, load_data),
+            MainThreadScriptMsg::Navigate(id, load_data, reason) =>
+                self.handle_navigate(id, None, load_data, reason),
             MainThreadScriptMsg::ExitWindow(id) =>
                 self.handle_exit_window_msg(id),
             MainThreadScriptMsg::DocumentLoadsComplete(id) =>
@@ -1212,7 +1246,7 @@ impl ScriptThread {
         let new_load = InProgressLoad::new(new_pipeline_id, Some((containing_pipeline_id, subpage_id, frame_type)),
                                            layout_chan, parent_window.window_size(),
                                            load_data.url.clone());
-        self.start_page_load(new_load, load_data);
+        self.start_page_load(new_load, load_data, None);
     }
 
     fn handle_loads_complete(&self, pipeline: PipelineId) {
@@ -1232,6 +1266,8 @@ impl ScriptThread {
         self.dom_manipulation_task_source.queue(handler, GlobalRef::Window(doc.window())).unwrap();
 
         self.constellation_chan.send(ConstellationMsg::LoadComplete(pipeline)).unwrap();
+        let msg = ConstellationMsg::ReportPageLifecycle(pipeline, PageLifecycleState::Active);
+        self.constellation_chan.send(msg).unwrap();
     }
 
     fn collect_reports(&self, reports_chan: ReportsChan) {
@@ -1317,6 +1353,9 @@ impl ScriptThread {
             if let Some(ref inner_context) = root_context.find(id) {
                 let window = inner_context.active_window();
                 window.freeze();
+                // TODO: Fire pagehide with persisted set, once PageTransitionEvent is supported.
+                let msg = ConstellationMsg::ReportPageLifecycle(id, PageLifecycleState::Frozen);
+                self.constellation_chan.send(msg).unwrap();
                 return;
             }
         }
@@ -1337,6 +1376,9 @@ impl ScriptThread {
             }
             let window = inner_context.active_window();
             window.thaw();
+            // TODO: Fire pageshow with persisted set, once PageTransitionEvent is supported.
+            let msg = ConstellationMsg::ReportPageLifecycle(id, PageLifecycleState::Resumed);
+            self.constellation_chan.send(msg).unwrap();
             return;
         }
         let mut loads = self.incomplete_loads.borrow_mut();
@@ -1433,7 +1475,7 @@ impl ScriptThread {
         // TODO(tkuehn): currently there is only one window,
         // so this can afford to be naive and just shut down the
         // constellation. In the future it'll need to be smarter.
-        self.constellation_chan.send(ConstellationMsg::Exit).unwrap();
+        self.constellation_chan.send(ConstellationMsg::Exit(None)).unwrap();
     }
 
     /// We have received notification that the response associated with a load has completed.
@@ -1506,7 +1548,9 @@ impl ScriptThread {
         if window.pipeline() == id {
             debug!("shutting down layout for root context {:?}", id);
             shut_down_layout(&context);
-            let _ = self.constellation_chan.send(ConstellationMsg::PipelineExited(id));
+            let msg = ConstellationMsg::ReportPageLifecycle(id, PageLifecycleState::Terminated);
+            let _ = self.constellation_chan.send(msg);
+            let _ = self.constellation_chan.send(ConstellationMsg::PipelineExited(id, PipelineExitReason::Normal));
             return true
         }
 
@@ -1514,7 +1558,9 @@ impl ScriptThread {
         if let Some(ref mut child_context) = context.remove(id) {
             shut_down_layout(&child_context);
         }
-        let _ = self.constellation_chan.send(ConstellationMsg::PipelineExited(id));
+        let msg = ConstellationMsg::ReportPageLifecycle(id, PageLifecycleState::Terminated);
+        let _ = self.constellation_chan.send(msg);
+        let _ = self.constellation_chan.send(ConstellationMsg::PipelineExited(id, PipelineExitReason::Normal));
         false
     }
 
@@ -1559,6 +1605,13 @@ impl ScriptThread {
             self.constellation_chan
                 .send(ConstellationMsg::SetFinalUrl(incomplete.pipeline_id, final_url.clone()))
                 .unwrap();
+
+            let disposition = navigation_disposition(&metadata);
+            self.constellation_chan
+                .send(ConstellationMsg::ResolveNavigationDisposition(incomplete.pipeline_id,
+                                                                    final_url.clone(),
+                                                                    disposition))
+                .unwrap();
         }
         debug!("ScriptThread: loading {} on pipeline {:?}", incomplete.url, incomplete.pipeline_id);
 
@@ -1582,6 +1635,23 @@ impl ScriptThread {
             })
         });
 
+        let origin = Origin::new(&final_url);
+        let parent_origin = frame_element.as_ref().map(|frame| Origin::new(frame.upcast::<Node>().owner_doc().url()));
+        let name = frame_element.as_ref().map(|frame| {
+            String::from(frame.upcast::<Element>().get_string_attribute(&atom!("name")))
+        }).and_then(|name| if name.is_empty() { None } else { Some(name) });
+        let frame_info = FrameInfo {
+            url: final_url.clone(),
+            // A parent that is not in this script thread is treated as cross-origin.
+            is_cross_origin: incomplete.parent_info.is_some() && parent_origin.map_or(true, |parent| parent != origin),
+            origin: origin,
+            name: name,
+            parent: incomplete.parent_info.map(|(parent_id, _, _)| parent_id),
+        };
+        self.constellation_chan
+            .send(ConstellationMsg::ReportFrameInfo(incomplete.pipeline_id, frame_info))
+            .unwrap();
+
         let MainThreadScriptChan(ref sender) = self.chan;
         let DOMManipulationTaskSource(ref dom_sender) = self.dom_manipulation_task_source;
         let UserInteractionTaskSource(ref user_sender) = self.user_interaction_task_source;
@@ -1755,6 +1825,14 @@ impl ScriptThread {
         self.constellation_chan
             .send(ConstellationMsg::ActivateDocument(incomplete.pipeline_id))
             .unwrap();
+        self.constellation_chan
+            .send(ConstellationMsg::SetDocumentEncoding(incomplete.pipeline_id,
+                                                        String::from(document.CharacterSet())))
+            .unwrap();
+        self.constellation_chan
+            .send(ConstellationMsg::SetContentType(incomplete.pipeline_id,
+                                                   String::from(document.ContentType())))
+            .unwrap();
 
         // Notify devtools that a new script global exists.
         self.notify_devtools(document.Title(), final_url.clone(), (browsing_context.pipeline(), None));
@@ -1839,6 +1917,32 @@ impl ScriptThread {
         }
     }
 
+    /// Scrolls to the part of the document indicated by a URL fragment: the text matched by its
+    /// text directive, if it has one that matches, or else the element it names.
+    fn scroll_to_fragment(&self, pipeline_id: PipelineId, document: &Document, fragment: &str) {
+        let mut fragment = fragment;
+        if let Some(index) = fragment.find(":~:") {
+            let directive = parse_text_directive(&fragment[index + ":~:".len()..]);
+            if let Some(directive) = directive {
+                if let Some(ref element) = document.find_text_fragment(&directive) {
+                    self.scroll_fragment_point(pipeline_id, element.r());
+                    let msg = ConstellationMsg::ScrollToTextFragment(pipeline_id, directive);
+                    self.constellation_chan.send(msg).unwrap();
+                    return;
+                }
+            }
+            // A directive that does not match is ignored, and an empty fragment before it must
+            // not scroll to the top of the document.
+            fragment = &fragment[..index];
+            if fragment.is_empty() {
+                return;
+            }
+        }
+        if let Some(ref element) = document.find_fragment_node(fragment) {
+            self.scroll_fragment_point(pipeline_id, element.r());
+        }
+    }
+
     fn scroll_fragment_point(&self, pipeline_id: PipelineId, element: &Element) {
         // FIXME(#8275, pcwalton): This is pretty bogus when multiple layers are involved.
         // Really what needs to happen is that this needs to go through layout to ask which
@@ -1885,6 +1989,9 @@ impl ScriptThread {
             }
 
             MouseButtonEvent(event_type, button, point) => {
+                if let MouseEventType::MouseDown = event_type {
+                    self.notify_user_activation(pipeline_id);
+                }
                 self.handle_mouse_event(pipeline_id, event_type, button, point);
             }
 
@@ -1905,6 +2012,18 @@ impl ScriptThread {
                     return;
                 }
 
+                // Notify Constellation about the tooltip of the topmost mouse over target, if
+                // the element it comes from changed.
+                let tooltip_element = self.topmost_mouse_over_target.get().and_then(|target| {
+                    title_element(&target)
+                });
+                if tooltip_element != self.tooltip_element.get() {
+                    self.tooltip_element.set(tooltip_element.r());
+                    let tooltip = tooltip_element.and_then(|element| tooltip_for_element(&document, &element));
+                    let event = ConstellationMsg::SetTooltip(pipeline_id, tooltip);
+                    self.constellation_chan.send(event).unwrap();
+                }
+
                 let mut state_already_changed = false;
 
                 // Notify Constellation about the topmost anchor mouse over target.
@@ -1913,15 +2032,14 @@ impl ScriptThread {
                                                 .inclusive_ancestors()
                                                 .filter_map(Root::downcast::<HTMLAnchorElement>)
                                                 .next() {
-                        let status = anchor.upcast::<Element>()
-                                           .get_attribute(&ns!(), &atom!("href"))
-                                           .and_then(|href| {
-                                               let value = href.value();
-                                               let url = document.url();
-                                               url.join(&value).map(|url| url.to_string()).ok()
-                                           });
-
-                        let event = ConstellationMsg::NodeStatus(status);
+                        let url = anchor.upcast::<Element>()
+                                        .get_attribute(&ns!(), &atom!("href"))
+                                        .and_then(|href| {
+                                            let value = href.value();
+                                            document.url().join(&value).ok()
+                                        });
+
+                        let event = ConstellationMsg::LinkHover(pipeline_id, url);
                         self.constellation_chan.send(event).unwrap();
 
                         state_already_changed = true;
@@ -1935,13 +2053,16 @@ impl ScriptThread {
                                                .inclusive_ancestors()
                                                .filter_map(Root::downcast::<HTMLAnchorElement>)
                                                .next() {
-                            let event = ConstellationMsg::NodeStatus(None);
+                            let event = ConstellationMsg::LinkHover(pipeline_id, None);
                             self.constellation_chan.send(event).unwrap();
                         }
                     }
                 }
             }
             TouchEvent(event_type, identifier, point) => {
+                if let TouchEventType::Up = event_type {
+                    self.notify_user_activation(pipeline_id);
+                }
                 let handled = self.handle_touch_event(pipeline_id, event_type, identifier, point);
                 match event_type {
                     TouchEventType::Down => {
@@ -1973,11 +2094,22 @@ impl ScriptThread {
                     Some(browsing_context) => browsing_context.active_document(),
                     None => return warn!("Message sent to closed pipeline {}.", pipeline_id),
                 };
+                if state == KeyState::Pressed && key != Key::Escape {
+                    self.notify_user_activation(pipeline_id);
+                }
                 document.dispatch_key_event(ch, key, state, modifiers, &self.constellation_chan);
             }
         }
     }
 
+    // https://html.spec.whatwg.org/multipage/#activation-triggering-input-event
+    fn notify_user_activation(&self, pipeline_id: PipelineId) {
+        if let Some(browsing_context) = self.root_browsing_context().find(pipeline_id) {
+            browsing_context.active_document().notify_user_activation();
+        }
+        self.constellation_chan.send(ConstellationMsg::NotifyUserActivation(pipeline_id)).unwrap();
+    }
+
     fn handle_mouse_event(&self,
                           pipeline_id: PipelineId,
                           mouse_event_type: MouseEventType,
@@ -2006,7 +2138,11 @@ impl ScriptThread {
     /// https://html.spec.whatwg.org/multipage/#navigating-across-documents
     /// The entry point for content to notify that a new load has been requested
     /// for the given pipeline (specifically the "navigate" algorithm).
-    fn handle_navigate(&self, pipeline_id: PipelineId, subpage_id: Option<SubpageId>, load_data: LoadData) {
+    fn handle_navigate(&self,
+                       pipeline_id: PipelineId,
+                       subpage_id: Option<SubpageId>,
+                       load_data: LoadData,
+                       reason: NavigationReason) {
         // Step 8.
         {
             let nurl = &load_data.url;
@@ -2018,12 +2154,21 @@ impl ScriptThread {
                 let url = document.url();
                 if &url[..Position::AfterQuery] == &nurl[..Position::AfterQuery] &&
                     load_data.method == Method::Get {
-                    match document.find_fragment_node(fragment) {
-                        Some(ref node) => {
-                            self.scroll_fragment_point(pipeline_id, node.r());
-                        }
-                        None => {}
-                    }
+                    // A navigation to the URL the document is already at replaces its entry
+                    // rather than pushing a duplicate.
+                    // https://html.spec.whatwg.org/multipage/#navigate
+                    let navigation_type = if reason == NavigationReason::Reload {
+                        NavigationType::Reload
+                    } else if *url == *nurl {
+                        NavigationType::Replace
+                    } else {
+                        NavigationType::Push
+                    };
+                    let msg = ConstellationMsg::SameDocumentNavigation(pipeline_id,
+                                                                      nurl.clone(),
+                                                                      navigation_type);
+                    self.constellation_chan.send(msg).unwrap();
+                    self.scroll_to_fragment(pipeline_id, document.r(), fragment);
                     return;
                 }
             }
@@ -2036,13 +2181,20 @@ impl ScriptThread {
                     let doc = context.active_document();
                     doc.find_iframe(subpage_id)
                 });
+                // TODO: Record the reason for iframe loads, which create their pipeline with
+                // `ScriptLoadedURLInIFrame` instead.
                 if let Some(iframe) = iframe.r() {
                     iframe.navigate_or_reload_child_browsing_context(Some(load_data));
                 }
             }
             None => {
+                // The document is about to be replaced, so its scroll position belongs to the
+                // outgoing session history entry.
+                if let Some(context) = self.root_browsing_context().find(pipeline_id) {
+                    context.active_window().save_scroll_position();
+                }
                 self.constellation_chan
-                    .send(ConstellationMsg::LoadUrl(pipeline_id, load_data))
+                    .send(ConstellationMsg::LoadUrl(pipeline_id, load_data, reason))
                     .unwrap();
             }
         }
@@ -2060,11 +2212,8 @@ impl ScriptThread {
                             ReflowReason::WindowResize);
 
         let document = context.active_document();
-        let fragment_node = window.steal_fragment_name()
-                                  .and_then(|name| document.find_fragment_node(&*name));
-        match fragment_node {
-            Some(ref node) => self.scroll_fragment_point(pipeline_id, node.r()),
-            None => {}
+        if let Some(name) = window.steal_fragment_name() {
+            self.scroll_to_fragment(pipeline_id, document.r(), &name);
         }
 
         // http://dev.w3.org/csswg/cssom-view/#resizing-viewports
@@ -2078,18 +2227,31 @@ impl ScriptThread {
     }
 
     /// Initiate a non-blocking fetch for a specified resource. Stores the InProgressLoad
-    /// argument until a notification is received that the fetch is complete.
-    fn start_page_load(&self, incomplete: InProgressLoad, mut load_data: LoadData) {
+    /// argument until a notification is received that the fetch is complete. If the constellation
+    /// already started the fetch as a navigation preload, its response is read from
+    /// `preload_response` instead.
+    fn start_page_load(&self,
+                       incomplete: InProgressLoad,
+                       mut load_data: LoadData,
+                       preload_response: Option<IpcReceiver<ResponseAction>>) {
         let id = incomplete.pipeline_id.clone();
         let subpage = incomplete.parent_info.clone().map(|p| p.1);
 
         let context = Arc::new(Mutex::new(ParserContext::new(id, subpage, load_data.url.clone())));
-        let (action_sender, action_receiver) = ipc::channel().unwrap();
         let listener = NetworkListener {
             context: context,
             script_chan: self.chan.clone(),
             wrapper: None,
         };
+        if let Some(preload_response) = preload_response {
+            ROUTER.add_route(preload_response.to_opaque(), box move |message| {
+                listener.notify_action(message.to().unwrap());
+            });
+            self.incomplete_loads.borrow_mut().push(incomplete);
+            return;
+        }
+
+        let (action_sender, action_receiver) = ipc::channel().unwrap();
         ROUTER.add_route(action_receiver.to_opaque(), box move |message| {
             listener.notify_action(message.to().unwrap());
         });
@@ -2176,6 +2338,94 @@ impl ScriptThread {
         }
     }
 
+    /// Continues spatial navigation from an iframe whose document had no target.
+    fn handle_spatial_navigate(&self, pipeline_id: PipelineId, subpage_id: SubpageId, direction: SpatialDirection) {
+        let context = match self.root_browsing_context().find(pipeline_id) {
+            Some(context) => context,
+            None => return warn!("Message sent to closed pipeline {}.", pipeline_id),
+        };
+        let document = context.active_document();
+        match document.find_iframe(subpage_id) {
+            Some(iframe) => {
+                let rect = iframe.upcast::<Node>().bounding_content_box();
+                document.navigate_spatially(direction, Some(rect));
+            }
+            None => warn!("Spatial navigation from missing iframe {:?}.", subpage_id),
+        }
+    }
+
+    fn handle_activate_access_key(&self, pipeline_id: PipelineId, element_id: ElementId) {
+        let context = match self.root_browsing_context().find(pipeline_id) {
+            Some(context) => context,
+            None => return warn!("Message sent to closed pipeline {}.", pipeline_id),
+        };
+        let document = context.active_document();
+        let ElementId(ref node_id) = element_id;
+        let element = document.upcast::<Node>()
+                              .traverse_preorder()
+                              .find(|node| node.unique_id() == *node_id)
+                              .and_then(Root::downcast::<HTMLElement>);
+        // https://html.spec.whatwg.org/multipage/#the-accesskey-attribute
+        if let Some(element) = element {
+            element.Focus();
+            element.Click();
+        }
+    }
+
+    fn handle_restore_focus(&self, pipeline_id: PipelineId, element_id: ElementId) {
+        let context = match self.root_browsing_context().find(pipeline_id) {
+            Some(context) => context,
+            None => return warn!("Message sent to closed pipeline {}.", pipeline_id),
+        };
+        let document = context.active_document();
+        if document.get_focused_element().is_some() {
+            return;
+        }
+        let ElementId(ref node_id) = element_id;
+        let element = document.upcast::<Node>()
+                              .traverse_preorder()
+                              .find(|node| node.unique_id() == *node_id)
+                              .and_then(Root::downcast::<HTMLElement>);
+        if let Some(element) = element {
+            element.Focus();
+        }
+    }
+
+    fn handle_resolve_link_for_context_menu(&self,
+                                            pipeline_id: PipelineId,
+                                            element_id: ElementId,
+                                            sender: IpcSender<Option<Url>>) {
+        let url = self.root_browsing_context().find(pipeline_id).and_then(|context| {
+            let document = context.active_document();
+            let ElementId(ref node_id) = element_id;
+            let node = match document.upcast::<Node>().traverse_preorder().find(|node| node.unique_id() == *node_id) {
+                Some(node) => node,
+                None => return None,
+            };
+            let href = node.inclusive_ancestors()
+                           .filter_map(Root::downcast::<Element>)
+                           .filter(|element| element.is::<HTMLAnchorElement>() || element.is::<HTMLAreaElement>())
+                           .filter_map(|element| element.get_attribute(&ns!(), &atom!("href")))
+                           .next();
+            href.and_then(|href| document.base_url().join(&href.value()).ok())
+        });
+        if let Err(e) = sender.send(url) {
+            warn!("Sending link resolution failed ({}).", e);
+        }
+    }
+
+    fn handle_clipboard_changed(&self, pipeline_id: PipelineId) {
+        if let Some(context) = self.find_child_context(pipeline_id) {
+            let document = context.active_document();
+            let window = context.active_window();
+            let event = Event::new(GlobalRef::Window(window.r()),
+                                   Atom::from("clipboardchange"),
+                                   EventBubbles::DoesNotBubble,
+                                   EventCancelable::NotCancelable);
+            event.fire(document.upcast());
+        }
+    }
+
     fn handle_reload(&self, pipeline_id: PipelineId) {
         if let Some(context) = self.find_child_context(pipeline_id) {
             let win = context.active_window();
@@ -2224,6 +2474,42 @@ fn shut_down_layout(context_tree: &BrowsingContext) {
     }
 }
 
+/// Builds the tooltip for an element from the nearest `title` attribute, describing it with the
+/// text of the elements its `aria-describedby` refers to, if any.
+/// The nearest inclusive ancestor of `target` with a `title`, which its tooltip comes from.
+fn title_element(target: &Element) -> Option<Root<Element>> {
+    target.upcast::<Node>()
+          .inclusive_ancestors()
+          .filter_map(Root::downcast::<Element>)
+          .find(|element| element.has_attribute(&atom!("title")))
+}
+
+fn tooltip_for_element(document: &Document, element: &Element) -> Option<Tooltip> {
+    let text = String::from(element.get_string_attribute(&atom!("title")));
+    if text.is_empty() {
+        return None;
+    }
+
+    let described_by = Atom::from("aria-describedby");
+    let description = if element.has_attribute(&described_by) {
+        let ids = element.get_string_attribute(&described_by);
+        let description = split_html_space_chars(&ids)
+            .filter_map(|id| document.get_element_by_id(&Atom::from(id)))
+            .filter_map(|element| element.upcast::<Node>().GetTextContent())
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(description)
+    } else {
+        None
+    };
+
+    Some(Tooltip {
+        text: text,
+        description: description,
+    })
+}
+
 // TODO: remove this function, as it's a source of panic.
 pub fn get_browsing_context(context: &BrowsingContext,
                             pipeline_id: PipelineId)
@@ -2233,6 +2519,20 @@ pub fn get_browsing_context(context: &BrowsingContext,
             This is a bug.")
 }
 
+/// Decides whether the response to a navigation replaces the current document.
+/// https://html.spec.whatwg.org/multipage/#process-a-navigate-response
+fn navigation_disposition(metadata: &Metadata) -> NavigationDisposition {
+    match metadata.status {
+        Some(RawStatus(204, _)) | Some(RawStatus(205, _)) => return NavigationDisposition::Ignore,
+        _ => {}
+    }
+    let content_disposition = metadata.headers.as_ref().and_then(|headers| headers.get::<ContentDisposition>());
+    match content_disposition {
+        Some(&ContentDisposition { disposition: DispositionType::Attachment, .. }) => NavigationDisposition::Download,
+        _ => NavigationDisposition::Navigate,
+    }
+}
+
 fn dom_last_modified(tm: &Tm) -> String {
     tm.to_local().strftime("%m/%d/%Y %H:%M:%S").unwrap().to_string()
 }
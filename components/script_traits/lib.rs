@@ -12,8 +12,11 @@
 #![deny(unsafe_code)]
 
 extern crate app_units;
+#[macro_use]
+extern crate bitflags;
 extern crate canvas_traits;
 extern crate cookie as cookie_rs;
+extern crate cssparser;
 extern crate devtools_traits;
 extern crate euclid;
 extern crate gfx_traits;
@@ -53,7 +56,7 @@ use libc::c_void;
 use msg::constellation_msg::{FrameId, FrameType, Image, Key, KeyModifiers, KeyState, LoadData};
 use msg::constellation_msg::{NavigationDirection, PanicMsg, PipelineId};
 use msg::constellation_msg::{PipelineNamespaceId, SubpageId, WindowSizeType};
-use net_traits::ResourceThreads;
+use net_traits::{ResourceThreads, ResponseAction};
 use net_traits::bluetooth_thread::BluetoothMethodMsg;
 use net_traits::image_cache_thread::ImageCacheThread;
 use net_traits::response::HttpsState;
@@ -62,11 +65,26 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::sync::mpsc::{Sender, Receiver};
 use style_traits::{PagePx, ViewportPx};
-use url::Url;
+use url::{Origin as UrlOrigin, Url};
 use util::ipc::OptionalOpaqueIpcSender;
 use webdriver_msg::{LoadStatus, WebDriverScriptCommand};
 
-pub use script_msg::{LayoutMsg, ScriptMsg, EventResult, LogEntry};
+pub use script_msg::{AnimatedProperty, AnimatedValue, AnimationId, AnimationTiming, AutoplayContext};
+pub use script_msg::{AuxiliaryContextInfo, BlockReason, CLEAR_CACHE, CLEAR_COOKIES};
+pub use script_msg::{CLEAR_SERVICE_WORKERS, CLEAR_STORAGE, CapturedLayer, ClearDataTypes};
+pub use script_msg::{ColorGamut, CompositorAnimation, CompositorKeyframe, CoverageReport, CursorId, DeprecationInfo};
+pub use script_msg::{DisplayCapabilities, DisplayCaptureConstraints, DisplaySurface, ElementId, EventResult};
+pub use script_msg::{FrameInfo, FrameStats, FrameStatsSummary, FullscreenOptions, HistoryState, IceCandidate};
+pub use script_msg::{IceConfig, IceGatheringId, IceServer, IdbConnectionId, ImeState, InputType, InterventionKind};
+pub use script_msg::{LayoutMsg, LogEntry, MediaConstraints, MediaDeviceInfo, MediaDeviceKind, MediaElementId};
+pub use script_msg::{MediaError, MediaStreamInfo, MediaTrackConstraints, MediaTrackInfo, MediaTrackKind, MediaType};
+pub use script_msg::{NavigationCapability, NavigationDisposition, NavigationReason, NavigationType};
+pub use script_msg::{NavigationUiPreference, PageLifecycleState, PingId, PipelineExitReason, PointerAccuracy};
+pub use script_msg::{PointerCapabilities, ReportBody, ScreenInfo, ScriptMsg, ScrollAxis, ScrollRestorationMode};
+pub use script_msg::{ScrollTimeline, ScrollbarPreferences, SelectionInfo, SourceLocation, SpatialDirection};
+pub use script_msg::{SpeechRecognitionConfig, SpeechRecognitionId, SpeechRecognitionResult, StorageBreakdown};
+pub use script_msg::{TextFragmentDirective, Tooltip, ViewTransitionSnapshot, VirtualKeyboardGeometry, WebLockGrant};
+pub use script_msg::{WebLockMode, WebLockToken, WindowFeatures, WindowPlacement};
 
 /// The address of a node. Layout sends these back. They must be validated via
 /// `from_untrusted_node_address` before they can be used, because we do not trust layout.
@@ -103,6 +121,46 @@ impl UntrustedNodeAddress {
     }
 }
 
+/// The origin of a document, in a form that can be sent to the constellation.
+///
+/// Origins travel as their ASCII serialization. An opaque origin is only meaningful within the
+/// thread that created it, so it arrives as a fresh opaque origin that is not same-origin with
+/// anything.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Origin(pub UrlOrigin);
+
+impl Origin {
+    /// Creates the origin of the given URL.
+    pub fn new(url: &Url) -> Origin {
+        Origin(url.origin())
+    }
+
+    /// Returns a URL with this origin and an empty path, for the storage backends that key
+    /// their data by URL. Returns `None` for opaque origins, which have no storage.
+    pub fn url(&self) -> Option<Url> {
+        match self.0 {
+            UrlOrigin::Tuple(..) => Url::parse(&self.0.ascii_serialization()).ok(),
+            UrlOrigin::Opaque(..) => None,
+        }
+    }
+}
+
+impl Serialize for Origin {
+    fn serialize<S: Serializer>(&self, s: &mut S) -> Result<(), S::Error> {
+        self.0.ascii_serialization().serialize(s)
+    }
+}
+
+impl Deserialize for Origin {
+    fn deserialize<D: Deserializer>(d: &mut D) -> Result<Origin, D::Error> {
+        let serialization: String = try!(Deserialize::deserialize(d));
+        Ok(match Url::parse(&serialization) {
+            Ok(url) => Origin::new(&url),
+            Err(_) => Origin(UrlOrigin::new_opaque()),
+        })
+    }
+}
+
 /// Messages sent to the layout thread from the constellation and/or compositor.
 #[derive(Deserialize, Serialize)]
 pub enum LayoutControlMsg {
@@ -174,8 +232,8 @@ pub enum ConstellationControlMsg {
     ChangeFrameVisibilityStatus(PipelineId, bool),
     /// Notifies script thread that frame visibility change is complete
     NotifyVisibilityChange(PipelineId, PipelineId, bool),
-    /// Notifies script thread that a url should be loaded in this iframe.
-    Navigate(PipelineId, SubpageId, LoadData),
+    /// Notifies script thread that a url should be loaded in this iframe, and why.
+    Navigate(PipelineId, SubpageId, LoadData, NavigationReason),
     /// Requests the script thread forward a mozbrowser event to an iframe it owns
     MozBrowserEvent(PipelineId, SubpageId, MozBrowserEvent),
     /// Updates the current subpage and pipeline IDs of a given iframe
@@ -202,6 +260,21 @@ pub enum ConstellationControlMsg {
     ReportCSSError(PipelineId, String, usize, usize, String),
     /// Reload the given page.
     Reload(PipelineId),
+    /// Runs the access key action of the given element: focuses and clicks it.
+    ActivateAccessKey(PipelineId, ElementId),
+    /// Asks script to answer with `ScriptMsg::Pong`, to check that it is responsive.
+    Ping(PipelineId, PingId),
+    /// Asks for the resolved URL of the link the given element is in, if any.
+    ResolveLinkForContextMenu(PipelineId, ElementId, IpcSender<Option<Url>>),
+    /// Continues spatial navigation in the given direction in the containing pipeline's document,
+    /// from the iframe with the given subpage id, whose document had no target.
+    SpatialNavigate(PipelineId, SubpageId, SpatialDirection),
+    /// Focuses the given element, the one reported by `ScriptMsg::SetInitialFocus`, unless
+    /// another element of the document has focus already.
+    RestoreFocus(PipelineId, ElementId),
+    /// Tells a pipeline subscribed with `ScriptMsg::SubscribeClipboardChanges` that the
+    /// clipboard contents changed, so that it fires `clipboardchange` at its document.
+    ClipboardChanged(PipelineId),
 }
 
 /// Used to determine if a script has any pending asynchronous activity.
@@ -285,6 +358,17 @@ pub enum CompositorEvent {
     KeyEvent(Option<char>, Key, KeyState, KeyModifiers),
 }
 
+/// The data carried by a drag.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DragData {
+    /// The dragged items, as pairs of MIME type and data.
+    pub items: Vec<(String, String)>,
+    /// The image set with `dataTransfer.setDragImage()`, as its RGBA pixels, its size, and the
+    /// point within it that sits under the pointer. When `None`, the default is used: a
+    /// rendering of the dragged element.
+    pub drag_image: Option<(Vec<u8>, Size2D<u32>, Point2D<u32>)>,
+}
+
 /// Touchpad pressure phase for TouchpadPressureEvent.
 #[derive(Copy, Clone, HeapSizeOf, PartialEq, Deserialize, Serialize)]
 pub enum TouchpadPressurePhase {
@@ -343,6 +427,10 @@ pub fn precise_time_ns() -> NsDuration {
     Length::new(time::precise_time_ns())
 }
 
+/// How long transient user activation lasts after the user interacts with a page, in
+/// milliseconds.
+pub const USER_ACTIVATION_DURATION_MS: u64 = 5000;
+
 /// Data needed to construct a script thread.
 ///
 /// NB: *DO NOT* add any Senders or Receivers here! pcwalton will have to rewrite your code if you
@@ -381,6 +469,9 @@ pub struct InitialScriptState {
     pub pipeline_namespace_id: PipelineNamespaceId,
     /// A ping will be sent on this channel once the script thread shuts down.
     pub content_process_shutdown_chan: IpcSender<()>,
+    /// The response to the initial load, if the constellation already started fetching it as a
+    /// navigation preload. The script thread then reads the page from here instead of fetching it.
+    pub preload_response: Option<IpcReceiver<ResponseAction>>,
 }
 
 /// This trait allows creating a `ScriptThread` without depending on the `script`
@@ -602,4 +693,15 @@ pub enum ConstellationMsg {
     Reload,
     /// A log entry, with the pipeline id and thread name
     LogEntry(Option<PipelineId>, Option<String>, LogEntry),
+    /// The user pressed the accelerator for an `accesskey` shortcut of the given pipeline.
+    ActivateAccessKey(PipelineId, ElementId),
+    /// The window became the active window, so focus goes back to the focused document.
+    WindowActivated,
+    /// Forcibly terminates an unresponsive pipeline, at the user's request. Unlike closing a
+    /// pipeline normally, this does not wait for its script thread, which may be stuck, to shut
+    /// down, and unlike `Exit` it leaves the rest of the application running. The constellation
+    /// handles the exit as `PipelineExited(_, PipelineExitReason::Killed)`, and the frame that
+    /// showed the pipeline then shows a crashed-frame placeholder, `about:failure`, instead. Only
+    /// the embedder can send this; content cannot kill other pipelines.
+    KillPipeline(PipelineId),
 }
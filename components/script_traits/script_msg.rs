@@ -4,12 +4,18 @@
 
 use AnimationState;
 use DocumentState;
+use DragData;
 use IFrameLoadInfo;
 use MouseButton;
 use MouseEventType;
 use MozBrowserEvent;
+use MsDuration;
+use Origin;
 use canvas_traits::CanvasMsg;
+use cssparser::RGBA;
+use euclid::Matrix4D;
 use euclid::point::Point2D;
+use euclid::rect::Rect;
 use euclid::size::Size2D;
 use gfx_traits::LayerId;
 use ipc_channel::ipc::IpcSender;
@@ -25,12 +31,787 @@ use url::Url;
 pub enum LayoutMsg {
     /// Indicates whether this pipeline is currently running animations.
     ChangeRunningAnimationsState(PipelineId, AnimationState),
+    /// Hands the given animations over to the compositor, which runs them without involving
+    /// layout or script. This replaces any animations previously offloaded for the pipeline;
+    /// an empty list stops them all. Animations of properties that cannot be offloaded (anything
+    /// other than `transform` and `opacity`) are never sent here: they keep running in layout,
+    /// which reports them through `ChangeRunningAnimationsState` as before.
+    SetCompositorAnimations(PipelineId, Vec<CompositorAnimation>),
+    /// Binds an animation previously offloaded with `SetCompositorAnimations` to the scroll
+    /// position of the given layer, for `animation-timeline: scroll()`. The compositor then derives
+    /// the animation's progress from the layer's scroll offset instead of from its timing. The
+    /// binding is dropped once the target animation is no longer in the pipeline's set of
+    /// compositor animations.
+    SetScrollTimeline(PipelineId, LayerId, ScrollTimeline),
+    /// Shifts the scroll position of the given layer by the given amount (in CSS pixels) to keep
+    /// the scroll anchor in place after content above it changed size, as required by CSS
+    /// scroll anchoring. Unlike scrolls by the user or by script, these adjustments are silent:
+    /// they do not fire `scroll` events, and they are not reported back as user scrolls.
+    ScrollAnchorAdjustment(PipelineId, LayerId, Point2D<f32>),
     /// Requests that the constellation inform the compositor of the a cursor change.
     SetCursor(Cursor),
+    /// Shows a labelled cursor, such as a remote collaborator's pointer, with the given shape at
+    /// the given position in the pipeline's viewport (in CSS pixels). Any number of named cursors
+    /// may be shown at once; sending `Cursor::None` for a `CursorId` hides it again. The user's
+    /// own pointer is unaffected and still follows `SetCursor`.
+    SetNamedCursor(PipelineId, CursorId, Cursor, Point2D<f32>),
+    /// Asks the compositor to capture the pipeline's layers as they are now, for the View
+    /// Transitions API. A transition takes two captures: one of the old state, before the
+    /// update callback runs, and one of the new state once the updated DOM has been laid out.
+    /// Script then animates between the two with `SetCompositorAnimations`, targeting the layers
+    /// listed in each snapshot. Each captured layer carries the pixels it showed at the time of the
+    /// capture, so the old state can still be shown once the DOM has been updated.
+    CaptureViewTransition(PipelineId, IpcSender<ViewTransitionSnapshot>),
     /// Notifies the constellation that the viewport has been constrained in some manner
     ViewportConstrained(PipelineId, ViewportConstraints),
 }
 
+/// The layers captured by `LayoutMsg::CaptureViewTransition`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ViewTransitionSnapshot {
+    /// The captured layers, in painting order.
+    pub layers: Vec<CapturedLayer>,
+}
+
+/// A layer captured for a view transition.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CapturedLayer {
+    /// The captured layer, which compositor animations can target.
+    pub layer_id: LayerId,
+    /// The bounds of the layer, relative to its parent layer.
+    pub bounds: Rect<f32>,
+    /// What the visible part of the layer showed at the time of the capture, as RGB8 pixels in
+    /// rows from the top. Empty if none of the layer was on screen, or if the compositor cannot
+    /// read back what it painted.
+    pub pixels: Vec<u8>,
+    /// The size of `pixels`, in device pixels.
+    pub pixels_size: Size2D<u32>,
+}
+
+/// An identifier for a speech recognition session, unique for the lifetime of the constellation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SpeechRecognitionId(pub u64);
+
+/// The settings of a speech recognition session.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpeechRecognitionConfig {
+    /// The BCP 47 language tag of the speech, or the document's language when empty.
+    pub lang: String,
+    /// Whether to keep recognizing after the user stops speaking, rather than returning a single
+    /// final result.
+    pub continuous: bool,
+    /// Whether to also report interim results, which may change before they are final.
+    pub interim_results: bool,
+}
+
+/// A phrase recognized in a speech recognition session.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpeechRecognitionResult {
+    /// The recognized text.
+    pub transcript: String,
+    /// How confident the recognizer is in the transcript, between 0 and 1.
+    pub confidence: f32,
+    /// Whether this result is final, or an interim result that may still change.
+    pub is_final: bool,
+}
+
+/// The kinds of track requested by `ScriptMsg::RequestUserMedia`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MediaConstraints {
+    /// The requirements of the audio track, or `None` if no audio is requested.
+    pub audio: Option<MediaTrackConstraints>,
+    /// The requirements of the video track, or `None` if no video is requested.
+    pub video: Option<MediaTrackConstraints>,
+}
+
+/// The requirements of a requested track.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MediaTrackConstraints {
+    /// The device the track must come from, if the page asked for a specific one.
+    pub device_id: Option<String>,
+}
+
+/// A media input or output device, as reported by `ScriptMsg::EnumerateMediaDevices`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MediaDeviceInfo {
+    /// The identifier of the device, which can be passed back in `MediaTrackConstraints`.
+    pub device_id: String,
+    /// The kind of device.
+    pub kind: MediaDeviceKind,
+    /// The name of the device, or an empty string if the document may not see it.
+    pub label: String,
+    /// An identifier shared by devices that belong to the same physical device, such as the
+    /// microphone and camera of a webcam.
+    pub group_id: String,
+}
+
+/// The kinds of media device.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MediaDeviceKind {
+    /// A microphone or other audio input.
+    AudioInput,
+    /// Speakers, headphones or other audio output.
+    AudioOutput,
+    /// A camera or other video input.
+    VideoInput,
+}
+
+/// The options of `ScriptMsg::RequestDisplayCapture`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DisplayCaptureConstraints {
+    /// Whether to capture the audio of the shared surface as well, where possible.
+    pub audio: bool,
+    /// The kind of surface the page would prefer the user to share, if any.
+    pub surface: Option<DisplaySurface>,
+}
+
+/// The kinds of surface that can be captured.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DisplaySurface {
+    /// A whole screen.
+    Monitor,
+    /// A single application window.
+    Window,
+    /// A browser tab.
+    Browser,
+}
+
+/// A media stream opened for a page.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MediaStreamInfo {
+    /// The tracks of the stream.
+    pub tracks: Vec<MediaTrackInfo>,
+}
+
+/// A track of an opened media stream.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MediaTrackInfo {
+    /// The identifier of the track.
+    pub id: String,
+    /// Whether the track carries audio or video.
+    pub kind: MediaTrackKind,
+    /// The label of the track, usually the name of its source device.
+    pub label: String,
+}
+
+/// The kinds of media track.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MediaTrackKind {
+    /// An audio track.
+    Audio,
+    /// A video track.
+    Video,
+}
+
+/// Why a media stream could not be opened.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MediaError {
+    /// The user or the embedder refused access (`NotAllowedError`).
+    NotAllowed,
+    /// There is no device of a requested kind (`NotFoundError`).
+    NotFound,
+    /// No device satisfies the constraints (`OverconstrainedError`).
+    OverConstrained,
+    /// The request was abandoned, for example because the device failed to start
+    /// (`AbortError`).
+    Aborted,
+}
+
+/// An identifier for an ICE candidate gathering session, unique for the lifetime of the
+/// constellation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct IceGatheringId(pub u64);
+
+/// The configuration of an ICE candidate gathering session.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IceConfig {
+    /// The STUN and TURN servers to gather candidates from.
+    pub servers: Vec<IceServer>,
+}
+
+/// A STUN or TURN server, from `RTCConfiguration.iceServers`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IceServer {
+    /// The `stun:`, `turn:` or `turns:` URLs of the server.
+    pub urls: Vec<String>,
+    /// The user name to authenticate to a TURN server with.
+    pub username: Option<String>,
+    /// The credential to authenticate to a TURN server with.
+    pub credential: Option<String>,
+}
+
+/// A gathered ICE candidate, as exposed by `RTCIceCandidate`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct IceCandidate {
+    /// The candidate in SDP `candidate` attribute syntax.
+    pub candidate: String,
+    /// The media stream identification tag of the media section the candidate belongs to.
+    pub sdp_mid: Option<String>,
+    /// The index of the media section the candidate belongs to.
+    pub sdp_mline_index: Option<u16>,
+}
+
+/// How accurately a pointing device can point, as matched by the `pointer` media feature.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum PointerAccuracy {
+    /// There is no pointing device.
+    None,
+    /// A pointing device of limited accuracy, such as a finger on a touch screen.
+    Coarse,
+    /// An accurate pointing device, such as a mouse.
+    Fine,
+}
+
+/// The pointing devices available to the user.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PointerCapabilities {
+    /// The accuracy of the primary pointing device.
+    pub pointer: PointerAccuracy,
+    /// Whether the primary pointing device can hover over elements.
+    pub hover: bool,
+    /// The accuracy of each available pointing device, for `any-pointer`.
+    pub any_pointer: Vec<PointerAccuracy>,
+    /// Whether any available pointing device can hover over elements.
+    pub any_hover: bool,
+}
+
+/// The range of colors a display can show, as matched by the `color-gamut` media feature.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ColorGamut {
+    /// The sRGB gamut.
+    Srgb,
+    /// The Display P3 gamut.
+    P3,
+    /// The ITU-R BT.2020 gamut.
+    Rec2020,
+}
+
+/// What a display can show.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DisplayCapabilities {
+    /// The widest color gamut the display covers.
+    pub color_gamut: ColorGamut,
+    /// Whether the display supports high dynamic range output.
+    pub high_dynamic_range: bool,
+    /// The number of bits per color channel.
+    pub bits_per_channel: u8,
+}
+
+/// A screen attached to the host, as exposed by `ScreenDetailed`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScreenInfo {
+    /// The area of the screen, in the coordinates of the host's virtual desktop.
+    pub bounds: Rect<i32>,
+    /// The part of the screen that windows can use, excluding taskbars, docks and the like.
+    pub available_bounds: Rect<i32>,
+    /// The ratio of device pixels to CSS pixels on the screen.
+    pub device_pixel_ratio: f32,
+    /// Whether this is the host's primary screen.
+    pub is_primary: bool,
+    /// A description of the screen for the user, such as the monitor's name.
+    pub label: String,
+}
+
+/// Where to place a window.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WindowPlacement {
+    /// The outer bounds of the window, in the coordinates of the host's virtual desktop.
+    pub bounds: Rect<i32>,
+    /// Whether the window should be made fullscreen on the screen it is placed on.
+    pub fullscreen: bool,
+    /// The label of the screen to place the window on, as reported by `GetScreens`.
+    pub screen_label: Option<String>,
+}
+
+/// A text directive from a URL fragment, `#:~:text=[prefix-,]start[,end][,-suffix]`, with its
+/// terms percent-decoded. The matched text runs from `start` to `end`, or is `start` alone when
+/// there is no `end`; `prefix` and `suffix` only disambiguate the match and are not part of it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TextFragmentDirective {
+    /// The text that must come immediately before the match.
+    pub prefix: Option<String>,
+    /// The start of the match.
+    pub start: String,
+    /// The end of the match, for matches that span a range of text.
+    pub end: Option<String>,
+    /// The text that must come immediately after the match.
+    pub suffix: Option<String>,
+}
+
+/// An identifier for a responsiveness ping, unique for the lifetime of the constellation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct PingId(pub u64);
+
+/// A direction for spatial navigation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SpatialDirection {
+    /// Towards the top of the viewport.
+    Up,
+    /// Towards the bottom of the viewport.
+    Down,
+    /// Towards the left of the viewport.
+    Left,
+    /// Towards the right of the viewport.
+    Right,
+}
+
+/// Why the embedder's protection blocked a navigation.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BlockReason {
+    /// The URL is known to serve malware.
+    Malware,
+    /// The URL is known to be a phishing site.
+    Phishing,
+    /// The URL is known to serve unwanted software.
+    UnwantedSoftware,
+    /// The embedder's own policy blocked the URL, for the given reason.
+    EmbedderPolicy(String),
+}
+
+/// The size of the joint session history and the position in it, for `history.length` and
+/// the embedder's back and forward buttons.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HistoryState {
+    /// The number of entries.
+    pub length: u32,
+    /// The index of the current entry, counting from 0.
+    pub current_index: u32,
+}
+
+/// Whether the session history allows going back or forward, for the embedder's toolbar.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NavigationCapability {
+    /// Whether there is an entry before the current one.
+    pub can_go_back: bool,
+    /// Whether there is an entry after the current one.
+    pub can_go_forward: bool,
+}
+
+/// Why a navigation happened, for metrics and back-forward cache eligibility. A `Reload` replaces
+/// the current session history entry with a fresh copy of the document, so the document it
+/// replaces is closed rather than kept in the back-forward cache, and is never restored from it.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NavigationReason {
+    /// The user followed a link.
+    LinkClick,
+    /// A form was submitted.
+    FormSubmit,
+    /// The document was reloaded.
+    Reload,
+    /// The user traversed the session history. Traversals with `Navigate` reuse existing
+    /// entries, so only a load started from one, such as reloading an evicted document, says so.
+    History,
+    /// A script navigated, through `location` or `window.open()`, say.
+    Script,
+    /// The user entered a URL or picked a bookmark in the embedder's chrome.
+    Address,
+}
+
+/// Where a page is in its lifecycle with respect to the back-forward cache.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PageLifecycleState {
+    /// The document has finished loading and is being shown.
+    Active,
+    /// The document was navigated away from and kept, frozen, in the back-forward cache.
+    Frozen,
+    /// The document was restored from the back-forward cache and is being shown again.
+    Resumed,
+    /// The document is being discarded, and will not be shown again.
+    Terminated,
+}
+
+/// Whether the browser restores a session history entry's scroll position on traversal, as set
+/// through `history.scrollRestoration`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ScrollRestorationMode {
+    /// The saved scroll position is restored when the entry is traversed to.
+    Auto,
+    /// The page restores its own scroll position, and the browser leaves it as it is.
+    Manual,
+}
+
+/// How a same-document navigation changed session history, as the Navigation API reports it.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NavigationType {
+    /// A new entry was pushed, through a fragment navigation or `history.pushState`.
+    Push,
+    /// The current entry was replaced, through `history.replaceState` or `location.replace`.
+    Replace,
+    /// Session history was traversed to another entry for the same document.
+    Traverse,
+    /// The current entry was reloaded without loading the document again.
+    Reload,
+}
+
+/// What to do with the response to a navigation, once its headers are known.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NavigationDisposition {
+    /// The response is rendered as the new document.
+    Navigate,
+    /// The response is saved as a download, and the current document stays.
+    Download,
+    /// The response is dropped, and the current document stays, as for a `204 No Content`.
+    Ignore,
+}
+
+/// How a Web Lock is held.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WebLockMode {
+    /// The lock is held by a single holder at a time.
+    Exclusive,
+    /// The lock may be held by several holders at once, as long as none holds it exclusively.
+    Shared,
+}
+
+/// An identifier for a granted Web Lock, unique for the lifetime of the constellation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct WebLockToken(pub u64);
+
+/// A Web Lock that was granted to a request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WebLockGrant {
+    /// The token to release the lock with.
+    pub token: WebLockToken,
+}
+
+/// An identifier for a connection to an IndexedDB database, unique for the lifetime of the
+/// constellation.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct IdbConnectionId(pub u64);
+
+/// The features parsed from the third argument of `window.open`, for the embedder to size and
+/// style the new window with.
+///
+/// The default is what an empty feature string parses to: no size or position, which leaves them
+/// to the embedder, and every flag `false`. Tokens that are not window features, such as
+/// `noopener`, are not represented here.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WindowFeatures {
+    /// The requested width of the content area, in CSS pixels.
+    pub width: Option<u32>,
+    /// The requested height of the content area, in CSS pixels.
+    pub height: Option<u32>,
+    /// The requested distance of the window from the left of the screen, in CSS pixels.
+    pub left: Option<i32>,
+    /// The requested distance of the window from the top of the screen, in CSS pixels.
+    pub top: Option<i32>,
+    /// Whether the window should have a toolbar.
+    pub toolbar: bool,
+    /// Whether the window should have a menu bar.
+    pub menubar: bool,
+    /// Whether the user should be able to resize the window.
+    pub resizable: bool,
+}
+
+/// Specifies the information required to open a new top-level browsing context from
+/// `window.open`.
+#[derive(Deserialize, Serialize)]
+pub struct AuxiliaryContextInfo {
+    /// The pipeline whose document called `window.open`.
+    pub opener: PipelineId,
+    /// The load of the new context's initial document.
+    pub url: LoadData,
+    /// The name of the new context, which `target` and `window.name` refer to.
+    pub name: String,
+    /// The features requested for the new window.
+    pub features: WindowFeatures,
+    /// Whether `noopener` was requested, so that the new context is not given an opener.
+    pub noopener: bool,
+}
+
+/// A position in a script, that a report points at.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SourceLocation {
+    /// The URL of the script.
+    pub url: Url,
+    /// The line number, starting at 1.
+    pub line: u32,
+    /// The column number, starting at 1.
+    pub column: u32,
+}
+
+/// The body of a report queued for the Reporting API.
+/// https://w3c.github.io/reporting/
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ReportBody {
+    /// A deprecated feature was used.
+    Deprecation {
+        /// The identifier of the feature.
+        id: String,
+        /// A message for developers.
+        message: String,
+        /// Where the feature was used, if it was used by script.
+        source: Option<SourceLocation>,
+        /// When the feature is expected to be removed, as an ISO 8601 date.
+        anticipated_removal: Option<String>,
+    },
+    /// The engine overrode what the page asked for.
+    Intervention {
+        /// The identifier of the intervention.
+        id: String,
+        /// A message for developers.
+        message: String,
+        /// The call that was intervened on, if it came from script.
+        source: Option<SourceLocation>,
+    },
+    /// The document's Content Security Policy was violated.
+    Csp {
+        /// The URL of the resource that was blocked, if any.
+        blocked_url: Option<Url>,
+        /// The directive that was violated.
+        effective_directive: String,
+        /// The policy the directive belongs to, as it was delivered.
+        original_policy: String,
+        /// Whether the policy was only being reported on, rather than enforced.
+        report_only: bool,
+    },
+    /// The document's pipeline crashed.
+    Crash {
+        /// Why it crashed, if known.
+        reason: Option<String>,
+    },
+    /// Fetching a resource failed.
+    NetworkError {
+        /// The URL of the resource.
+        url: Url,
+        /// The HTTP status code, if a response arrived.
+        status: Option<u16>,
+        /// The kind of failure, such as `dns.name_not_resolved` or `http.error`.
+        error_type: String,
+    },
+}
+
+/// A way in which the engine overrides what a page asked for.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InterventionKind {
+    /// A media element was not allowed to start playing on its own.
+    BlockedAutoplay,
+    /// A popup window was not opened, for lack of user activation.
+    BlockedPopup,
+    /// The timers of a hidden document were made to fire less often.
+    ThrottledTimer,
+}
+
+impl InterventionKind {
+    /// The identifier of the intervention in Reporting API reports.
+    pub fn id(&self) -> &'static str {
+        match *self {
+            InterventionKind::BlockedAutoplay => "blocked-autoplay",
+            InterventionKind::BlockedPopup => "blocked-popup",
+            InterventionKind::ThrottledTimer => "throttled-timer",
+        }
+    }
+}
+
+/// The use of a deprecated feature, to be reported to developers.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeprecationInfo {
+    /// The identifier of the feature.
+    pub feature_id: String,
+    /// A message for developers, which should suggest a replacement if there is one.
+    pub message: String,
+    /// Where the feature was used, if it was used by script and the location is known.
+    pub source: Option<SourceLocation>,
+    /// When the feature is expected to be removed, as an ISO 8601 date.
+    pub anticipated_removal: Option<String>,
+}
+
+/// Why a pipeline exited.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PipelineExitReason {
+    /// The pipeline was closed, and its script thread shut it down.
+    Normal,
+    /// The pipeline was terminated with `ConstellationMsg::KillPipeline`.
+    Killed,
+}
+
+/// What devtools needs to know about a frame to show it in the frame tree.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FrameInfo {
+    /// The URL of the frame's document.
+    pub url: Url,
+    /// The origin of the frame's document.
+    pub origin: Origin,
+    /// The frame's name, from its `iframe` element's `name` attribute.
+    pub name: Option<String>,
+    /// The pipeline of the parent frame, or `None` for a top-level frame.
+    pub parent: Option<PipelineId>,
+    /// Whether the frame's origin differs from its parent's.
+    pub is_cross_origin: bool,
+}
+
+/// Which parts of a script or style sheet were used, for the devtools coverage panel.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CoverageReport {
+    /// The URL of the resource.
+    pub url: Url,
+    /// The size of the resource, in bytes.
+    pub total_bytes: u64,
+    /// The parts of the resource that were used, as `(start, end)` byte offsets into the resource,
+    /// with `end` exclusive. Ranges may overlap, and need not be sorted.
+    pub used_ranges: Vec<(u32, u32)>,
+}
+
+/// How long an animation frame took, for jank analysis. The phase timings are in milliseconds of
+/// the monotonic clock behind `time::precise_time_ns`, the one the script thread uses for reflow
+/// timeouts, not of the wall clock. Painting happens after the frame leaves script, so its time is
+/// not included.
+#[derive(Clone, Copy, Debug, Deserialize, HeapSizeOf, PartialEq, Serialize)]
+pub struct FrameStats {
+    /// The number of `requestAnimationFrame` callbacks that ran in the frame.
+    pub raf_callbacks: u32,
+    /// The time spent running those callbacks.
+    pub script_ms: f32,
+    /// The time spent laying out the frame.
+    pub layout_ms: f32,
+    /// Whether the frame took longer than the 60Hz frame budget, so that it missed a refresh.
+    pub dropped: bool,
+}
+
+/// A summary of a pipeline's recent frames, for the embedder's performance overlay. The times
+/// are averages per frame, in milliseconds.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FrameStatsSummary {
+    /// The number of frames summarized.
+    pub frames: u32,
+    /// How many of those frames were dropped.
+    pub dropped_frames: u32,
+    /// The average number of `requestAnimationFrame` callbacks per frame.
+    pub raf_callbacks: f32,
+    /// The average time spent running script.
+    pub script_ms: f32,
+    /// The average time spent in layout.
+    pub layout_ms: f32,
+}
+
+/// How the host platform draws scrollbars.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScrollbarPreferences {
+    /// Whether scrollbars are drawn over the content, rather than beside it.
+    pub overlay: bool,
+    /// The thickness of a scrollbar, in CSS pixels.
+    pub width: f32,
+    /// Whether scrollbars are hidden while the content is not being scrolled.
+    pub auto_hide: bool,
+}
+
+/// The area covered by the embedder's on-screen keyboard.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VirtualKeyboardGeometry {
+    /// The keyboard's bounds, in CSS pixels relative to the viewport.
+    pub bounding_rect: Rect<f32>,
+}
+
+/// The state of the embedder's input method editor requested by `ScriptMsg::SetImeState`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ImeState {
+    /// Whether the input method editor should be enabled.
+    pub enabled: bool,
+    /// The kind of text the focused control accepts.
+    pub input_type: InputType,
+    /// The caret of the focused control, in CSS pixels relative to the viewport, if known, so
+    /// that candidate windows can be placed next to it.
+    pub caret_rect: Option<Rect<f32>>,
+}
+
+/// The kinds of text entry a focused control can ask of the input method editor.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InputType {
+    /// Free-form text, as in `<textarea>` and `<input type=text>`.
+    Text,
+    /// An email address.
+    Email,
+    /// A number.
+    Number,
+    /// A password, which the input method should neither display nor learn from.
+    Password,
+    /// Nothing editable has focus.
+    None,
+}
+
+/// An identifier for a cursor shown with `LayoutMsg::SetNamedCursor`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CursorId(pub u32);
+
+/// An identifier for an animation that has been offloaded to the compositor.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct AnimationId(pub u64);
+
+/// The properties that the compositor is able to animate on its own.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AnimatedProperty {
+    /// The layer's `transform`.
+    Transform,
+    /// The layer's `opacity`.
+    Opacity,
+}
+
+/// The value of an animated property at a given keyframe.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AnimatedValue {
+    /// A transform, already resolved to a matrix.
+    Transform(Matrix4D<f32>),
+    /// An opacity between 0 and 1.
+    Opacity(f32),
+}
+
+/// A single keyframe of a compositor animation.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CompositorKeyframe {
+    /// The position of this keyframe within an iteration, between 0 and 1.
+    pub offset: f32,
+    /// The value of the animated property at this keyframe.
+    pub value: AnimatedValue,
+}
+
+/// When and for how long a compositor animation runs.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AnimationTiming {
+    /// The time at which the animation started, in seconds, as given by `time::precise_time_s`.
+    pub start_time: f64,
+    /// The delay before the first iteration begins, in seconds.
+    pub delay: f64,
+    /// The duration of a single iteration, in seconds.
+    pub duration: f64,
+    /// The number of iterations to run, or `None` to repeat forever.
+    pub iteration_count: Option<f32>,
+}
+
+/// An animation that the compositor runs without the help of layout or script.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CompositorAnimation {
+    /// The identifier of this animation.
+    pub id: AnimationId,
+    /// The layer whose property is animated.
+    pub layer_id: LayerId,
+    /// The animated property. Every keyframe value must be of the matching kind.
+    pub property: AnimatedProperty,
+    /// The keyframes of the animation, sorted by offset.
+    pub keyframes: Vec<CompositorKeyframe>,
+    /// The timing of the animation.
+    pub timing: AnimationTiming,
+}
+
+/// The direction of scrolling that drives a scroll timeline.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ScrollAxis {
+    /// The horizontal scroll offset.
+    Horizontal,
+    /// The vertical scroll offset.
+    Vertical,
+}
+
+/// A timeline that advances an animation as a layer scrolls.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScrollTimeline {
+    /// The scroll offset along which the timeline progresses.
+    pub axis: ScrollAxis,
+    /// The scroll offsets, in layer pixels, at which the animation is at 0% and 100% progress.
+    pub range: (f32, f32),
+    /// The compositor animation driven by this timeline.
+    pub target_animation: AnimationId,
+}
+
 /// Whether a DOM event was prevented by web content
 #[derive(Deserialize, Serialize)]
 pub enum EventResult {
@@ -53,6 +834,108 @@ pub enum LogEntry {
     Warn(String)
 }
 
+bitflags! {
+    #[doc = "The kinds of data stored for an origin that `ClearSiteData` can remove."]
+    #[derive(Deserialize, Serialize)]
+    pub flags ClearDataTypes: u8 {
+        #[doc = "Cookies set for the origin's host."]
+        const CLEAR_COOKIES = 0x01,
+        #[doc = "`localStorage` and `sessionStorage` data."]
+        const CLEAR_STORAGE = 0x02,
+        #[doc = "Cache API storage."]
+        const CLEAR_CACHE = 0x04,
+        #[doc = "Service worker registrations."]
+        const CLEAR_SERVICE_WORKERS = 0x08,
+    }
+}
+
+/// The storage used by an origin, in bytes, for each storage backend.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StorageBreakdown {
+    /// Bytes used by the Cache API.
+    pub cache_bytes: u64,
+    /// Bytes used by IndexedDB databases.
+    pub indexeddb_bytes: u64,
+    /// Bytes used by `localStorage`.
+    pub localstorage_bytes: u64,
+    /// Bytes used by service worker registrations and their scripts.
+    pub serviceworker_bytes: u64,
+}
+
+/// The contents and geometry of a document's selection.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SelectionInfo {
+    /// The selected text.
+    pub text: String,
+    /// The boxes covering the selection, one per line box fragment, in CSS pixels relative to
+    /// the pipeline's viewport.
+    pub rects: Vec<Rect<f32>>,
+}
+
+/// The media types a document can be laid out for.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MediaType {
+    /// A screen, which is what documents are normally laid out for.
+    Screen,
+    /// Paged output, for printing and print preview.
+    Print,
+}
+
+/// Whether the browser's navigation UI should be shown while an element is fullscreen.
+/// https://fullscreen.spec.whatwg.org/#dictdef-fullscreenoptions
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NavigationUiPreference {
+    /// Leave the choice to the embedder. This is the default, as in the specification.
+    Auto,
+    /// Keep the navigation UI visible.
+    Show,
+    /// Hide the navigation UI, giving the element the whole screen.
+    Hide,
+}
+
+impl Default for NavigationUiPreference {
+    fn default() -> NavigationUiPreference {
+        NavigationUiPreference::Auto
+    }
+}
+
+/// The options passed to `requestFullscreen()`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FullscreenOptions {
+    /// The preferred visibility of the navigation UI.
+    pub navigation_ui: NavigationUiPreference,
+}
+
+/// What the embedder's autoplay policy gets to know about a media element that wants to start
+/// playing on its own.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AutoplayContext {
+    /// Whether the element would play without sound.
+    pub muted: bool,
+    /// Whether the document has transient user activation from a click, key press or the like.
+    pub has_user_gesture: bool,
+}
+
+/// An identifier for an element, stable for the element's lifetime. This is the same identifier
+/// that devtools uses for the node.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ElementId(pub String);
+
+/// An identifier for a media element, unique within its pipeline.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, HeapSizeOf, PartialEq, Serialize)]
+pub struct MediaElementId(pub u64);
+
+/// A tooltip to show for the element under the mouse.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Tooltip {
+    /// The text to show, taken from the nearest `title` attribute.
+    pub text: String,
+    /// The element's accessible description, for assistive technology. It comes from the
+    /// elements referenced by `aria-describedby` when that attribute is present, with `text`
+    /// still shown visually; otherwise it is `None`, and `text` doubles as the description.
+    pub description: Option<String>,
+}
+
 /// Messages from the script to the constellation.
 #[derive(Deserialize, Serialize)]
 pub enum ScriptMsg {
@@ -72,26 +955,363 @@ pub enum ScriptMsg {
     DOMLoad(PipelineId),
     /// Notifies the constellation that this frame has received focus.
     Focus(PipelineId),
+    /// Reports that focus moved to the given element, after `Focus`, once script has scrolled
+    /// the element into view (through `ScrollFragmentPoint`) if it was outside the viewport. The
+    /// embedder passes it on to assistive technology, which can then follow the focus.
+    EnsureFocusVisible(PipelineId, ElementId),
+    /// Reports the element that should have focus when the pipeline's document is first shown:
+    /// the element script focused while the document loaded, or else the first element with an
+    /// `autofocus` attribute, or `None` if neither exists. It is sent once the `load` event has
+    /// fired, immediately before `DOMLoad`. The constellation keeps it to restore focus when the
+    /// window is activated, and passes it on to the embedder for assistive technology.
+    SetInitialFocus(PipelineId, Option<ElementId>),
+    /// Reports the `accesskey` shortcuts of the pipeline's document, as pairs of the assigned
+    /// key and the element it activates, in tree order, after an element with an `accesskey`
+    /// attribute is added, removed or changes its keys. Changes are batched up and sent at most
+    /// once per reflow. Embedders can list the shortcuts in their menus and handle the platform
+    /// accelerators for them; activating one is routed back to script with
+    /// `WindowEvent::ActivateAccessKey`, which focuses and clicks the element.
+    SetAccessKeys(PipelineId, Vec<(String, ElementId)>),
+    /// Reports that spatial navigation, which moves focus with the arrow keys to the nearest
+    /// focusable element in a direction, found no target in the pipeline's document. The
+    /// constellation continues the search in the parent frame's document, starting from the
+    /// frame's `iframe`, which also covers sibling frames. Top-level frames have nowhere further
+    /// to look, so focus stays where it is. Unlike sequential (Tab-order) focus navigation, which
+    /// is handled entirely within script, this message is only for the hand-off between frames;
+    /// once an element has been found, the frame that owns it reports it with `Focus` as usual.
+    /// Spatial navigation is off unless the `dom.spatial_navigation.enabled` preference is set.
+    SpatialNavigate(PipelineId, SpatialDirection),
     /// Re-send a mouse button event that was sent to the parent window.
     ForwardMouseButtonEvent(PipelineId, MouseEventType, MouseButton, Point2D<f32>),
     /// Re-send a mouse move event that was sent to the parent window.
     ForwardMouseMoveEvent(PipelineId, Point2D<f32>),
+    /// Asks the embedder to run its native drag loop for a drag that started in the pipeline's
+    /// document, once `dragstart` has fired and not been cancelled. The embedder shows the drag
+    /// image under the pointer for the rest of the drag, also outside the window.
+    StartDrag(PipelineId, DragData),
+    /// Removes the selected kinds of data stored for the origin of the pipeline's document, and
+    /// replies once they are gone. This backs the `Clear-Site-Data` response header; the origin
+    /// comes from the pipeline, so a document can only clear its own origin's data.
+    ClearSiteData(PipelineId, ClearDataTypes, IpcSender<()>),
+    /// Subscribes the pipeline to notifications that the system clipboard changed, or
+    /// unsubscribes it, for the async Clipboard API's `clipboardchange` event. The notifications
+    /// are sent back as `ConstellationControlMsg::ClipboardChanged`. The clipboard crate cannot
+    /// watch the system clipboard, so only writes that go through the constellation
+    /// (`SetClipboardContents`) are noticed, not copies made in other applications. Subscribing
+    /// exposes when the user copies something, so script must only send this once the document
+    /// holds the `clipboard-read` permission.
+    SubscribeClipboardChanges(PipelineId, bool),
     /// Requests that the constellation retrieve the current contents of the clipboard
     GetClipboardContents(IpcSender<String>),
+    /// Reads the clipboard as the given MIME type (`text/plain` or `text/html`), replying with
+    /// `None` when the clipboard holds nothing of that type. When the flag is set, `text/html`
+    /// is passed through `constellation::sanitizer::sanitize_html` first, which strips scripts,
+    /// embedded content and event handler attributes; see that module for the exact list.
+    GetClipboardData(String, bool, IpcSender<Option<String>>),
+    /// Requests the keyboard layout map for `navigator.keyboard.getLayoutMap()`: pairs of a
+    /// physical key's `code` (`"KeyQ"`) and the key it produces in the current layout (`"a"` on
+    /// AZERTY). The layout can identify the user, so script only asks once the document holds
+    /// the `keyboard-map` permission.
+    GetKeyboardLayoutMap(IpcSender<Vec<(String, String)>>),
+    /// Requests how much storage the origin of the pipeline's document is using, broken down by
+    /// backend. This is the per-backend counterpart of a storage estimate
+    /// (`navigator.storage.estimate()`), which only reports the total, and is what devtools
+    /// storage panels display.
+    GetStorageBreakdown(PipelineId, IpcSender<StorageBreakdown>),
+    /// Asks the embedder to show the whole window fullscreen on behalf of an element of the
+    /// pipeline's document (a `<video>`, say) with the given options, or to leave fullscreen
+    /// when `None`. Requests to enter fullscreen are ignored unless the pipeline has transient
+    /// user activation (see `NotifyUserActivation`); leaving fullscreen is always allowed.
+    SetFullscreenState(PipelineId, Option<FullscreenOptions>),
+    /// Reports that the user interacted with the pipeline's document through an
+    /// activation-triggering input event: a mouse button or key press other than Escape, or the
+    /// end of a touch. Script sends it before dispatching the event. The pipeline and its
+    /// ancestors then have transient user activation for five seconds, which the constellation
+    /// checks before passing on messages that need it, `SetFullscreenState` and
+    /// `RequestStorageAccess`, rather than trusting the sending script thread to check.
+    NotifyUserActivation(PipelineId),
     /// <head> tag finished parsing
     HeadParsed,
     /// All pending loads are complete.
     LoadComplete(PipelineId),
-    /// A new load has been requested.
-    LoadUrl(PipelineId, LoadData),
+    /// The mouse moved over a link with the given target, or away from the last link hovered
+    /// when `None`. Unlike `NodeStatus`, the URL is left unformatted so that the embedder can
+    /// elide it and highlight its host itself.
+    LinkHover(PipelineId, Option<Url>),
+    /// Asks the embedder to show the given media element in a picture-in-picture window, for
+    /// `requestPictureInPicture()`. The embedder replies with an error message if it refuses.
+    RequestPictureInPicture(PipelineId, MediaElementId, IpcSender<Result<(), String>>),
+    /// Asks the embedder to close the picture-in-picture window showing the given media element.
+    ExitPictureInPicture(PipelineId, MediaElementId),
+    /// Asks the embedder to capture the given keys (by `code`, or every key when empty) for
+    /// `navigator.keyboard.lock()`, so that keys it normally reserves, such as Escape and system
+    /// shortcuts, reach the page instead. Browsers only honour this while the page is fullscreen,
+    /// so the embedder is expected to refuse, replying with an error, otherwise.
+    LockKeyboard(PipelineId, Vec<String>, IpcSender<Result<(), String>>),
+    /// Starts recognizing speech from the microphone for a `SpeechRecognition` object, replying
+    /// with the identifier of the new recognition session. Script must only send this once the
+    /// document holds the `microphone` permission. Recognized phrases come back as
+    /// `SpeechRecognitionResult`s until the session is stopped or, unless it is continuous, the
+    /// user stops speaking.
+    StartSpeechRecognition(PipelineId, SpeechRecognitionConfig, IpcSender<SpeechRecognitionId>),
+    /// Stops a speech recognition session, for `SpeechRecognition.stop()` and `abort()`.
+    StopSpeechRecognition(SpeechRecognitionId),
+    /// Asks for a stream from the user's camera and/or microphone, for
+    /// `navigator.mediaDevices.getUserMedia()`. Script must only send this once the document
+    /// holds the `camera` permission for video and the `microphone` permission for audio; the
+    /// embedder may still ask the user which device to use. The reply describes the tracks of
+    /// the new stream, or why none could be opened.
+    RequestUserMedia(PipelineId, MediaConstraints, IpcSender<Result<MediaStreamInfo, MediaError>>),
+    /// Lists the user's media input and output devices, for
+    /// `navigator.mediaDevices.enumerateDevices()`. To keep devices from being used to
+    /// fingerprint the user, their labels are empty until the document has been granted access
+    /// to a device of that kind, through `RequestUserMedia`; the embedder, which keeps track of
+    /// what it granted, is responsible for blanking them.
+    EnumerateMediaDevices(PipelineId, IpcSender<Vec<MediaDeviceInfo>>),
+    /// Asks to capture the contents of a screen, window or tab, for
+    /// `navigator.mediaDevices.getDisplayMedia()`. The embedder must always let the user pick
+    /// what to share in its own source picker: the page cannot choose a source, and access is
+    /// never granted without the picker, even if an earlier capture was allowed. The surface in
+    /// the constraints only decides which kind of source the picker offers first. Script must
+    /// only send this while handling a user gesture. Cancelling the picker is reported as
+    /// `MediaError::NotAllowed`.
+    RequestDisplayCapture(PipelineId, DisplayCaptureConstraints, IpcSender<Result<MediaStreamInfo, MediaError>>),
+    /// Starts gathering ICE candidates for an `RTCPeerConnection`, replying with the identifier
+    /// of the gathering session. Finding candidates needs the host's network interfaces and,
+    /// through the STUN and TURN servers in the configuration, its public addresses or a relay,
+    /// none of which content processes can reach themselves, so the embedder does the gathering.
+    /// The candidates it finds come back as `IceCandidate`s.
+    GatherIceCandidates(PipelineId, IceConfig, IpcSender<IceGatheringId>),
+    /// Asks for the largest number of simultaneous touch contacts the device supports, for
+    /// `navigator.maxTouchPoints`, which is 0 on devices without a touch screen. It is also what
+    /// the `pointer` and `hover` media queries fall back on. An embedder emulating another
+    /// device reports that device's value instead of the host's.
+    GetMaxTouchPoints(IpcSender<u32>),
+    /// Asks what pointing devices the user has, for the `pointer`, `hover`, `any-pointer` and
+    /// `any-hover` media features. The embedder answers from the input devices attached to the
+    /// host, or, when emulating another device, from that device's.
+    GetPointerCapabilities(IpcSender<PointerCapabilities>),
+    /// Asks what the display the pipeline's window is on can show, for the `color-gamut` and
+    /// `dynamic-range` media features. The reply only describes the current display, so script
+    /// asks again rather than caching it.
+    GetDisplayCapabilities(PipelineId, IpcSender<DisplayCapabilities>),
+    /// Asks for the refresh rate, in Hz, of the display the pipeline's window is on, so that
+    /// `requestAnimationFrame` callbacks can be paced to it and the `update` media feature
+    /// answered. A reply of 0.0 means the rate is unknown or variable.
+    GetDisplayRefreshRate(PipelineId, IpcSender<f32>),
+    /// Lists the screens attached to the host, for `window.getScreenDetails()`. The screen
+    /// configuration can be used to fingerprint the user, so script must only send this once the
+    /// document holds the `window-management` permission; without it, `getScreenDetails()`
+    /// rejects and the embedder is never asked.
+    GetScreens(PipelineId, IpcSender<Vec<ScreenInfo>>),
+    /// Asks to move the pipeline's window, possibly onto another screen, for multi-screen
+    /// `window.moveTo()` and `requestFullscreen({ screen })`. The bounds are in the same virtual
+    /// desktop coordinates as the `ScreenInfo::bounds` reported by `GetScreens`, and
+    /// `screen_label` names the screen from that list the window should end up on. Script must
+    /// only send this once the document holds the `window-management` permission; without it,
+    /// the window can only be moved within its current screen, with `MoveTo`. Requests from
+    /// subframes are ignored.
+    RequestWindowPlacement(PipelineId, WindowPlacement),
+    /// Reports that a script called `document.write` on the pipeline's document after it had
+    /// finished loading, which implicitly opens a new, blank document in its place. The
+    /// pipeline keeps its id: this message precedes a fresh parse of the same pipeline, and the
+    /// old document's contents are lost.
+    DocumentWriteAfterLoad(PipelineId),
+    /// Describes the pipeline's frame for the devtools frame tree, each time a document is loaded
+    /// into it. The constellation passes it on to the devtools server, if there is one.
+    ReportFrameInfo(PipelineId, FrameInfo),
+    /// Reports which bytes of a script or style sheet loaded by the pipeline have been used. The
+    /// constellation passes it on to the devtools server, if there is one, which shows the share
+    /// of unused bytes of each resource.
+    ReportCodeCoverage(PipelineId, CoverageReport),
+    /// Reports the statistics of the pipeline's recent animation frames, oldest first. Script
+    /// batches them rather than sending a message per frame. The constellation summarizes the
+    /// pipeline's last 60 frames for the embedder's performance overlay.
+    ReportFrameStats(PipelineId, Vec<FrameStats>),
+    /// Asks for the URL of the link an element is in, for the embedder's context menu to copy. The
+    /// link is the nearest `a` or `area` element with an `href`, from the element itself up; its
+    /// `href` is resolved against the document's base URL, so that `<base href>` is taken into
+    /// account. The answer is `None` if the element is in no link, or its `href` does not parse.
+    ResolveLinkForContextMenu(PipelineId, ElementId, IpcSender<Option<Url>>),
+    /// Asks how the host platform draws scrollbars, for `scrollbar-width`, `scrollbar-color` and
+    /// the scrollbar gutter. The embedder answers from the OS settings. Classic scrollbars take
+    /// their `width` away from the space available to lay out a scroll container's contents;
+    /// overlay scrollbars are drawn over the contents and leave their layout width unchanged.
+    GetScrollbarPreferences(IpcSender<ScrollbarPreferences>),
+    /// Reports that a subresource loaded by the pipeline failed its Subresource Integrity
+    /// check, with the resource's URL and a summary of the expected and actual hashes. This is
+    /// not a warning: the resource is blocked, so a script is not run and a style sheet is not
+    /// applied, and the element that requested it gets an `error` event as for a network error.
+    IntegrityCheckFailed(PipelineId, Url, String),
+    /// Reports that a navigation of the pipeline's frame to the given URL was blocked by the
+    /// embedder's phishing and malware protection. The load does not proceed: the constellation
+    /// drops any pending document for the frame, which keeps showing the pipeline, and has the
+    /// embedder show a warning interstitial. This is distinct from a failed load, reported with
+    /// the `load_error` embedder method and its `NetError`: the URL may well be reachable, but
+    /// must not be loaded, so no `about:failure` page is shown either. The user's choice on the
+    /// interstitial is reported with `InterstitialDecision`.
+    NavigationBlocked(PipelineId, Url, BlockReason),
+    /// Reports whether the user chose to proceed past the warning interstitial shown over the
+    /// pipeline's frame (`true`), or to go back to safety (`false`). Either way the embedder
+    /// hides the interstitial. Proceeding loads the URL that was blocked into the frame, so the
+    /// embedder's protection must let it through this time; going back leaves the pipeline's
+    /// document as it was. This is the one override flow for every kind of interstitial: those
+    /// for `NavigationBlocked`, and, once certificate errors get an override, the
+    /// `about:sslfail` page shown for them, which for now has no way to proceed.
+    InterstitialDecision(PipelineId, bool),
+    /// Asks for the size of the joint session history of the pipeline's top-level browsing context
+    /// and the current position in it, which the constellation answers from its frame tree. For
+    /// privacy, a frame whose origin differs from the top-level document's only sees its own
+    /// contribution: the entries of its own frame's session history, and its position in those,
+    /// so that it cannot learn how its embedder has been navigated.
+    GetHistoryState(PipelineId, IpcSender<HistoryState>),
+    /// Asks whether the pipeline's joint session history has entries before and after the current
+    /// entry. This is a lightweight alternative to `GetHistoryState` for enabling back and
+    /// forward buttons, and is subject to the same restriction for cross-origin frames.
+    GetNavigationState(PipelineId, IpcSender<NavigationCapability>),
+    /// Reports a change in the pipeline's page lifecycle, which the constellation passes on to
+    /// the embedder so that it can tell when a page enters or leaves the back-forward cache, to
+    /// restore its scroll position, say. These are the points at which `pagehide` and `pageshow`
+    /// fire with `persisted` set. `Active` follows `LoadComplete`. `Frozen` and `Resumed` follow
+    /// the script thread's handling of `ConstellationControlMsg::Freeze` and
+    /// `ConstellationControlMsg::Thaw`, which the constellation sends when session history
+    /// traversal hides and shows the pipeline, so they always arrive after the constellation has
+    /// switched documents. `Terminated` precedes `PipelineExited`.
+    ReportPageLifecycle(PipelineId, PageLifecycleState),
+    /// Sets the scroll restoration mode of the pipeline's session history entry, which decides
+    /// whether the constellation reapplies the entry's saved scroll offset when session history
+    /// is traversed back to it. `Manual` suppresses automatic scroll restoration, leaving the
+    /// page to scroll itself, from its `popstate` handler, say. Entries start out as `Auto`.
+    SetScrollRestoration(PipelineId, ScrollRestorationMode),
+    /// Saves the scroll offset of the given layer of the pipeline's document on its session
+    /// history entry. Script sends this just before navigating away, and when session history is
+    /// traversed back to the entry, the constellation reapplies the offset by sending
+    /// `ScrollFragmentPoint` to the compositor, unless `SetScrollRestoration` set the entry's
+    /// mode to `Manual`.
+    SaveScrollPosition(PipelineId, LayerId, Point2D<f32>),
+    /// Reports that the pipeline navigated within its document, to the given URL, without loading
+    /// a new one, so that the constellation can update its session history and the embedder its
+    /// address bar. This covers fragment navigations as well as the History API's `pushState`
+    /// and `replaceState`.
+    SameDocumentNavigation(PipelineId, Url, NavigationType),
+    /// Reports what should be done with the response loaded by the given pipeline, once its
+    /// status and `Content-Disposition` header are known. The constellation continues the load
+    /// for `Navigate`, and otherwise drops the pipeline, keeping the document it was to replace.
+    /// Downloads are handed to the embedder.
+    ResolveNavigationDisposition(PipelineId, Url, NavigationDisposition),
+    /// Requests the Web Lock with the given name for the pipeline, for `navigator.locks.request`.
+    /// The lock is shared by every context of the pipeline's origin, as the constellation knows
+    /// it. The grant is sent once the lock is available: straight away if nothing holds it, and
+    /// otherwise after the holders release it. Requests are granted in the order they were made,
+    /// so a shared request waits behind an exclusive one queued before it even while the lock is
+    /// only held shared.
+    AcquireWebLock(PipelineId, String, WebLockMode, IpcSender<WebLockGrant>),
+    /// Releases a Web Lock granted to the pipeline, letting whatever is queued for it go ahead.
+    /// Locks still held when the pipeline exits are released then.
+    ReleaseWebLock(PipelineId, WebLockToken),
+    /// Opens the IndexedDB database with the given name and version, for `indexedDB.open`.
+    /// Databases are scoped to the origin of the pipeline, as the constellation knows it: two
+    /// origins opening the same name get different databases, and every same-origin context
+    /// opening it shares one. The version must be at
+    /// least 1, and opening with 0 fails with a `TypeError` message. Opening with a version lower
+    /// than the database's current one fails with a `VersionError` message; otherwise the
+    /// connection is sent back, and the version upgrade, if one is needed, and every later
+    /// operation go through it.
+    OpenIndexedDb(PipelineId, String, u64, IpcSender<Result<IdbConnectionId, String>>),
+    /// Opens a new top-level browsing context for `window.open`, sending back the id of the
+    /// pipeline it loads into, and passes the requested features on to the embedder. Unless
+    /// `noopener` is set, that pipeline's `window.opener` refers to the opener; with it, the two
+    /// are unrelated, and script returns `null` from `window.open` even though the pipeline was
+    /// created. The new context has a frame tree of its own beside the root one, so it never
+    /// replaces its opener's page; it is up to the embedder to show it. The sender is dropped if
+    /// the context cannot be opened.
+    OpenAuxiliaryBrowsingContext(AuxiliaryContextInfo, IpcSender<PipelineId>),
+    /// Resolves a browsing context name, from a link's `target` or the second argument of
+    /// `window.open`, to the pipeline currently shown in that context, so that script can
+    /// navigate it rather than open a new one. `_self`, `_parent` and `_top`, ignoring case,
+    /// resolve as usual, and `_blank` never does. Other names only match contexts the pipeline is
+    /// familiar with: those with a same-origin document, those with a same-origin ancestor, and
+    /// the top-level context, so that a page cannot find out the names of cross-origin frames
+    /// nested in other pages. Sends back `None` if nothing matches.
+    FindNamedBrowsingContext(PipelineId, String, IpcSender<Option<PipelineId>>),
+    /// Queues a report for the Reporting API. Reports are not delivered yet, since no
+    /// `Reporting-Endpoints` header is parsed to deliver them to: the constellation keeps them,
+    /// dropping the oldest once too many are waiting. Reports outlive the pipeline that queued
+    /// them. CSP violations are reported through this as `ReportBody::Csp`.
+    QueueReport(PipelineId, ReportBody),
+    /// Reports that the engine overrode what the pipeline's document asked for, with a message
+    /// for developers. Interventions are informational rather than errors: the page keeps
+    /// running, only without what was intervened on. The constellation shows the message in the
+    /// devtools console, and keeps an intervention report with the undelivered Reporting API
+    /// reports, as `QueueReport` does.
+    InterventionReport(PipelineId, InterventionKind, String),
+    /// Reports that the pipeline's document used a deprecated feature. Script reports each
+    /// feature once per document, however many times it is used, so that a deprecated call in a
+    /// loop does not flood the console. The constellation shows the message in the devtools
+    /// console as a warning, and keeps a deprecation report with the undelivered Reporting API
+    /// reports, as `QueueReport` does.
+    DeprecationReport(PipelineId, DeprecationInfo),
+    /// Asks the embedder to show its on-screen keyboard, for `navigator.virtualKeyboard.show()`.
+    /// The reply is the area of the viewport the keyboard covers once shown, which is empty
+    /// when there is no on-screen keyboard. Normally the viewport shrinks to make room for the
+    /// keyboard; when the page has set `overlaysContent`, it does not, and the keyboard is drawn
+    /// over the page, which uses the reported geometry to move its content out of the way.
+    ShowVirtualKeyboard(PipelineId, IpcSender<VirtualKeyboardGeometry>),
+    /// Asks the embedder to hide its on-screen keyboard, for `navigator.virtualKeyboard.hide()`.
+    HideVirtualKeyboard(PipelineId),
+    /// Reports whether the embedder's input method editor should be enabled, and for what kind
+    /// of text entry, whenever the focused element of the pipeline's document changes. It is
+    /// only enabled while an editable text field has focus; `ImeState::input_type` lets the
+    /// embedder pick the matching on-screen keyboard.
+    SetImeState(PipelineId, ImeState),
+    /// A new load has been requested, for the given reason, which the constellation records on
+    /// the session history entry it creates.
+    LoadUrl(PipelineId, LoadData, NavigationReason),
     /// Dispatch a mozbrowser event to a given iframe. Only available in experimental mode.
     MozBrowserEvent(PipelineId, SubpageId, MozBrowserEvent),
     /// HTMLIFrameElement Forward or Back navigation.
     Navigate(Option<(PipelineId, SubpageId)>, NavigationDirection),
-    /// Favicon detected
-    NewFavicon(Url),
-    /// Status message to be displayed in the chrome, eg. a link URL on mouseover.
+    /// Favicon detected in the given pipeline's document. Only the favicon of the top-level
+    /// pipeline is shown in the window chrome; favicons of subframes are ignored.
+    NewFavicon(PipelineId, Url),
+    /// Status message to be displayed in the chrome. Link targets under the mouse are reported
+    /// with `LinkHover` instead.
     NodeStatus(Option<String>),
+    /// Reports that the document set `document.domain`, or that its effective domain went back to
+    /// its origin's host when `None`. Setting it relaxes the same-origin check between frames
+    /// whose documents opt in to the same registrable domain, so the constellation must record it
+    /// and use it, rather than the URL's origin, when deciding whether frames may script each
+    /// other. Script must only send a domain that is the document's host or one of its
+    /// registrable suffixes. The `document.domain` setter is deprecated, and origin-keyed agent
+    /// clusters ignore it, so this only affects documents that have not opted in to those.
+    SetDocumentDomain(PipelineId, Option<String>),
+    /// Severs the opener relationship of the given pipeline, because its document was served
+    /// with a `Cross-Origin-Opener-Policy` that does not match its opener's. Afterwards neither
+    /// side can reach the other: `window.opener` is `null` in the pipeline, and windows it
+    /// opened see `null` too. This has the same effect as opening the window with `noopener`.
+    SeverOpener(PipelineId),
+    /// Reports the media type the pipeline's document is now laid out for: `Print` when print
+    /// layout (or print preview) begins, and `Screen` again once it ends. This must be sent, and
+    /// the document restyled so that `@media print` rules and `matchMedia('print')` match,
+    /// before the page is captured for printing, and only switched back after the capture. The
+    /// constellation passes it on to the embedder, which does the capturing.
+    SetMediaType(PipelineId, MediaType),
+    /// Enables or disables navigation preload for the given pipeline, with an optional value for
+    /// the `Service-Worker-Navigation-Preload` header (`"true"` when `None`). While enabled, the
+    /// constellation itself starts fetching the page of each top-level navigation the pipeline
+    /// initiates, with that header, in parallel with starting up the new pipeline, which then
+    /// takes the response over. Navigations of frames are fetched by their parent's script
+    /// thread right away, so they only carry the header. Once service worker registration
+    /// (`RegisterServiceWorker`) is routed through the constellation, this setting belongs to the
+    /// registration rather than to the pipeline.
+    SetNavigationPreload(PipelineId, bool, Option<String>),
+    /// Reports that the document's selection changed, or was cleared when `None`, so that the
+    /// embedder can draw selection overlays or share the selection with collaborators. While the
+    /// user drags out a selection, script should send at most one of these per animation frame
+    /// rather than one per mouse move.
+    SelectionChanged(PipelineId, Option<SelectionInfo>),
+    /// Releases a keyboard lock taken with `LockKeyboard`.
+    UnlockKeyboard(PipelineId),
     /// Notification that this iframe should be removed.
     RemoveIFrame(PipelineId, Option<IpcSender<()>>),
     /// Change pipeline visibility
@@ -100,20 +1320,64 @@ pub enum ScriptMsg {
     VisibilityChangeComplete(PipelineId, bool),
     /// A load has been requested in an IFrame.
     ScriptLoadedURLInIFrame(IFrameLoadInfo),
+    /// Asks whether a media element may start playing without the user asking it to. The
+    /// answer comes from the embedder's autoplay policy, which is also where any engagement
+    /// heuristics (how often the user has played media on the site before, say) belong.
+    CheckAutoplayAllowed(PipelineId, AutoplayContext, IpcSender<bool>),
+    /// Asks for first-party storage (cookie) access on behalf of an embedded frame, backing
+    /// `document.requestStorageAccess()`. The reply is whether access was granted. The decision
+    /// is left to the embedder's anti-tracking policy, which may prompt the user. Unless the
+    /// pipeline has transient user activation (see `NotifyUserActivation`), the constellation
+    /// denies access without asking the embedder.
+    RequestStorageAccess(PipelineId, IpcSender<bool>),
     /// Requests that the constellation set the contents of the clipboard
     SetClipboardContents(String),
     /// Mark a new document as active
     ActivateDocument(PipelineId),
     /// Set the document state for a pipeline (used by screenshot / reftests)
     SetDocumentState(PipelineId, DocumentState),
+    /// Reports the hash of the latest composited frame of a pipeline, for reftests. The hash is
+    /// taken over the RGB pixels of the whole composited surface, as they would be written to
+    /// the screenshot, so equal hashes mean identical output. Until the last two hashes reported
+    /// for a pipeline agree, the constellation does not consider it stable enough to save the
+    /// screenshot; pipelines that never report one are not held back.
+    ReportFrameHash(PipelineId, u64),
     /// Update the pipeline Url, which can change after redirections.
     SetFinalUrl(PipelineId, Url),
     /// Check if an alert dialog box should be presented
     Alert(PipelineId, String, IpcSender<bool>),
     /// Scroll a page in a window
     ScrollFragmentPoint(PipelineId, LayerId, Point2D<f32>, bool),
+    /// Reports that script scrolled to the text matched by the `#:~:text=` directive of the
+    /// pipeline's URL, through `ScrollFragmentPoint`, so that the compositor can highlight the
+    /// match. It is only sent when the directive matched: otherwise script ignores it, scrolling
+    /// only for the element id before the directive, if there is one, and not at all if not.
+    ScrollToTextFragment(PipelineId, TextFragmentDirective),
+    /// Reports the character encoding of the pipeline's document, sent once when the document is
+    /// created. The parser does not switch encodings part way through yet, so a late
+    /// `<meta charset>` neither re-parses the document nor sends this message again.
+    SetDocumentEncoding(PipelineId, String),
+    /// Reports the MIME type of the pipeline's document (`text/html`, `application/xhtml+xml`,
+    /// `text/plain`, an image type, ...) once the response type is known, so that the embedder
+    /// can treat documents that aren't HTML specially. Content types that get downloaded rather
+    /// than rendered never produce a document, so they are never reported here.
+    SetContentType(PipelineId, String),
+    /// Reports the color given by a `<meta name="theme-color">` in the pipeline's document.
+    /// As with `NewFavicon`, only the top-level pipeline's theme color reaches the window
+    /// chrome, so that embedded content cannot restyle the browser around it.
+    SetThemeColor(PipelineId, RGBA),
+    /// Shows a tooltip for the element under the mouse, or hides it when `None`. It is only sent
+    /// when the mouse moves onto content whose tooltip comes from a different `title` element.
+    SetTooltip(PipelineId, Option<Tooltip>),
+    /// Sets the application badge for `navigator.setAppBadge()`: `Some(n)` shows the number
+    /// `n`, `Some(0)` shows a plain flag without a number, and `None` clears the badge. Only
+    /// the top-level pipeline may badge the application; badges set by subframes are ignored.
+    SetAppBadge(PipelineId, Option<u64>),
     /// Set title of current page
     /// https://html.spec.whatwg.org/multipage/#document.title
+    /// Sent whenever the title changes, also after the document has loaded. The constellation
+    /// records it as the title of the pipeline's session history entry, so that back and forward
+    /// menus show the current title.
     SetTitle(PipelineId, Option<String>),
     /// Send a key event
     SendKeyEvent(Option<char>, Key, KeyState, KeyModifiers),
@@ -129,8 +1393,22 @@ pub enum ScriptMsg {
     GetScrollOffset(PipelineId, LayerId, IpcSender<Point2D<f32>>),
     /// A log entry, with the pipeline id and thread name
     LogEntry(Option<PipelineId>, Option<String>, LogEntry),
-    /// Notifies the constellation that this pipeline has exited.
-    PipelineExited(PipelineId),
-    /// Requests that the compositor shut down.
-    Exit,
+    /// Reports that a script in the pipeline has been running for longer than its time budget,
+    /// with how long it has run so far, so that the embedder can ask the user whether to stop it,
+    /// the same way it asks them to confirm a `confirm()` dialog. The script keeps running while
+    /// the embedder decides; if the user stops it, the embedder sends `WindowEvent::KillPipeline`,
+    /// and the pipeline's exit is recorded as `PipelineExited(_, PipelineExitReason::Killed)`.
+    ScriptTimeout(PipelineId, MsDuration),
+    /// Answers a `ConstellationControlMsg::Ping` with the same id, to show that the pipeline's
+    /// script thread is still responsive. The constellation pings each pipeline at most once a
+    /// second, and only once its previous ping has been answered; a pipeline that has not
+    /// answered within five seconds is reported to the embedder as unresponsive, so that it can
+    /// offer to kill it, and as responsive again when the late pong arrives.
+    Pong(PipelineId, PingId),
+    /// Notifies the constellation that this pipeline has exited, and why.
+    PipelineExited(PipelineId, PipelineExitReason),
+    /// Requests that the compositor shut down. When a sender is given, it is sent `()` once
+    /// shutdown has completed, so that an embedder can wait for teardown to finish before exiting
+    /// the process; `None` shuts down without acknowledgement.
+    Exit(Option<IpcSender<()>>),
 }
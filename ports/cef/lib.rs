@@ -21,6 +21,7 @@ extern crate log;
 
 extern crate servo;
 extern crate compositing;
+extern crate cssparser;
 
 extern crate euclid;
 extern crate gleam;
@@ -20,14 +20,22 @@ use wrappers::CefWrap;
 
 use compositing::compositor_thread::{self, CompositorProxy, CompositorReceiver};
 use compositing::windowing::{WindowEvent, WindowMethods};
-use euclid::point::Point2D;
+use cssparser::RGBA;
+use euclid::point::{Point2D, TypedPoint2D};
+use euclid::rect::Rect;
 use euclid::scale_factor::ScaleFactor;
 use euclid::size::{Size2D, TypedSize2D};
 use gleam::gl;
 use layers::geometry::DevicePixel;
 use layers::platform::surface::NativeDisplay;
-use msg::constellation_msg::{Key, KeyModifiers};
+use msg::constellation_msg::{Key, KeyModifiers, PipelineId};
 use net_traits::net_error_list::NetError;
+use script_traits::{AutoplayContext, BlockReason, ColorGamut, CursorId, DisplayCapabilities};
+use script_traits::{DisplayCaptureConstraints, DragData, ElementId, FrameStatsSummary, FullscreenOptions, IceConfig};
+use script_traits::{IceGatheringId, ImeState, MediaConstraints, MediaDeviceInfo, MediaElementId, MediaError};
+use script_traits::{MediaStreamInfo, MediaType, MsDuration, PageLifecycleState, PointerAccuracy, PointerCapabilities};
+use script_traits::{ScreenInfo, ScrollbarPreferences, SelectionInfo, SpeechRecognitionConfig, SpeechRecognitionId};
+use script_traits::{TextFragmentDirective, Tooltip, VirtualKeyboardGeometry, WindowFeatures, WindowPlacement};
 use std::cell::RefCell;
 use std::ffi::CString;
 use std::os::raw::{c_char, c_void};
@@ -355,6 +363,14 @@ impl WindowMethods for Window {
         }
     }
 
+    fn link_hover(&self, url: Option<Url>) {
+        // CEF shows link targets through the status message.
+        self.status(url.map(|url| url.to_string()))
+    }
+
+    fn set_tooltip(&self, _: Option<Tooltip>) {
+    }
+
     fn load_start(&self, back: bool, forward: bool) {
         let browser = self.cef_browser.borrow();
         let browser = match *browser {
@@ -506,6 +522,184 @@ impl WindowMethods for Window {
     fn supports_clipboard(&self) -> bool {
         true
     }
+
+    fn set_page_encoding(&self, _: String) {
+    }
+
+    fn set_page_content_type(&self, _: String) {
+    }
+
+    fn set_theme_color(&self, _: RGBA) {
+    }
+
+    fn set_named_cursor(&self, _: CursorId, _: Cursor, _: TypedPoint2D<DevicePixel, f32>) {
+    }
+
+    fn set_selection(&self, _: Option<SelectionInfo>) {
+    }
+
+    fn request_picture_in_picture(&self, _: PipelineId, _: MediaElementId) -> Result<(), String> {
+        Err("Picture-in-picture is not supported".to_owned())
+    }
+
+    fn exit_picture_in_picture(&self, _: PipelineId, _: MediaElementId) {
+    }
+
+    fn set_fullscreen_state(&self, _: Option<FullscreenOptions>) {
+    }
+
+    fn allow_autoplay(&self, _: PipelineId, _: AutoplayContext) -> bool {
+        true
+    }
+
+    fn allow_storage_access(&self, _: PipelineId) -> bool {
+        // Storage is not partitioned, so embedded frames already have first-party access.
+        true
+    }
+
+    fn set_app_badge(&self, _: Option<u64>) {
+    }
+
+    fn download(&self, url: Url) {
+        warn!("Downloading {} is not supported.", url);
+    }
+
+    fn keyboard_layout_map(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn lock_keyboard(&self, _: PipelineId, _: Vec<String>) -> Result<(), String> {
+        Err("Keyboard lock is not supported".to_owned())
+    }
+
+    fn unlock_keyboard(&self, _: PipelineId) {
+    }
+
+    fn set_ime_state(&self, _: PipelineId, _: ImeState) {
+    }
+
+    fn show_virtual_keyboard(&self, _: PipelineId) -> VirtualKeyboardGeometry {
+        VirtualKeyboardGeometry {
+            bounding_rect: Rect::zero(),
+        }
+    }
+
+    fn hide_virtual_keyboard(&self, _: PipelineId) {
+    }
+
+    fn focus_visible(&self, _: PipelineId, _: ElementId) {
+    }
+
+    fn start_speech_recognition(&self, _: PipelineId, _: SpeechRecognitionId, _: SpeechRecognitionConfig) {
+    }
+
+    fn stop_speech_recognition(&self, _: SpeechRecognitionId) {
+    }
+
+    fn request_user_media(&self, _: PipelineId, _: MediaConstraints) -> Result<MediaStreamInfo, MediaError> {
+        // There are no capture devices to open.
+        Err(MediaError::NotFound)
+    }
+
+    fn media_devices(&self, _: PipelineId) -> Vec<MediaDeviceInfo> {
+        vec![]
+    }
+
+    fn request_display_capture(&self,
+                               _: PipelineId,
+                               _: DisplayCaptureConstraints)
+                               -> Result<MediaStreamInfo, MediaError> {
+        // There is no source picker, so the user can never agree to share anything.
+        Err(MediaError::NotAllowed)
+    }
+
+    fn gather_ice_candidates(&self, _: PipelineId, _: IceGatheringId, _: IceConfig) {
+    }
+
+    fn max_touch_points(&self) -> u32 {
+        // The browser host does not tell us how many touch contacts the device supports.
+        0
+    }
+
+    fn pointer_capabilities(&self) -> PointerCapabilities {
+        // Assume a desktop with a mouse.
+        PointerCapabilities {
+            pointer: PointerAccuracy::Fine,
+            hover: true,
+            any_pointer: vec![PointerAccuracy::Fine],
+            any_hover: true,
+        }
+    }
+
+    fn display_capabilities(&self, _: PipelineId) -> DisplayCapabilities {
+        // Assume a standard dynamic range sRGB display.
+        DisplayCapabilities {
+            color_gamut: ColorGamut::Srgb,
+            high_dynamic_range: false,
+            bits_per_channel: 8,
+        }
+    }
+
+    fn display_refresh_rate(&self, _: PipelineId) -> f32 {
+        // The browser host does not report the monitor's refresh rate.
+        0.0
+    }
+
+    fn screens(&self, _: PipelineId) -> Vec<ScreenInfo> {
+        // The browser host does not tell us about the screens.
+        vec![]
+    }
+
+    fn place_window(&self, _: WindowPlacement) {
+    }
+
+    fn set_initial_focus(&self, _: PipelineId, _: Option<ElementId>) {
+    }
+
+    fn set_access_keys(&self, _: PipelineId, _: Vec<(String, ElementId)>) {
+    }
+
+    fn start_drag(&self, _: PipelineId, _: DragData) {
+    }
+
+    fn highlight_text_fragment(&self, _: PipelineId, _: TextFragmentDirective) {
+    }
+
+    fn script_timed_out(&self, _: PipelineId, _: MsDuration) {
+    }
+
+    fn set_pipeline_responsive(&self, _: PipelineId, _: bool) {
+    }
+
+    fn update_performance_overlay(&self, _: PipelineId, _: FrameStatsSummary) {
+    }
+
+    fn scrollbar_preferences(&self) -> ScrollbarPreferences {
+        // The browser host does not tell us its scrollbar settings. Servo does not draw classic
+        // scrollbars, so report overlay ones, which take no layout width.
+        ScrollbarPreferences {
+            overlay: true,
+            width: 0.0,
+            auto_hide: true,
+        }
+    }
+
+    fn show_navigation_blocked(&self, _: PipelineId, _: Url, _: BlockReason) {
+    }
+
+    fn hide_interstitial(&self, _: PipelineId) {
+    }
+
+    fn page_lifecycle_changed(&self, _: PipelineId, _: PageLifecycleState) {
+    }
+
+    fn auxiliary_browsing_context_opened(&self, _: PipelineId, _: WindowFeatures) {
+        // There is only one window, which shows the root frame tree, so the new context runs
+        // without being shown.
+    }
+
+    fn media_type_changed(&self, _: PipelineId, _: MediaType) {
+    }
 }
 
 struct CefCompositorProxy {
@@ -8,9 +8,11 @@ use NestedEventLoopListener;
 use compositing::compositor_thread::{self, CompositorProxy, CompositorReceiver};
 use compositing::windowing::{MouseWindowEvent, WindowNavigateMsg};
 use compositing::windowing::{WindowEvent, WindowMethods};
+use cssparser::RGBA;
+use euclid::point::TypedPoint2D;
 use euclid::scale_factor::ScaleFactor;
 use euclid::size::TypedSize2D;
-use euclid::{Size2D, Point2D};
+use euclid::{Rect, Size2D, Point2D};
 #[cfg(target_os = "windows")] use gdi32;
 use gleam::gl;
 use glutin;
@@ -21,9 +23,15 @@ use glutin::{ScanCode, TouchPhase};
 use layers::geometry::DevicePixel;
 use layers::platform::surface::NativeDisplay;
 use msg::constellation_msg::{KeyState, NONE, CONTROL, SHIFT, ALT, SUPER};
-use msg::constellation_msg::{self, Key};
+use msg::constellation_msg::{self, Key, PipelineId};
 use net_traits::net_error_list::NetError;
-use script_traits::{TouchEventType, TouchpadPressurePhase};
+use script_traits::{AutoplayContext, BlockReason, ColorGamut, CursorId, DisplayCapabilities};
+use script_traits::{DisplayCaptureConstraints, DragData, ElementId, FrameStatsSummary, FullscreenOptions};
+use script_traits::{IceConfig, IceGatheringId, ImeState, MediaConstraints, MediaDeviceInfo, MediaElementId};
+use script_traits::{MediaError, MediaStreamInfo, MediaType, MsDuration, PageLifecycleState, PointerAccuracy};
+use script_traits::{PointerCapabilities, ScreenInfo, ScrollbarPreferences, SelectionInfo};
+use script_traits::{SpeechRecognitionConfig, SpeechRecognitionId, TextFragmentDirective, Tooltip, TouchEventType};
+use script_traits::{TouchpadPressurePhase, VirtualKeyboardGeometry, WindowFeatures, WindowPlacement};
 use std::cell::{Cell, RefCell};
 #[cfg(not(target_os = "android"))]
 use std::os::raw::c_void;
@@ -347,6 +355,9 @@ impl Window {
             Event::Refresh => {
                 self.event_queue.borrow_mut().push(WindowEvent::Refresh);
             }
+            Event::Focused(true) => {
+                self.event_queue.borrow_mut().push(WindowEvent::Activated);
+            }
             Event::Closed => {
                 return true
             }
@@ -756,6 +767,12 @@ impl WindowMethods for Window {
     fn status(&self, _: Option<String>) {
     }
 
+    fn link_hover(&self, _: Option<Url>) {
+    }
+
+    fn set_tooltip(&self, _: Option<Tooltip>) {
+    }
+
     fn load_start(&self, _: bool, _: bool) {
     }
 
@@ -932,6 +949,198 @@ impl WindowMethods for Window {
     fn supports_clipboard(&self) -> bool {
         true
     }
+
+    fn set_page_encoding(&self, _: String) {
+    }
+
+    fn set_page_content_type(&self, _: String) {
+    }
+
+    fn set_theme_color(&self, _: RGBA) {
+    }
+
+    fn set_named_cursor(&self, _: CursorId, _: Cursor, _: TypedPoint2D<DevicePixel, f32>) {
+    }
+
+    fn set_selection(&self, _: Option<SelectionInfo>) {
+    }
+
+    fn request_picture_in_picture(&self, _: PipelineId, _: MediaElementId) -> Result<(), String> {
+        Err("Picture-in-picture is not supported".to_owned())
+    }
+
+    fn exit_picture_in_picture(&self, _: PipelineId, _: MediaElementId) {
+    }
+
+    fn set_fullscreen_state(&self, _: Option<FullscreenOptions>) {
+    }
+
+    fn allow_autoplay(&self, _: PipelineId, _: AutoplayContext) -> bool {
+        true
+    }
+
+    fn allow_storage_access(&self, _: PipelineId) -> bool {
+        // Storage is not partitioned, so embedded frames already have first-party access.
+        true
+    }
+
+    fn set_app_badge(&self, _: Option<u64>) {
+    }
+
+    fn download(&self, url: Url) {
+        warn!("Downloading {} is not supported.", url);
+    }
+
+    fn keyboard_layout_map(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    fn lock_keyboard(&self, _: PipelineId, _: Vec<String>) -> Result<(), String> {
+        Err("Keyboard lock is not supported".to_owned())
+    }
+
+    fn unlock_keyboard(&self, _: PipelineId) {
+    }
+
+    fn set_ime_state(&self, _: PipelineId, _: ImeState) {
+    }
+
+    fn show_virtual_keyboard(&self, _: PipelineId) -> VirtualKeyboardGeometry {
+        VirtualKeyboardGeometry {
+            bounding_rect: Rect::zero(),
+        }
+    }
+
+    fn hide_virtual_keyboard(&self, _: PipelineId) {
+    }
+
+    fn focus_visible(&self, _: PipelineId, _: ElementId) {
+    }
+
+    fn start_speech_recognition(&self, _: PipelineId, _: SpeechRecognitionId, _: SpeechRecognitionConfig) {
+    }
+
+    fn stop_speech_recognition(&self, _: SpeechRecognitionId) {
+    }
+
+    fn request_user_media(&self, _: PipelineId, _: MediaConstraints) -> Result<MediaStreamInfo, MediaError> {
+        // There are no capture devices to open.
+        Err(MediaError::NotFound)
+    }
+
+    fn media_devices(&self, _: PipelineId) -> Vec<MediaDeviceInfo> {
+        vec![]
+    }
+
+    fn request_display_capture(&self,
+                               _: PipelineId,
+                               _: DisplayCaptureConstraints)
+                               -> Result<MediaStreamInfo, MediaError> {
+        // There is no source picker, so the user can never agree to share anything.
+        Err(MediaError::NotAllowed)
+    }
+
+    fn gather_ice_candidates(&self, _: PipelineId, _: IceGatheringId, _: IceConfig) {
+    }
+
+    fn max_touch_points(&self) -> u32 {
+        // glutin does not tell us how many touch contacts the device supports.
+        0
+    }
+
+    fn pointer_capabilities(&self) -> PointerCapabilities {
+        // Assume a desktop with a mouse.
+        PointerCapabilities {
+            pointer: PointerAccuracy::Fine,
+            hover: true,
+            any_pointer: vec![PointerAccuracy::Fine],
+            any_hover: true,
+        }
+    }
+
+    fn display_capabilities(&self, _: PipelineId) -> DisplayCapabilities {
+        // Assume a standard dynamic range sRGB display.
+        DisplayCapabilities {
+            color_gamut: ColorGamut::Srgb,
+            high_dynamic_range: false,
+            bits_per_channel: 8,
+        }
+    }
+
+    fn display_refresh_rate(&self, _: PipelineId) -> f32 {
+        // glutin does not report the monitor's refresh rate.
+        0.0
+    }
+
+    fn screens(&self, _: PipelineId) -> Vec<ScreenInfo> {
+        // glutin only tells us the size and name of the primary monitor.
+        let monitor = glutin::get_primary_monitor();
+        let (width, height) = monitor.get_dimensions();
+        let bounds = Rect::new(Point2D::zero(), Size2D::new(width as i32, height as i32));
+        vec![ScreenInfo {
+            bounds: bounds,
+            available_bounds: bounds,
+            device_pixel_ratio: self.window.hidpi_factor(),
+            is_primary: true,
+            label: monitor.get_name().unwrap_or_default(),
+        }]
+    }
+
+    fn place_window(&self, placement: WindowPlacement) {
+        // The bounds already say which screen the window goes on, and glutin windows cannot be
+        // made fullscreen after they are created.
+        self.set_position(placement.bounds.origin);
+        let size = placement.bounds.size;
+        self.set_inner_size(Size2D::new(size.width as u32, size.height as u32));
+    }
+
+    fn set_initial_focus(&self, _: PipelineId, _: Option<ElementId>) {
+    }
+
+    fn set_access_keys(&self, _: PipelineId, _: Vec<(String, ElementId)>) {
+    }
+
+    fn start_drag(&self, _: PipelineId, _: DragData) {
+    }
+
+    fn highlight_text_fragment(&self, _: PipelineId, _: TextFragmentDirective) {
+    }
+
+    fn script_timed_out(&self, _: PipelineId, _: MsDuration) {
+    }
+
+    fn set_pipeline_responsive(&self, _: PipelineId, _: bool) {
+    }
+
+    fn update_performance_overlay(&self, _: PipelineId, _: FrameStatsSummary) {
+    }
+
+    fn scrollbar_preferences(&self) -> ScrollbarPreferences {
+        // glutin does not expose the OS scrollbar settings. Servo does not draw classic
+        // scrollbars, so report overlay ones, which take no layout width.
+        ScrollbarPreferences {
+            overlay: true,
+            width: 0.0,
+            auto_hide: true,
+        }
+    }
+
+    fn show_navigation_blocked(&self, _: PipelineId, _: Url, _: BlockReason) {
+    }
+
+    fn hide_interstitial(&self, _: PipelineId) {
+    }
+
+    fn page_lifecycle_changed(&self, _: PipelineId, _: PageLifecycleState) {
+    }
+
+    fn auxiliary_browsing_context_opened(&self, _: PipelineId, _: WindowFeatures) {
+        // There is only one window, which shows the root frame tree, so the new context runs
+        // without being shown.
+    }
+
+    fn media_type_changed(&self, _: PipelineId, _: MediaType) {
+    }
 }
 
 struct GlutinCompositorProxy {
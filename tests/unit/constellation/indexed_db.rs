@@ -0,0 +1,37 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use constellation::indexed_db::IndexedDbManager;
+use script_traits::Origin;
+use url::Url;
+
+fn origin(url: &str) -> Origin {
+    Origin::new(&Url::parse(url).unwrap())
+}
+
+#[test]
+fn test_open_rejects_version_zero() {
+    let mut manager = IndexedDbManager::new();
+    let result = manager.open(origin("https://example.com/"), "db".to_owned(), 0);
+    assert!(result.unwrap_err().starts_with("TypeError"));
+
+    // The rejected open leaves the database free to be opened at version 1.
+    assert!(manager.open(origin("https://example.com/"), "db".to_owned(), 1).is_ok());
+}
+
+#[test]
+fn test_open_rejects_version_below_current() {
+    let mut manager = IndexedDbManager::new();
+    assert!(manager.open(origin("https://example.com/"), "db".to_owned(), 3).is_ok());
+    let result = manager.open(origin("https://example.com/"), "db".to_owned(), 2);
+    assert!(result.unwrap_err().starts_with("VersionError"));
+    assert!(manager.open(origin("https://example.com/"), "db".to_owned(), 3).is_ok());
+}
+
+#[test]
+fn test_databases_are_scoped_to_origin() {
+    let mut manager = IndexedDbManager::new();
+    assert!(manager.open(origin("https://example.com/"), "db".to_owned(), 5).is_ok());
+    assert!(manager.open(origin("https://example.org/"), "db".to_owned(), 1).is_ok());
+}
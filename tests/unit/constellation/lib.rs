@@ -0,0 +1,18 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+#![cfg(test)]
+#![feature(box_syntax)]
+
+extern crate compositing;
+extern crate constellation;
+extern crate ipc_channel;
+extern crate msg;
+extern crate script_traits;
+extern crate url;
+
+mod indexed_db;
+mod pipeline;
+mod sanitizer;
+mod web_locks;
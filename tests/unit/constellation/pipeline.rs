@@ -0,0 +1,169 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use compositing::CompositorProxy;
+use compositing::compositor_thread::Msg;
+use constellation::Pipeline;
+use ipc_channel::ipc::{self, IpcReceiver};
+use msg::constellation_msg::PipelineId;
+use script_traits::{ConstellationControlMsg, FrameStats, LayoutControlMsg, PingId};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use url::Url;
+
+struct TestCompositorProxy(Sender<Msg>);
+
+impl CompositorProxy for TestCompositorProxy {
+    fn send(&self, msg: Msg) {
+        self.0.send(msg).unwrap();
+    }
+
+    fn clone_compositor_proxy(&self) -> Box<CompositorProxy + 'static + Send> {
+        box TestCompositorProxy(self.0.clone())
+    }
+}
+
+fn new_pipeline() -> (Pipeline, IpcReceiver<ConstellationControlMsg>, Receiver<Msg>) {
+    let (script_chan, script_port) = ipc::channel().unwrap();
+    let (layout_chan, _layout_port) = ipc::channel::<LayoutControlMsg>().unwrap();
+    let (compositor_chan, compositor_port) = channel();
+    let (chrome_to_paint_chan, _chrome_to_paint_port) = channel();
+    let pipeline = Pipeline::new(PipelineId::fake_root_pipeline_id(),
+                                 None,
+                                 script_chan,
+                                 layout_chan,
+                                 box TestCompositorProxy(compositor_chan),
+                                 chrome_to_paint_chan,
+                                 false,
+                                 Url::parse("http://example.com/").unwrap(),
+                                 None,
+                                 true);
+    (pipeline, script_port, compositor_port)
+}
+
+fn expect_title(compositor_port: &Receiver<Msg>, expected: Option<&str>) {
+    match compositor_port.try_recv() {
+        Ok(Msg::ChangePageTitle(_, title)) => assert_eq!(title.as_ref().map(|t| &**t), expected),
+        _ => panic!("expected a title change"),
+    }
+}
+
+#[test]
+fn test_title_set_after_load_updates_history_entry() {
+    let (mut pipeline, script_port, compositor_port) = new_pipeline();
+
+    pipeline.set_title(Some("Loaded".to_owned()));
+    expect_title(&compositor_port, Some("Loaded"));
+
+    pipeline.set_title(Some("Changed by script".to_owned()));
+    expect_title(&compositor_port, Some("Changed by script"));
+    assert_eq!(pipeline.title, Some("Changed by script".to_owned()));
+
+    // Asking for the title answers from the updated entry without going back to script.
+    pipeline.send_title().unwrap();
+    expect_title(&compositor_port, Some("Changed by script"));
+    drop(pipeline);
+    assert!(script_port.recv().is_err());
+}
+
+#[test]
+fn test_missing_title_is_requested_from_script() {
+    let (pipeline, script_port, compositor_port) = new_pipeline();
+
+    pipeline.send_title().unwrap();
+    match script_port.recv() {
+        Ok(ConstellationControlMsg::GetTitle(id)) => assert_eq!(id, pipeline.id),
+        _ => panic!("expected a title request"),
+    }
+    assert!(compositor_port.try_recv().is_err());
+}
+
+#[test]
+fn test_unanswered_ping_times_out_once() {
+    let (mut pipeline, script_port, _compositor_port) = new_pipeline();
+
+    pipeline.ping(PingId(0), 1000).unwrap();
+    match script_port.recv() {
+        Ok(ConstellationControlMsg::Ping(id, PingId(0))) => assert_eq!(id, pipeline.id),
+        _ => panic!("expected a ping"),
+    }
+
+    assert!(!pipeline.check_ping_timeout(5999, 5000));
+    assert!(!pipeline.unresponsive);
+    assert!(pipeline.check_ping_timeout(6000, 5000));
+    assert!(pipeline.unresponsive);
+    // The pipeline is only reported as unresponsive once.
+    assert!(!pipeline.check_ping_timeout(7000, 5000));
+}
+
+#[test]
+fn test_no_timeout_without_pending_ping() {
+    let (mut pipeline, _script_port, _compositor_port) = new_pipeline();
+
+    assert!(!pipeline.check_ping_timeout(10000, 5000));
+    assert!(!pipeline.unresponsive);
+}
+
+#[test]
+fn test_document_domain_relaxes_same_origin_check() {
+    let (mut pipeline, _script_port, _compositor_port) = new_pipeline();
+    let (mut other, _other_script_port, _other_compositor_port) = new_pipeline();
+    pipeline.url = Url::parse("http://a.example.com/").unwrap();
+    other.url = Url::parse("http://b.example.com/").unwrap();
+    assert!(!pipeline.is_same_origin_domain(&other));
+
+    // Both documents have to opt in.
+    pipeline.document_domain = Some("example.com".to_owned());
+    assert!(!pipeline.is_same_origin_domain(&other));
+    other.document_domain = Some("example.com".to_owned());
+    assert!(pipeline.is_same_origin_domain(&other));
+
+    // Once one document set it, it no longer matches a same-origin document that did not.
+    other.url = Url::parse("http://a.example.com/other.html").unwrap();
+    other.document_domain = None;
+    assert!(!pipeline.is_same_origin_domain(&other));
+
+    other.url = Url::parse("https://b.example.com/").unwrap();
+    other.document_domain = Some("example.com".to_owned());
+    assert!(!pipeline.is_same_origin_domain(&other));
+}
+
+fn frame_stats(script_ms: f32, dropped: bool) -> FrameStats {
+    FrameStats {
+        raf_callbacks: 1,
+        script_ms: script_ms,
+        layout_ms: 2.0,
+        dropped: dropped,
+    }
+}
+
+#[test]
+fn test_frame_stats_summarize_recent_frames() {
+    let (mut pipeline, _script_port, _compositor_port) = new_pipeline();
+
+    pipeline.add_frame_stats(frame_stats(30.0, true), 2);
+    let summary = pipeline.add_frame_stats(frame_stats(2.0, false), 2);
+    assert_eq!(summary.frames, 2);
+    assert_eq!(summary.dropped_frames, 1);
+    assert_eq!(summary.script_ms, 16.0);
+    assert_eq!(summary.layout_ms, 2.0);
+
+    // The oldest frame falls out of the window.
+    let summary = pipeline.add_frame_stats(frame_stats(4.0, false), 2);
+    assert_eq!(summary.frames, 2);
+    assert_eq!(summary.dropped_frames, 0);
+    assert_eq!(summary.script_ms, 3.0);
+    assert_eq!(summary.raf_callbacks, 1.0);
+}
+
+#[test]
+fn test_frame_hash_is_stable_once_repeated() {
+    let (mut pipeline, _script_port, _compositor_port) = new_pipeline();
+
+    pipeline.set_frame_hash(1);
+    assert!(!pipeline.frame_hash_stable);
+    pipeline.set_frame_hash(1);
+    assert!(pipeline.frame_hash_stable);
+    pipeline.set_frame_hash(2);
+    assert!(!pipeline.frame_hash_stable);
+}
@@ -0,0 +1,39 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use constellation::sanitizer::sanitize_html;
+
+#[test]
+fn test_sanitize_removes_scripts() {
+    let html = "<p onclick=\"steal()\">Hello <b>world</b></p>\
+                <script>alert(\"<b>pwned</b>\")</script>\
+                <a href=\" javascript:steal()\" title=\"link\">click</a>\
+                <iframe src=\"https://example.com/\"><p>fallback</p></iframe>\
+                <!-- comment --><img src=\"cat.png\" onerror=\"steal()\">";
+    assert_eq!(sanitize_html(html),
+               "<p>Hello <b>world</b></p><a title=\"link\">click</a><img src=\"cat.png\">");
+}
+
+#[test]
+fn test_sanitize_keeps_safe_markup() {
+    let html = "<ul class=\"list\"><li><a href=\"https://example.com/?a=1&amp;b=2\">x &lt; y</a></li></ul>";
+    assert_eq!(sanitize_html(html), html);
+}
+
+#[test]
+fn test_sanitize_removes_obfuscated_script_urls() {
+    let html = "<a href=\"java&#9;script:steal()\">tab</a>\
+                <a href=\"\njava\nscript:steal()\">newline</a>\
+                <a href=\"VBScript:steal()\">vbscript</a>\
+                <a href=\"data:text/html,&lt;script&gt;steal()&lt;/script&gt;\">data</a>\
+                <img src=\" data:image/svg+xml,&lt;svg onload=steal()&gt;\">";
+    assert_eq!(sanitize_html(html),
+               "<a>tab</a><a>newline</a><a>vbscript</a><a>data</a><img>");
+}
+
+#[test]
+fn test_sanitize_removes_style_attributes() {
+    let html = "<p style=\"background: url(javascript:steal())\" class=\"note\">Hi</p>";
+    assert_eq!(sanitize_html(html), "<p class=\"note\">Hi</p>");
+}
@@ -0,0 +1,66 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use constellation::web_locks::WebLockManager;
+use ipc_channel::ipc::{self, IpcReceiver};
+use msg::constellation_msg::{PipelineId, PipelineIndex, PipelineNamespaceId};
+use script_traits::{Origin, WebLockGrant, WebLockMode};
+use url::Url;
+
+fn pipeline_id(index: u32) -> PipelineId {
+    PipelineId {
+        namespace_id: PipelineNamespaceId(0),
+        index: PipelineIndex(index),
+    }
+}
+
+fn origin() -> Origin {
+    Origin::new(&Url::parse("https://example.com/").unwrap())
+}
+
+fn acquire(manager: &mut WebLockManager, pipeline_id: PipelineId, mode: WebLockMode) -> IpcReceiver<WebLockGrant> {
+    let (sender, receiver) = ipc::channel().unwrap();
+    manager.acquire(pipeline_id, origin(), "sync".to_owned(), mode, sender);
+    receiver
+}
+
+#[test]
+fn test_release_by_other_pipeline_is_ignored() {
+    let mut manager = WebLockManager::new();
+    let first = acquire(&mut manager, pipeline_id(1), WebLockMode::Exclusive);
+    let grant = first.recv().unwrap();
+    let second = acquire(&mut manager, pipeline_id(2), WebLockMode::Exclusive);
+
+    manager.release(pipeline_id(2), grant.token);
+    assert!(second.try_recv().is_err());
+
+    manager.release(pipeline_id(1), grant.token);
+    assert!(second.try_recv().is_ok());
+}
+
+#[test]
+fn test_exited_pipeline_releases_its_locks() {
+    let mut manager = WebLockManager::new();
+    let first = acquire(&mut manager, pipeline_id(1), WebLockMode::Exclusive);
+    first.recv().unwrap();
+    let second = acquire(&mut manager, pipeline_id(2), WebLockMode::Exclusive);
+    assert!(second.try_recv().is_err());
+
+    manager.release_all(pipeline_id(1));
+    assert!(second.try_recv().is_ok());
+}
+
+#[test]
+fn test_exited_pipeline_leaves_the_queue() {
+    let mut manager = WebLockManager::new();
+    let first = acquire(&mut manager, pipeline_id(1), WebLockMode::Exclusive);
+    let grant = first.recv().unwrap();
+    let _second = acquire(&mut manager, pipeline_id(2), WebLockMode::Exclusive);
+    let third = acquire(&mut manager, pipeline_id(3), WebLockMode::Exclusive);
+
+    // The request of the exited pipeline no longer stands between the holder and the third.
+    manager.release_all(pipeline_id(2));
+    manager.release(pipeline_id(1), grant.token);
+    assert!(third.try_recv().is_ok());
+}
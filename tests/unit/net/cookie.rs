@@ -133,3 +133,21 @@ fn test_sort_order() {
     assert!(CookieStorage::cookie_comparator(&a_prime, &a) == Ordering::Greater);
     assert!(CookieStorage::cookie_comparator(&a, &a) == Ordering::Equal);
 }
+
+#[test]
+fn test_remove_for_host() {
+    let url = &Url::parse("http://example.com/foo").unwrap();
+    let other_url = &Url::parse("http://example.org/").unwrap();
+    let mut storage = CookieStorage::new();
+    for cookie in &["a=1; Path=/foo/bar/", "b=2; Domain=example.com"] {
+        let cookie = cookie_rs::Cookie::parse(cookie).unwrap();
+        storage.push(Cookie::new_wrapped(cookie, url, CookieSource::HTTP).unwrap(), CookieSource::HTTP);
+    }
+    let cookie = cookie_rs::Cookie::parse("c=3").unwrap();
+    storage.push(Cookie::new_wrapped(cookie, other_url, CookieSource::HTTP).unwrap(), CookieSource::HTTP);
+
+    storage.remove_for_host("example.com");
+    assert_eq!(storage.cookies_for_url(&Url::parse("http://example.com/foo/bar/").unwrap(),
+                                       CookieSource::HTTP), None);
+    assert_eq!(storage.cookies_for_url(other_url, CookieSource::HTTP), Some("c=3".to_owned()));
+}
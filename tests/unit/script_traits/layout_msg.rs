@@ -0,0 +1,134 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use euclid::Matrix4D;
+use euclid::point::Point2D;
+use euclid::rect::Rect;
+use euclid::size::Size2D;
+use gfx_traits::{LayerId, LayerType};
+use ipc_channel::ipc;
+use pipeline_id;
+use round_trip;
+use script_traits::ViewTransitionSnapshot;
+use script_traits::{AnimatedProperty, AnimatedValue, AnimationId, AnimationTiming, CapturedLayer};
+use script_traits::{CompositorAnimation, CompositorKeyframe, CursorId, LayoutMsg, ScrollAxis, ScrollTimeline};
+use style_traits::cursor::Cursor;
+
+fn layer_id() -> LayerId {
+    LayerId::new_of_type(LayerType::FragmentBody, 1)
+}
+
+#[test]
+fn test_set_compositor_animations_transform() {
+    let animation = CompositorAnimation {
+        id: AnimationId(7),
+        layer_id: layer_id(),
+        property: AnimatedProperty::Transform,
+        keyframes: vec![
+            CompositorKeyframe {
+                offset: 0.0,
+                value: AnimatedValue::Transform(Matrix4D::identity()),
+            },
+            CompositorKeyframe {
+                offset: 1.0,
+                value: AnimatedValue::Transform(Matrix4D::create_translation(100.0, 0.0, 0.0)),
+            },
+        ],
+        timing: AnimationTiming {
+            start_time: 10.0,
+            delay: 0.5,
+            duration: 2.0,
+            iteration_count: None,
+        },
+    };
+
+    match round_trip(LayoutMsg::SetCompositorAnimations(pipeline_id(), vec![animation.clone()])) {
+        LayoutMsg::SetCompositorAnimations(id, animations) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(animations, vec![animation]);
+        }
+        _ => panic!("expected SetCompositorAnimations"),
+    }
+}
+
+#[test]
+fn test_set_scroll_timeline() {
+    let timeline = ScrollTimeline {
+        axis: ScrollAxis::Vertical,
+        range: (0.0, 500.0),
+        target_animation: AnimationId(7),
+    };
+
+    match round_trip(LayoutMsg::SetScrollTimeline(pipeline_id(), layer_id(), timeline)) {
+        LayoutMsg::SetScrollTimeline(id, layer, received) => {
+            assert_eq!(id, pipeline_id());
+            assert!(layer == layer_id());
+            assert_eq!(received, timeline);
+        }
+        _ => panic!("expected SetScrollTimeline"),
+    }
+}
+
+#[test]
+fn test_set_named_cursors() {
+    let cursors = vec![
+        (CursorId(1), Cursor::Default, Point2D::new(10.0, 20.0)),
+        (CursorId(2), Cursor::Text, Point2D::new(300.5, 42.0)),
+    ];
+
+    for &(cursor_id, cursor, point) in &cursors {
+        match round_trip(LayoutMsg::SetNamedCursor(pipeline_id(), cursor_id, cursor, point)) {
+            LayoutMsg::SetNamedCursor(id, received_id, received_cursor, received_point) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_id, cursor_id);
+                assert_eq!(received_cursor, cursor);
+                assert_eq!(received_point, point);
+            }
+            _ => panic!("expected SetNamedCursor"),
+        }
+    }
+}
+
+#[test]
+fn test_capture_view_transition() {
+    let snapshot = ViewTransitionSnapshot {
+        layers: vec![
+            CapturedLayer {
+                layer_id: LayerId::null(),
+                bounds: Rect::new(Point2D::new(0.0, 0.0), Size2D::new(800.0, 600.0)),
+                pixels: vec![],
+                pixels_size: Size2D::zero(),
+            },
+            CapturedLayer {
+                layer_id: layer_id(),
+                bounds: Rect::new(Point2D::new(10.0, 20.0), Size2D::new(100.0, 50.0)),
+                pixels: vec![0xff, 0x00, 0x00, 0x00, 0xff, 0x00],
+                pixels_size: Size2D::new(2, 1),
+            },
+        ],
+    };
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(LayoutMsg::CaptureViewTransition(pipeline_id(), sender)) {
+        LayoutMsg::CaptureViewTransition(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(snapshot.clone()).unwrap();
+        }
+        _ => panic!("expected CaptureViewTransition"),
+    }
+    assert_eq!(receiver.recv().unwrap(), snapshot);
+}
+
+#[test]
+fn test_scroll_anchor_adjustment() {
+    let delta = Point2D::new(0.0, -120.5);
+    match round_trip(LayoutMsg::ScrollAnchorAdjustment(pipeline_id(), layer_id(), delta)) {
+        LayoutMsg::ScrollAnchorAdjustment(id, layer, received) => {
+            assert_eq!(id, pipeline_id());
+            assert!(layer == layer_id());
+            assert_eq!(received, delta);
+        }
+        _ => panic!("expected ScrollAnchorAdjustment"),
+    }
+}
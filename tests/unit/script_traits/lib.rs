@@ -0,0 +1,40 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+extern crate cssparser;
+extern crate euclid;
+extern crate gfx_traits;
+extern crate ipc_channel;
+extern crate msg;
+extern crate script_traits;
+extern crate serde;
+extern crate style_traits;
+extern crate url;
+
+#[cfg(test)] mod layout_msg;
+#[cfg(test)] mod script_msg;
+
+#[cfg(test)]
+use ipc_channel::ipc;
+#[cfg(test)]
+use msg::constellation_msg::{PipelineId, PipelineIndex, PipelineNamespaceId};
+#[cfg(test)]
+use serde::{Deserialize, Serialize};
+
+/// Sends a value across an IPC channel and returns what arrives on the other end.
+#[cfg(test)]
+fn round_trip<T: Deserialize + Serialize>(value: T) -> T {
+    let (sender, receiver) = ipc::channel().unwrap();
+    sender.send(value).unwrap();
+    receiver.recv().unwrap()
+}
+
+/// The pipeline that the messages under test claim to come from.
+#[cfg(test)]
+fn pipeline_id() -> PipelineId {
+    PipelineId {
+        namespace_id: PipelineNamespaceId(1),
+        index: PipelineIndex(1),
+    }
+}
@@ -0,0 +1,1451 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use cssparser::RGBA;
+use euclid::length::Length;
+use euclid::point::Point2D;
+use euclid::rect::Rect;
+use euclid::size::Size2D;
+use gfx_traits::LayerId;
+use ipc_channel::ipc;
+use msg::constellation_msg::{LoadData, PipelineId, PipelineIndex, PipelineNamespaceId};
+use pipeline_id;
+use round_trip;
+use script_traits::{AutoplayContext, AuxiliaryContextInfo, BlockReason, CLEAR_CACHE, CLEAR_COOKIES};
+use script_traits::{CLEAR_SERVICE_WORKERS, CLEAR_STORAGE, ClearDataTypes, ColorGamut, ConstellationMsg, CoverageReport};
+use script_traits::{DeprecationInfo, DisplayCapabilities, DisplayCaptureConstraints, DisplaySurface, DragData};
+use script_traits::{ElementId, FrameInfo, FrameStats, FullscreenOptions, HistoryState, IceCandidate, IceConfig};
+use script_traits::{IceGatheringId, IceServer, IdbConnectionId, ImeState, InputType, InterventionKind};
+use script_traits::{MediaConstraints, MediaDeviceInfo, MediaDeviceKind, MediaElementId, MediaError, MediaStreamInfo};
+use script_traits::{MediaTrackConstraints, MediaTrackInfo, MediaTrackKind, MediaType, NavigationCapability};
+use script_traits::{NavigationDisposition, NavigationReason, NavigationType, NavigationUiPreference, Origin};
+use script_traits::{PageLifecycleState, PingId, PipelineExitReason, PointerAccuracy, PointerCapabilities, ReportBody};
+use script_traits::{ScreenInfo, ScriptMsg, ScrollRestorationMode, ScrollbarPreferences, SelectionInfo, SourceLocation};
+use script_traits::{SpatialDirection, SpeechRecognitionConfig, SpeechRecognitionId, SpeechRecognitionResult};
+use script_traits::{StorageBreakdown, TextFragmentDirective, Tooltip, VirtualKeyboardGeometry, WebLockGrant};
+use script_traits::{WebLockMode, WebLockToken, WindowFeatures, WindowPlacement};
+use url::Url;
+
+/// A pipeline nested inside the one returned by `pipeline_id()`.
+fn subframe_pipeline_id() -> PipelineId {
+    PipelineId {
+        namespace_id: PipelineNamespaceId(1),
+        index: PipelineIndex(2),
+    }
+}
+
+#[test]
+fn test_set_navigation_preload() {
+    let msg = ScriptMsg::SetNavigationPreload(pipeline_id(), true, Some("custom".to_owned()));
+    match round_trip(msg) {
+        ScriptMsg::SetNavigationPreload(id, enabled, header_value) => {
+            assert_eq!(id, pipeline_id());
+            assert!(enabled);
+            assert_eq!(header_value, Some("custom".to_owned()));
+        }
+        _ => panic!("expected SetNavigationPreload"),
+    }
+}
+
+#[test]
+fn test_origin_round_trip() {
+    let origin = Origin::new(&Url::parse("https://example.com:8080/index.html").unwrap());
+    assert_eq!(round_trip(origin.clone()), origin);
+    assert_eq!(origin.url(), Some(Url::parse("https://example.com:8080/").unwrap()));
+
+    let opaque = Origin::new(&Url::parse("data:text/html,hello").unwrap());
+    assert!(round_trip(opaque.clone()) != opaque);
+    assert_eq!(opaque.url(), None);
+}
+
+#[test]
+fn test_get_storage_breakdown() {
+    let (sender, receiver) = ipc::channel().unwrap();
+    let breakdown = StorageBreakdown {
+        cache_bytes: 1,
+        indexeddb_bytes: 2,
+        localstorage_bytes: 3,
+        serviceworker_bytes: 4,
+    };
+
+    match round_trip(ScriptMsg::GetStorageBreakdown(pipeline_id(), sender)) {
+        ScriptMsg::GetStorageBreakdown(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(breakdown).unwrap();
+        }
+        _ => panic!("expected GetStorageBreakdown"),
+    }
+    assert_eq!(receiver.recv().unwrap(), breakdown);
+}
+
+#[test]
+fn test_clear_site_data() {
+    let combinations = [
+        CLEAR_COOKIES,
+        CLEAR_STORAGE | CLEAR_CACHE,
+        CLEAR_COOKIES | CLEAR_SERVICE_WORKERS,
+        ClearDataTypes::all(),
+    ];
+    for &data_types in &combinations {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::ClearSiteData(pipeline_id(), data_types, sender)) {
+            ScriptMsg::ClearSiteData(id, received_types, sender) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_types, data_types);
+                sender.send(()).unwrap();
+            }
+            _ => panic!("expected ClearSiteData"),
+        }
+        receiver.recv().unwrap();
+    }
+}
+
+#[test]
+fn test_set_document_encoding() {
+    match round_trip(ScriptMsg::SetDocumentEncoding(pipeline_id(), "windows-1252".to_owned())) {
+        ScriptMsg::SetDocumentEncoding(id, encoding) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(encoding, "windows-1252");
+        }
+        _ => panic!("expected SetDocumentEncoding"),
+    }
+}
+
+#[test]
+fn test_set_content_type() {
+    match round_trip(ScriptMsg::SetContentType(pipeline_id(), "application/xhtml+xml".to_owned())) {
+        ScriptMsg::SetContentType(id, content_type) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(content_type, "application/xhtml+xml");
+        }
+        _ => panic!("expected SetContentType"),
+    }
+}
+
+#[test]
+fn test_new_favicon_keeps_originating_pipeline() {
+    // The constellation decides whether a favicon reaches the chrome by comparing this id
+    // against the root frame's pipeline, so a subframe's id must survive the trip intact.
+    let url = Url::parse("http://example.com/favicon.ico").unwrap();
+    for &pipeline in &[pipeline_id(), subframe_pipeline_id()] {
+        match round_trip(ScriptMsg::NewFavicon(pipeline, url.clone())) {
+            ScriptMsg::NewFavicon(id, received) => {
+                assert_eq!(id, pipeline);
+                assert_eq!(received, url);
+            }
+            _ => panic!("expected NewFavicon"),
+        }
+    }
+}
+
+#[test]
+fn test_set_theme_color_keeps_originating_pipeline() {
+    let color = RGBA { red: 1.0, green: 0.5, blue: 0.0, alpha: 1.0 };
+    for &pipeline in &[pipeline_id(), subframe_pipeline_id()] {
+        match round_trip(ScriptMsg::SetThemeColor(pipeline, color)) {
+            ScriptMsg::SetThemeColor(id, received) => {
+                assert_eq!(id, pipeline);
+                assert_eq!(received, color);
+            }
+            _ => panic!("expected SetThemeColor"),
+        }
+    }
+}
+
+#[test]
+fn test_selection_changed() {
+    let selection = SelectionInfo {
+        text: "spans two\nlines".to_owned(),
+        rects: vec![
+            Rect::new(Point2D::new(40.0, 10.0), Size2D::new(200.0, 18.0)),
+            Rect::new(Point2D::new(8.0, 28.0), Size2D::new(35.5, 18.0)),
+        ],
+    };
+
+    match round_trip(ScriptMsg::SelectionChanged(pipeline_id(), Some(selection.clone()))) {
+        ScriptMsg::SelectionChanged(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, Some(selection));
+        }
+        _ => panic!("expected SelectionChanged"),
+    }
+
+    match round_trip(ScriptMsg::SelectionChanged(pipeline_id(), None)) {
+        ScriptMsg::SelectionChanged(_, received) => assert_eq!(received, None),
+        _ => panic!("expected SelectionChanged"),
+    }
+}
+
+#[test]
+fn test_link_hover() {
+    let url = Url::parse("https://example.com/a/long/path?query=1").unwrap();
+    match round_trip(ScriptMsg::LinkHover(pipeline_id(), Some(url.clone()))) {
+        ScriptMsg::LinkHover(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, Some(url));
+        }
+        _ => panic!("expected LinkHover"),
+    }
+
+    match round_trip(ScriptMsg::LinkHover(pipeline_id(), None)) {
+        ScriptMsg::LinkHover(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, None);
+        }
+        _ => panic!("expected LinkHover"),
+    }
+}
+
+#[test]
+fn test_set_tooltip() {
+    let tooltips = vec![
+        Tooltip {
+            text: "Delete".to_owned(),
+            description: None,
+        },
+        Tooltip {
+            text: "Delete".to_owned(),
+            description: Some("Removes the message permanently".to_owned()),
+        },
+    ];
+
+    for tooltip in tooltips {
+        match round_trip(ScriptMsg::SetTooltip(pipeline_id(), Some(tooltip.clone()))) {
+            ScriptMsg::SetTooltip(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, Some(tooltip));
+            }
+            _ => panic!("expected SetTooltip"),
+        }
+    }
+}
+
+#[test]
+fn test_request_picture_in_picture() {
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::RequestPictureInPicture(pipeline_id(), MediaElementId(2), sender)) {
+        ScriptMsg::RequestPictureInPicture(id, media_id, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(media_id, MediaElementId(2));
+            sender.send(Err("not allowed".to_owned())).unwrap();
+        }
+        _ => panic!("expected RequestPictureInPicture"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Err("not allowed".to_owned()));
+}
+
+#[test]
+fn test_exit_picture_in_picture() {
+    match round_trip(ScriptMsg::ExitPictureInPicture(pipeline_id(), MediaElementId(2))) {
+        ScriptMsg::ExitPictureInPicture(id, media_id) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(media_id, MediaElementId(2));
+        }
+        _ => panic!("expected ExitPictureInPicture"),
+    }
+}
+
+#[test]
+fn test_set_fullscreen_state() {
+    assert_eq!(FullscreenOptions::default().navigation_ui, NavigationUiPreference::Auto);
+
+    for &navigation_ui in &[NavigationUiPreference::Auto, NavigationUiPreference::Show, NavigationUiPreference::Hide] {
+        let options = FullscreenOptions { navigation_ui: navigation_ui };
+        match round_trip(ScriptMsg::SetFullscreenState(pipeline_id(), Some(options))) {
+            ScriptMsg::SetFullscreenState(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, Some(options));
+            }
+            _ => panic!("expected SetFullscreenState"),
+        }
+    }
+
+    match round_trip(ScriptMsg::SetFullscreenState(pipeline_id(), None)) {
+        ScriptMsg::SetFullscreenState(_, received) => assert_eq!(received, None),
+        _ => panic!("expected SetFullscreenState"),
+    }
+}
+
+#[test]
+fn test_check_autoplay_allowed() {
+    let muted = AutoplayContext { muted: true, has_user_gesture: false };
+    let audible = AutoplayContext { muted: false, has_user_gesture: false };
+
+    // A policy that lets muted media play on its own, but not media with sound.
+    for &(context, allowed) in &[(muted, true), (audible, false)] {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::CheckAutoplayAllowed(pipeline_id(), context, sender)) {
+            ScriptMsg::CheckAutoplayAllowed(id, received, sender) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, context);
+                sender.send(received.muted || received.has_user_gesture).unwrap();
+            }
+            _ => panic!("expected CheckAutoplayAllowed"),
+        }
+        assert_eq!(receiver.recv().unwrap(), allowed);
+    }
+}
+
+#[test]
+fn test_set_app_badge() {
+    for &badge in &[Some(12), Some(0), None] {
+        match round_trip(ScriptMsg::SetAppBadge(pipeline_id(), badge)) {
+            ScriptMsg::SetAppBadge(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, badge);
+            }
+            _ => panic!("expected SetAppBadge"),
+        }
+    }
+}
+
+#[test]
+fn test_subscribe_clipboard_changes() {
+    for &subscribed in &[true, false] {
+        match round_trip(ScriptMsg::SubscribeClipboardChanges(pipeline_id(), subscribed)) {
+            ScriptMsg::SubscribeClipboardChanges(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, subscribed);
+            }
+            _ => panic!("expected SubscribeClipboardChanges"),
+        }
+    }
+}
+
+#[test]
+fn test_get_keyboard_layout_map() {
+    let layout_map = vec![
+        ("KeyQ".to_owned(), "a".to_owned()),
+        ("KeyW".to_owned(), "z".to_owned()),
+        ("Semicolon".to_owned(), "m".to_owned()),
+    ];
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetKeyboardLayoutMap(sender)) {
+        ScriptMsg::GetKeyboardLayoutMap(sender) => sender.send(layout_map.clone()).unwrap(),
+        _ => panic!("expected GetKeyboardLayoutMap"),
+    }
+    assert_eq!(receiver.recv().unwrap(), layout_map);
+}
+
+#[test]
+fn test_lock_keyboard() {
+    let codes = vec!["Escape".to_owned(), "Tab".to_owned()];
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::LockKeyboard(pipeline_id(), codes.clone(), sender)) {
+        ScriptMsg::LockKeyboard(id, received, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, codes);
+            sender.send(Ok(())).unwrap();
+        }
+        _ => panic!("expected LockKeyboard"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Ok(()));
+}
+
+#[test]
+fn test_unlock_keyboard() {
+    match round_trip(ScriptMsg::UnlockKeyboard(pipeline_id())) {
+        ScriptMsg::UnlockKeyboard(id) => assert_eq!(id, pipeline_id()),
+        _ => panic!("expected UnlockKeyboard"),
+    }
+}
+
+#[test]
+fn test_set_document_domain() {
+    for domain in vec![Some("example.com".to_owned()), None] {
+        match round_trip(ScriptMsg::SetDocumentDomain(pipeline_id(), domain.clone())) {
+            ScriptMsg::SetDocumentDomain(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, domain);
+            }
+            _ => panic!("expected SetDocumentDomain"),
+        }
+    }
+}
+
+#[test]
+fn test_request_storage_access() {
+    for &granted in &[true, false] {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::RequestStorageAccess(subframe_pipeline_id(), sender)) {
+            ScriptMsg::RequestStorageAccess(id, sender) => {
+                assert_eq!(id, subframe_pipeline_id());
+                sender.send(granted).unwrap();
+            }
+            _ => panic!("expected RequestStorageAccess"),
+        }
+        assert_eq!(receiver.recv().unwrap(), granted);
+    }
+}
+
+#[test]
+fn test_sever_opener() {
+    match round_trip(ScriptMsg::SeverOpener(pipeline_id())) {
+        ScriptMsg::SeverOpener(id) => assert_eq!(id, pipeline_id()),
+        _ => panic!("expected SeverOpener"),
+    }
+}
+
+#[test]
+fn test_set_media_type() {
+    for &media_type in &[MediaType::Print, MediaType::Screen] {
+        match round_trip(ScriptMsg::SetMediaType(pipeline_id(), media_type)) {
+            ScriptMsg::SetMediaType(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, media_type);
+            }
+            _ => panic!("expected SetMediaType"),
+        }
+    }
+}
+
+#[test]
+fn test_exit() {
+    match round_trip(ScriptMsg::Exit(None)) {
+        ScriptMsg::Exit(sender) => assert!(sender.is_none()),
+        _ => panic!("expected Exit"),
+    }
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::Exit(Some(sender))) {
+        ScriptMsg::Exit(Some(sender)) => sender.send(()).unwrap(),
+        _ => panic!("expected Exit with an acknowledgement sender"),
+    }
+    assert_eq!(receiver.recv().unwrap(), ());
+}
+
+#[test]
+fn test_report_frame_hash() {
+    match round_trip(ScriptMsg::ReportFrameHash(pipeline_id(), 0xdead_beef_cafe_f00d)) {
+        ScriptMsg::ReportFrameHash(id, hash) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(hash, 0xdead_beef_cafe_f00d);
+        }
+        _ => panic!("expected ReportFrameHash"),
+    }
+}
+
+#[test]
+fn test_set_ime_state() {
+    let states = vec![
+        ImeState {
+            enabled: true,
+            input_type: InputType::Text,
+            caret_rect: Some(Rect::new(Point2D::new(40.0, 12.0), Size2D::new(1.0, 16.0))),
+        },
+        ImeState {
+            enabled: false,
+            input_type: InputType::None,
+            caret_rect: None,
+        },
+    ];
+
+    for state in states {
+        match round_trip(ScriptMsg::SetImeState(pipeline_id(), state)) {
+            ScriptMsg::SetImeState(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, state);
+            }
+            _ => panic!("expected SetImeState"),
+        }
+    }
+}
+
+#[test]
+fn test_show_virtual_keyboard() {
+    let geometry = VirtualKeyboardGeometry {
+        bounding_rect: Rect::new(Point2D::new(0.0, 400.0), Size2D::new(360.0, 240.0)),
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::ShowVirtualKeyboard(pipeline_id(), sender)) {
+        ScriptMsg::ShowVirtualKeyboard(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(geometry).unwrap();
+        }
+        _ => panic!("expected ShowVirtualKeyboard"),
+    }
+    assert_eq!(receiver.recv().unwrap(), geometry);
+}
+
+#[test]
+fn test_hide_virtual_keyboard() {
+    match round_trip(ScriptMsg::HideVirtualKeyboard(pipeline_id())) {
+        ScriptMsg::HideVirtualKeyboard(id) => assert_eq!(id, pipeline_id()),
+        _ => panic!("expected HideVirtualKeyboard"),
+    }
+}
+
+#[test]
+fn test_ensure_focus_visible() {
+    let element_id = ElementId("4f8e5a1c2b3d4e5f8a9b0c1d2e3f4a5b".to_owned());
+    match round_trip(ScriptMsg::EnsureFocusVisible(pipeline_id(), element_id.clone())) {
+        ScriptMsg::EnsureFocusVisible(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, element_id);
+        }
+        _ => panic!("expected EnsureFocusVisible"),
+    }
+}
+
+#[test]
+fn test_start_speech_recognition() {
+    let config = SpeechRecognitionConfig {
+        lang: "en-GB".to_owned(),
+        continuous: true,
+        interim_results: false,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::StartSpeechRecognition(pipeline_id(), config.clone(), sender)) {
+        ScriptMsg::StartSpeechRecognition(id, received, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, config);
+            sender.send(SpeechRecognitionId(3)).unwrap();
+        }
+        _ => panic!("expected StartSpeechRecognition"),
+    }
+    assert_eq!(receiver.recv().unwrap(), SpeechRecognitionId(3));
+}
+
+#[test]
+fn test_stop_speech_recognition() {
+    match round_trip(ScriptMsg::StopSpeechRecognition(SpeechRecognitionId(3))) {
+        ScriptMsg::StopSpeechRecognition(id) => assert_eq!(id, SpeechRecognitionId(3)),
+        _ => panic!("expected StopSpeechRecognition"),
+    }
+}
+
+#[test]
+fn test_speech_recognition_result() {
+    let results = vec![
+        SpeechRecognitionResult {
+            transcript: "hello wor".to_owned(),
+            confidence: 0.4,
+            is_final: false,
+        },
+        SpeechRecognitionResult {
+            transcript: "hello world".to_owned(),
+            confidence: 0.92,
+            is_final: true,
+        },
+    ];
+    assert_eq!(round_trip(results.clone()), results);
+}
+
+#[test]
+fn test_request_user_media() {
+    let constraints = MediaConstraints {
+        audio: Some(MediaTrackConstraints::default()),
+        video: None,
+    };
+    let stream = MediaStreamInfo {
+        tracks: vec![
+            MediaTrackInfo {
+                id: "track-1".to_owned(),
+                kind: MediaTrackKind::Audio,
+                label: "Built-in Microphone".to_owned(),
+            },
+        ],
+    };
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::RequestUserMedia(pipeline_id(), constraints.clone(), sender)) {
+        ScriptMsg::RequestUserMedia(id, received, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, constraints);
+            sender.send(Ok(stream.clone())).unwrap();
+        }
+        _ => panic!("expected RequestUserMedia"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Ok(stream));
+}
+
+#[test]
+fn test_request_user_media_not_allowed() {
+    let constraints = MediaConstraints {
+        audio: None,
+        video: Some(MediaTrackConstraints {
+            device_id: Some("camera-1".to_owned()),
+        }),
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::RequestUserMedia(pipeline_id(), constraints, sender)) {
+        ScriptMsg::RequestUserMedia(_, _, sender) => sender.send(Err(MediaError::NotAllowed)).unwrap(),
+        _ => panic!("expected RequestUserMedia"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Err(MediaError::NotAllowed));
+}
+
+#[test]
+fn test_enumerate_media_devices() {
+    let devices = vec![
+        MediaDeviceInfo {
+            device_id: "microphone-1".to_owned(),
+            kind: MediaDeviceKind::AudioInput,
+            label: "Built-in Microphone".to_owned(),
+            group_id: "built-in".to_owned(),
+        },
+        MediaDeviceInfo {
+            device_id: "camera-1".to_owned(),
+            kind: MediaDeviceKind::VideoInput,
+            label: String::new(),
+            group_id: "webcam".to_owned(),
+        },
+    ];
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::EnumerateMediaDevices(pipeline_id(), sender)) {
+        ScriptMsg::EnumerateMediaDevices(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(devices.clone()).unwrap();
+        }
+        _ => panic!("expected EnumerateMediaDevices"),
+    }
+    assert_eq!(receiver.recv().unwrap(), devices);
+}
+
+#[test]
+fn test_request_display_capture() {
+    let constraints = DisplayCaptureConstraints {
+        audio: false,
+        surface: Some(DisplaySurface::Monitor),
+    };
+    let stream = MediaStreamInfo {
+        tracks: vec![
+            MediaTrackInfo {
+                id: "screen-1".to_owned(),
+                kind: MediaTrackKind::Video,
+                label: "Screen 1".to_owned(),
+            },
+        ],
+    };
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::RequestDisplayCapture(pipeline_id(), constraints, sender)) {
+        ScriptMsg::RequestDisplayCapture(id, received, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, constraints);
+            sender.send(Ok(stream.clone())).unwrap();
+        }
+        _ => panic!("expected RequestDisplayCapture"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Ok(stream));
+}
+
+#[test]
+fn test_request_display_capture_cancelled() {
+    let constraints = DisplayCaptureConstraints {
+        audio: true,
+        surface: None,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::RequestDisplayCapture(pipeline_id(), constraints, sender)) {
+        ScriptMsg::RequestDisplayCapture(_, _, sender) => sender.send(Err(MediaError::NotAllowed)).unwrap(),
+        _ => panic!("expected RequestDisplayCapture"),
+    }
+    assert_eq!(receiver.recv().unwrap(), Err(MediaError::NotAllowed));
+}
+
+#[test]
+fn test_gather_ice_candidates() {
+    let config = IceConfig {
+        servers: vec![
+            IceServer {
+                urls: vec!["stun:stun.example.com:3478".to_owned()],
+                username: None,
+                credential: None,
+            },
+            IceServer {
+                urls: vec!["turn:turn.example.com:3478?transport=udp".to_owned()],
+                username: Some("user".to_owned()),
+                credential: Some("secret".to_owned()),
+            },
+        ],
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GatherIceCandidates(pipeline_id(), config.clone(), sender)) {
+        ScriptMsg::GatherIceCandidates(id, received, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, config);
+            sender.send(IceGatheringId(1)).unwrap();
+        }
+        _ => panic!("expected GatherIceCandidates"),
+    }
+    assert_eq!(receiver.recv().unwrap(), IceGatheringId(1));
+}
+
+#[test]
+fn test_ice_candidate() {
+    let candidates = vec![
+        IceCandidate {
+            candidate: "candidate:1 1 udp 2122260223 192.0.2.10 54400 typ host".to_owned(),
+            sdp_mid: Some("0".to_owned()),
+            sdp_mline_index: Some(0),
+        },
+        IceCandidate {
+            candidate: "candidate:2 1 udp 1686052607 198.51.100.7 54400 typ srflx".to_owned(),
+            sdp_mid: None,
+            sdp_mline_index: None,
+        },
+    ];
+    assert_eq!(round_trip(candidates.clone()), candidates);
+}
+
+#[test]
+fn test_get_max_touch_points() {
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetMaxTouchPoints(sender)) {
+        ScriptMsg::GetMaxTouchPoints(sender) => sender.send(10).unwrap(),
+        _ => panic!("expected GetMaxTouchPoints"),
+    }
+    assert_eq!(receiver.recv().unwrap(), 10);
+}
+
+#[test]
+fn test_get_pointer_capabilities() {
+    let capabilities = PointerCapabilities {
+        pointer: PointerAccuracy::Coarse,
+        hover: false,
+        any_pointer: vec![PointerAccuracy::Coarse, PointerAccuracy::Fine],
+        any_hover: true,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetPointerCapabilities(sender)) {
+        ScriptMsg::GetPointerCapabilities(sender) => sender.send(capabilities.clone()).unwrap(),
+        _ => panic!("expected GetPointerCapabilities"),
+    }
+    assert_eq!(receiver.recv().unwrap(), capabilities);
+}
+
+#[test]
+fn test_get_display_capabilities() {
+    let capabilities = DisplayCapabilities {
+        color_gamut: ColorGamut::P3,
+        high_dynamic_range: true,
+        bits_per_channel: 10,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetDisplayCapabilities(pipeline_id(), sender)) {
+        ScriptMsg::GetDisplayCapabilities(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(capabilities).unwrap();
+        }
+        _ => panic!("expected GetDisplayCapabilities"),
+    }
+    assert_eq!(receiver.recv().unwrap(), capabilities);
+}
+
+#[test]
+fn test_get_display_refresh_rate() {
+    for &rate in &[60.0, 143.856, 0.0] {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::GetDisplayRefreshRate(pipeline_id(), sender)) {
+            ScriptMsg::GetDisplayRefreshRate(id, sender) => {
+                assert_eq!(id, pipeline_id());
+                sender.send(rate).unwrap();
+            }
+            _ => panic!("expected GetDisplayRefreshRate"),
+        }
+        assert_eq!(receiver.recv().unwrap(), rate);
+    }
+}
+
+#[test]
+fn test_get_screens() {
+    let screens = vec![
+        ScreenInfo {
+            bounds: Rect::new(Point2D::new(0, 0), Size2D::new(2560, 1440)),
+            available_bounds: Rect::new(Point2D::new(0, 0), Size2D::new(2560, 1400)),
+            device_pixel_ratio: 2.0,
+            is_primary: true,
+            label: "Built-in Retina Display".to_owned(),
+        },
+        ScreenInfo {
+            bounds: Rect::new(Point2D::new(-1920, 0), Size2D::new(1920, 1080)),
+            available_bounds: Rect::new(Point2D::new(-1920, 0), Size2D::new(1920, 1080)),
+            device_pixel_ratio: 1.0,
+            is_primary: false,
+            label: "DELL U2412M".to_owned(),
+        },
+    ];
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetScreens(pipeline_id(), sender)) {
+        ScriptMsg::GetScreens(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(screens.clone()).unwrap();
+        }
+        _ => panic!("expected GetScreens"),
+    }
+    assert_eq!(receiver.recv().unwrap(), screens);
+}
+
+#[test]
+fn test_request_window_placement() {
+    let placements = vec![
+        WindowPlacement {
+            bounds: Rect::new(Point2D::new(-1920, 0), Size2D::new(1920, 1080)),
+            fullscreen: true,
+            screen_label: Some("DELL U2412M".to_owned()),
+        },
+        WindowPlacement {
+            bounds: Rect::new(Point2D::new(100, 100), Size2D::new(800, 600)),
+            fullscreen: false,
+            screen_label: None,
+        },
+    ];
+    for placement in placements {
+        match round_trip(ScriptMsg::RequestWindowPlacement(pipeline_id(), placement.clone())) {
+            ScriptMsg::RequestWindowPlacement(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, placement);
+            }
+            _ => panic!("expected RequestWindowPlacement"),
+        }
+    }
+}
+
+#[test]
+fn test_set_initial_focus() {
+    for element_id in vec![Some(ElementId("5f0b2c".to_owned())), None] {
+        match round_trip(ScriptMsg::SetInitialFocus(pipeline_id(), element_id.clone())) {
+            ScriptMsg::SetInitialFocus(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, element_id);
+            }
+            _ => panic!("expected SetInitialFocus"),
+        }
+    }
+}
+
+#[test]
+fn test_set_access_keys() {
+    let access_keys = vec![
+        ("s".to_owned(), ElementId("3a7e91".to_owned())),
+        ("ß".to_owned(), ElementId("c04d12".to_owned())),
+    ];
+    match round_trip(ScriptMsg::SetAccessKeys(pipeline_id(), access_keys.clone())) {
+        ScriptMsg::SetAccessKeys(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, access_keys);
+        }
+        _ => panic!("expected SetAccessKeys"),
+    }
+}
+
+#[test]
+fn test_start_drag() {
+    let items = vec![("text/uri-list".to_owned(), "https://example.com/".to_owned())];
+    let images = vec![
+        Some((vec![0xff; 2 * 2 * 4], Size2D::new(2, 2), Point2D::new(1, 1))),
+        None,
+    ];
+    for drag_image in images {
+        let data = DragData {
+            items: items.clone(),
+            drag_image: drag_image,
+        };
+        match round_trip(ScriptMsg::StartDrag(pipeline_id(), data.clone())) {
+            ScriptMsg::StartDrag(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, data);
+            }
+            _ => panic!("expected StartDrag"),
+        }
+    }
+}
+
+#[test]
+fn test_scroll_to_text_fragment() {
+    let directives = vec![
+        TextFragmentDirective {
+            prefix: None,
+            start: "an example".to_owned(),
+            end: None,
+            suffix: None,
+        },
+        TextFragmentDirective {
+            prefix: Some("this is".to_owned()),
+            start: "an".to_owned(),
+            end: Some("text".to_owned()),
+            suffix: Some("fragment".to_owned()),
+        },
+    ];
+    for directive in directives {
+        match round_trip(ScriptMsg::ScrollToTextFragment(pipeline_id(), directive.clone())) {
+            ScriptMsg::ScrollToTextFragment(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, directive);
+            }
+            _ => panic!("expected ScrollToTextFragment"),
+        }
+    }
+}
+
+#[test]
+fn test_script_timeout() {
+    match round_trip(ScriptMsg::ScriptTimeout(pipeline_id(), Length::new(10_000))) {
+        ScriptMsg::ScriptTimeout(id, elapsed) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(elapsed.get(), 10_000);
+        }
+        _ => panic!("expected ScriptTimeout"),
+    }
+}
+
+#[test]
+fn test_kill_pipeline() {
+    match round_trip(ConstellationMsg::KillPipeline(subframe_pipeline_id())) {
+        ConstellationMsg::KillPipeline(id) => assert_eq!(id, subframe_pipeline_id()),
+        _ => panic!("expected KillPipeline"),
+    }
+    match round_trip(ScriptMsg::PipelineExited(subframe_pipeline_id(), PipelineExitReason::Killed)) {
+        ScriptMsg::PipelineExited(id, reason) => {
+            assert_eq!(id, subframe_pipeline_id());
+            assert_eq!(reason, PipelineExitReason::Killed);
+        }
+        _ => panic!("expected PipelineExited"),
+    }
+}
+
+#[test]
+fn test_pong() {
+    match round_trip(ScriptMsg::Pong(pipeline_id(), PingId(u64::max_value()))) {
+        ScriptMsg::Pong(id, ping_id) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(ping_id, PingId(u64::max_value()));
+        }
+        _ => panic!("expected Pong"),
+    }
+}
+
+#[test]
+fn test_document_write_after_load() {
+    match round_trip(ScriptMsg::DocumentWriteAfterLoad(subframe_pipeline_id())) {
+        ScriptMsg::DocumentWriteAfterLoad(id) => assert_eq!(id, subframe_pipeline_id()),
+        _ => panic!("expected DocumentWriteAfterLoad"),
+    }
+}
+
+#[test]
+fn test_report_frame_info() {
+    let top_level_url = Url::parse("https://example.com/index.html").unwrap();
+    let top_level = FrameInfo {
+        url: top_level_url.clone(),
+        origin: Origin::new(&top_level_url),
+        name: None,
+        parent: None,
+        is_cross_origin: false,
+    };
+    let nested_url = Url::parse("https://ads.example.net:8443/frame.html").unwrap();
+    let nested = FrameInfo {
+        url: nested_url.clone(),
+        origin: Origin::new(&nested_url),
+        name: Some("ad".to_owned()),
+        parent: Some(pipeline_id()),
+        is_cross_origin: true,
+    };
+
+    for &(id, ref frame_info) in &[(pipeline_id(), top_level), (subframe_pipeline_id(), nested)] {
+        match round_trip(ScriptMsg::ReportFrameInfo(id, frame_info.clone())) {
+            ScriptMsg::ReportFrameInfo(received_id, received) => {
+                assert_eq!(received_id, id);
+                assert_eq!(received, *frame_info);
+            }
+            _ => panic!("expected ReportFrameInfo"),
+        }
+    }
+}
+
+#[test]
+fn test_report_code_coverage() {
+    let report = CoverageReport {
+        url: Url::parse("https://example.com/app.js").unwrap(),
+        total_bytes: 4096,
+        used_ranges: vec![(0, 512), (1024, 1536)],
+    };
+    match round_trip(ScriptMsg::ReportCodeCoverage(pipeline_id(), report.clone())) {
+        ScriptMsg::ReportCodeCoverage(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, report);
+        }
+        _ => panic!("expected ReportCodeCoverage"),
+    }
+}
+
+#[test]
+fn test_report_frame_stats() {
+    let clean = FrameStats {
+        raf_callbacks: 2,
+        script_ms: 1.5,
+        layout_ms: 3.25,
+        dropped: false,
+    };
+    let dropped = FrameStats {
+        raf_callbacks: 1,
+        script_ms: 24.0,
+        layout_ms: 8.5,
+        dropped: true,
+    };
+
+    match round_trip(ScriptMsg::ReportFrameStats(pipeline_id(), vec![clean, dropped])) {
+        ScriptMsg::ReportFrameStats(id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received, vec![clean, dropped]);
+        }
+        _ => panic!("expected ReportFrameStats"),
+    }
+}
+
+#[test]
+fn test_resolve_link_for_context_menu() {
+    let link = Url::parse("https://example.com/docs/page.html#section").unwrap();
+    for answer in vec![Some(link), None] {
+        let (sender, receiver) = ipc::channel().unwrap();
+        let element_id = ElementId("link-or-not".to_owned());
+        match round_trip(ScriptMsg::ResolveLinkForContextMenu(pipeline_id(), element_id.clone(), sender)) {
+            ScriptMsg::ResolveLinkForContextMenu(id, received_id, sender) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_id, element_id);
+                sender.send(answer.clone()).unwrap();
+            }
+            _ => panic!("expected ResolveLinkForContextMenu"),
+        }
+        assert_eq!(receiver.recv().unwrap(), answer);
+    }
+}
+
+#[test]
+fn test_spatial_navigate() {
+    for &direction in &[SpatialDirection::Up, SpatialDirection::Down, SpatialDirection::Left, SpatialDirection::Right] {
+        match round_trip(ScriptMsg::SpatialNavigate(subframe_pipeline_id(), direction)) {
+            ScriptMsg::SpatialNavigate(id, received) => {
+                assert_eq!(id, subframe_pipeline_id());
+                assert_eq!(received, direction);
+            }
+            _ => panic!("expected SpatialNavigate"),
+        }
+    }
+}
+
+#[test]
+fn test_get_scrollbar_preferences() {
+    let preferences = ScrollbarPreferences {
+        overlay: false,
+        width: 15.0,
+        auto_hide: false,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetScrollbarPreferences(sender)) {
+        ScriptMsg::GetScrollbarPreferences(sender) => sender.send(preferences).unwrap(),
+        _ => panic!("expected GetScrollbarPreferences"),
+    }
+    assert_eq!(receiver.recv().unwrap(), preferences);
+}
+
+#[test]
+fn test_notify_user_activation() {
+    match round_trip(ScriptMsg::NotifyUserActivation(subframe_pipeline_id())) {
+        ScriptMsg::NotifyUserActivation(id) => assert_eq!(id, subframe_pipeline_id()),
+        _ => panic!("expected NotifyUserActivation"),
+    }
+}
+
+#[test]
+fn test_integrity_check_failed() {
+    let url = Url::parse("https://cdn.example.net/library.min.js").unwrap();
+    let summary = "expected sha384-oqVuAfXRKap7fdgcCY5uykM6+R9GqQ8K, got sha384-H8BRh8j48O9oYatf".to_owned();
+    match round_trip(ScriptMsg::IntegrityCheckFailed(pipeline_id(), url.clone(), summary.clone())) {
+        ScriptMsg::IntegrityCheckFailed(id, received_url, received_summary) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(received_url, url);
+            assert_eq!(received_summary, summary);
+        }
+        _ => panic!("expected IntegrityCheckFailed"),
+    }
+}
+
+#[test]
+fn test_navigation_blocked() {
+    let url = Url::parse("http://malware.example.org/download").unwrap();
+    let reasons = vec![
+        BlockReason::Malware,
+        BlockReason::Phishing,
+        BlockReason::UnwantedSoftware,
+        BlockReason::EmbedderPolicy("parental controls".to_owned()),
+    ];
+
+    for reason in reasons {
+        match round_trip(ScriptMsg::NavigationBlocked(pipeline_id(), url.clone(), reason.clone())) {
+            ScriptMsg::NavigationBlocked(id, received_url, received_reason) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_url, url);
+                assert_eq!(received_reason, reason);
+            }
+            _ => panic!("expected NavigationBlocked"),
+        }
+    }
+}
+
+#[test]
+fn test_interstitial_decision() {
+    for &proceed in &[true, false] {
+        match round_trip(ScriptMsg::InterstitialDecision(pipeline_id(), proceed)) {
+            ScriptMsg::InterstitialDecision(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, proceed);
+            }
+            _ => panic!("expected InterstitialDecision"),
+        }
+    }
+}
+
+#[test]
+fn test_get_history_state() {
+    let state = HistoryState {
+        length: 5,
+        current_index: 3,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetHistoryState(subframe_pipeline_id(), sender)) {
+        ScriptMsg::GetHistoryState(id, sender) => {
+            assert_eq!(id, subframe_pipeline_id());
+            sender.send(state).unwrap();
+        }
+        _ => panic!("expected GetHistoryState"),
+    }
+    assert_eq!(receiver.recv().unwrap(), state);
+}
+
+#[test]
+fn test_get_navigation_state() {
+    let capability = NavigationCapability {
+        can_go_back: true,
+        can_go_forward: false,
+    };
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::GetNavigationState(pipeline_id(), sender)) {
+        ScriptMsg::GetNavigationState(id, sender) => {
+            assert_eq!(id, pipeline_id());
+            sender.send(capability).unwrap();
+        }
+        _ => panic!("expected GetNavigationState"),
+    }
+    assert_eq!(receiver.recv().unwrap(), capability);
+}
+
+#[test]
+fn test_load_url_reason() {
+    let url = Url::parse("https://example.com/search?q=servo").unwrap();
+    for &reason in &[NavigationReason::LinkClick, NavigationReason::FormSubmit, NavigationReason::Reload] {
+        match round_trip(ScriptMsg::LoadUrl(pipeline_id(), LoadData::new(url.clone(), None, None), reason)) {
+            ScriptMsg::LoadUrl(id, load_data, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(load_data.url, url);
+                assert_eq!(received, reason);
+            }
+            _ => panic!("expected LoadUrl"),
+        }
+    }
+}
+
+#[test]
+fn test_report_page_lifecycle() {
+    let states = [
+        PageLifecycleState::Active,
+        PageLifecycleState::Frozen,
+        PageLifecycleState::Resumed,
+        PageLifecycleState::Terminated,
+    ];
+    for &state in &states {
+        match round_trip(ScriptMsg::ReportPageLifecycle(pipeline_id(), state)) {
+            ScriptMsg::ReportPageLifecycle(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, state);
+            }
+            _ => panic!("expected ReportPageLifecycle"),
+        }
+    }
+}
+
+#[test]
+fn test_set_scroll_restoration() {
+    for &mode in &[ScrollRestorationMode::Auto, ScrollRestorationMode::Manual] {
+        match round_trip(ScriptMsg::SetScrollRestoration(pipeline_id(), mode)) {
+            ScriptMsg::SetScrollRestoration(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, mode);
+            }
+            _ => panic!("expected SetScrollRestoration"),
+        }
+    }
+}
+
+#[test]
+fn test_save_scroll_position() {
+    let point = Point2D::new(0.0, 1250.5);
+    match round_trip(ScriptMsg::SaveScrollPosition(pipeline_id(), LayerId::null(), point)) {
+        ScriptMsg::SaveScrollPosition(id, layer_id, received) => {
+            assert_eq!(id, pipeline_id());
+            assert!(layer_id == LayerId::null());
+            assert_eq!(received, point);
+        }
+        _ => panic!("expected SaveScrollPosition"),
+    }
+}
+
+#[test]
+fn test_same_document_navigation() {
+    let url = Url::parse("https://example.com/page#section").unwrap();
+    let types = [
+        NavigationType::Push,
+        NavigationType::Replace,
+        NavigationType::Traverse,
+        NavigationType::Reload,
+    ];
+    for &navigation_type in &types {
+        match round_trip(ScriptMsg::SameDocumentNavigation(pipeline_id(), url.clone(), navigation_type)) {
+            ScriptMsg::SameDocumentNavigation(id, received_url, received_type) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_url, url);
+                assert_eq!(received_type, navigation_type);
+            }
+            _ => panic!("expected SameDocumentNavigation"),
+        }
+    }
+}
+
+#[test]
+fn test_resolve_navigation_disposition() {
+    let url = Url::parse("https://example.com/installer.exe").unwrap();
+    let dispositions = [
+        NavigationDisposition::Navigate,
+        NavigationDisposition::Download,
+        NavigationDisposition::Ignore,
+    ];
+    for &disposition in &dispositions {
+        match round_trip(ScriptMsg::ResolveNavigationDisposition(pipeline_id(), url.clone(), disposition)) {
+            ScriptMsg::ResolveNavigationDisposition(id, received_url, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_url, url);
+                assert_eq!(received, disposition);
+            }
+            _ => panic!("expected ResolveNavigationDisposition"),
+        }
+    }
+}
+
+#[test]
+fn test_acquire_web_lock() {
+    let grant = WebLockGrant {
+        token: WebLockToken(3),
+    };
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    let msg = ScriptMsg::AcquireWebLock(pipeline_id(), String::from("sync"), WebLockMode::Shared, sender);
+    match round_trip(msg) {
+        ScriptMsg::AcquireWebLock(id, name, mode, sender) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(name, "sync");
+            assert_eq!(mode, WebLockMode::Shared);
+            sender.send(grant.clone()).unwrap();
+        }
+        _ => panic!("expected AcquireWebLock"),
+    }
+    assert_eq!(receiver.recv().unwrap(), grant);
+}
+
+#[test]
+fn test_release_web_lock() {
+    match round_trip(ScriptMsg::ReleaseWebLock(pipeline_id(), WebLockToken(3))) {
+        ScriptMsg::ReleaseWebLock(id, token) => {
+            assert_eq!(id, pipeline_id());
+            assert_eq!(token, WebLockToken(3));
+        }
+        _ => panic!("expected ReleaseWebLock"),
+    }
+}
+
+#[test]
+fn test_open_indexed_db() {
+    let results = vec![
+        Ok(IdbConnectionId(4)),
+        Err(String::from("VersionError: the database is at version 3, above the requested 2.")),
+    ];
+
+    for result in results {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::OpenIndexedDb(pipeline_id(), String::from("notes"), 2, sender)) {
+            ScriptMsg::OpenIndexedDb(id, name, version, sender) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(name, "notes");
+                assert_eq!(version, 2);
+                sender.send(result.clone()).unwrap();
+            }
+            _ => panic!("expected OpenIndexedDb"),
+        }
+        assert_eq!(receiver.recv().unwrap(), result);
+    }
+}
+
+#[test]
+fn test_open_auxiliary_browsing_context() {
+    let url = Url::parse("https://example.com/popup.html").unwrap();
+    let features = WindowFeatures {
+        width: Some(400),
+        height: Some(300),
+        left: None,
+        top: Some(-20),
+        toolbar: false,
+        menubar: false,
+        resizable: true,
+    };
+    let info = AuxiliaryContextInfo {
+        opener: pipeline_id(),
+        url: LoadData::new(url.clone(), None, None),
+        name: String::from("help"),
+        features: features,
+        noopener: true,
+    };
+
+    let (sender, receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::OpenAuxiliaryBrowsingContext(info, sender)) {
+        ScriptMsg::OpenAuxiliaryBrowsingContext(info, sender) => {
+            assert_eq!(info.opener, pipeline_id());
+            assert_eq!(info.url.url, url);
+            assert_eq!(info.name, "help");
+            assert_eq!(info.features, features);
+            assert!(info.noopener);
+            sender.send(subframe_pipeline_id()).unwrap();
+        }
+        _ => panic!("expected OpenAuxiliaryBrowsingContext"),
+    }
+    assert_eq!(receiver.recv().unwrap(), subframe_pipeline_id());
+}
+
+#[test]
+fn test_window_features() {
+    // "width=400,height=300,noopener"
+    let features = WindowFeatures {
+        width: Some(400),
+        height: Some(300),
+        ..WindowFeatures::default()
+    };
+    let info = AuxiliaryContextInfo {
+        opener: pipeline_id(),
+        url: LoadData::new(Url::parse("https://example.com/").unwrap(), None, None),
+        name: String::new(),
+        features: features,
+        noopener: true,
+    };
+
+    let (sender, _receiver) = ipc::channel().unwrap();
+    match round_trip(ScriptMsg::OpenAuxiliaryBrowsingContext(info, sender)) {
+        ScriptMsg::OpenAuxiliaryBrowsingContext(info, _) => {
+            assert_eq!(info.features, features);
+            assert_eq!(info.features.left, None);
+            assert_eq!(info.features.top, None);
+            assert!(!info.features.toolbar && !info.features.menubar && !info.features.resizable);
+            assert!(info.noopener);
+        }
+        _ => panic!("expected OpenAuxiliaryBrowsingContext"),
+    }
+}
+
+#[test]
+fn test_find_named_browsing_context() {
+    for found in vec![Some(subframe_pipeline_id()), None] {
+        let (sender, receiver) = ipc::channel().unwrap();
+        match round_trip(ScriptMsg::FindNamedBrowsingContext(pipeline_id(), String::from("results"), sender)) {
+            ScriptMsg::FindNamedBrowsingContext(id, name, sender) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(name, "results");
+                sender.send(found).unwrap();
+            }
+            _ => panic!("expected FindNamedBrowsingContext"),
+        }
+        assert_eq!(receiver.recv().unwrap(), found);
+    }
+}
+
+#[test]
+fn test_queue_report() {
+    let reports = vec![
+        ReportBody::Deprecation {
+            id: String::from("webkit-prefixed-animation"),
+            message: String::from("-webkit-animation is deprecated; use animation instead."),
+            source: Some(SourceLocation {
+                url: Url::parse("https://example.com/app.js").unwrap(),
+                line: 12,
+                column: 5,
+            }),
+            anticipated_removal: None,
+        },
+        ReportBody::NetworkError {
+            url: Url::parse("https://cdn.example.com/font.woff2").unwrap(),
+            status: Some(503),
+            error_type: String::from("http.error"),
+        },
+    ];
+
+    for report in reports {
+        match round_trip(ScriptMsg::QueueReport(pipeline_id(), report.clone())) {
+            ScriptMsg::QueueReport(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, report);
+            }
+            _ => panic!("expected QueueReport"),
+        }
+    }
+}
+
+#[test]
+fn test_intervention_report() {
+    let interventions = vec![
+        (InterventionKind::BlockedAutoplay, "Autoplay was blocked by the autoplay policy."),
+        (InterventionKind::ThrottledTimer, "Timers of a hidden document fire at most once a second."),
+    ];
+
+    for (kind, message) in interventions {
+        match round_trip(ScriptMsg::InterventionReport(pipeline_id(), kind, String::from(message))) {
+            ScriptMsg::InterventionReport(id, received_kind, received_message) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received_kind, kind);
+                assert_eq!(received_message, message);
+            }
+            _ => panic!("expected InterventionReport"),
+        }
+    }
+}
+
+#[test]
+fn test_deprecation_report() {
+    let infos = vec![
+        DeprecationInfo {
+            feature_id: String::from("legacy-event-capture"),
+            message: String::from("document.captureEvents() does nothing, and can be removed."),
+            source: None,
+            anticipated_removal: None,
+        },
+        DeprecationInfo {
+            feature_id: String::from("sync-xhr"),
+            message: String::from("Synchronous XMLHttpRequest on the main thread is deprecated."),
+            source: Some(SourceLocation {
+                url: Url::parse("https://example.com/app.js").unwrap(),
+                line: 40,
+                column: 9,
+            }),
+            anticipated_removal: Some(String::from("2017-01-01")),
+        },
+    ];
+
+    for info in infos {
+        match round_trip(ScriptMsg::DeprecationReport(pipeline_id(), info.clone())) {
+            ScriptMsg::DeprecationReport(id, received) => {
+                assert_eq!(id, pipeline_id());
+                assert_eq!(received, info);
+            }
+            _ => panic!("expected DeprecationReport"),
+        }
+    }
+}